@@ -0,0 +1,40 @@
+use std::{sync::mpsc::channel, thread::spawn};
+
+use windows_hotkeys::{
+    keys::{ModKey, VKey},
+    HotkeyManager, HotkeyManagerImpl,
+};
+
+fn main() {
+    // Channel that is used to send the callbacks to the "UI" thread. In a real application this
+    // would be the dispatch mechanism of the GUI framework, for example posting to the main thread
+    let (snd, rec) = channel::<Box<dyn FnOnce() + Send>>();
+
+    let mut hkm = HotkeyManager::new();
+
+    // Register a system-wide hotkey with the main key `A` and the modifier key `ALT`
+    hkm.register(VKey::A, &[ModKey::Alt], || {
+        println!(
+            "Hotkey ALT + A was pressed. Executed on thread {:?}",
+            std::thread::current().id()
+        );
+    })
+    .unwrap();
+
+    // Hand all matched callbacks to the channel instead of executing them on the hotkey thread
+    hkm.set_dispatcher(move |callback| {
+        snd.send(callback).unwrap();
+    });
+
+    // Run the hotkey event loop in the background
+    spawn(move || {
+        hkm.event_loop();
+    });
+
+    println!("UI thread is {:?}", std::thread::current().id());
+
+    // Execute the dispatched callbacks on this thread
+    for callback in rec {
+        callback();
+    }
+}
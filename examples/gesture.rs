@@ -0,0 +1,24 @@
+use windows_hotkeys::{
+    gesture::Stroke, mouse::MouseButton, singlethreaded::HotkeyManager, HotkeyManagerImpl,
+};
+
+fn main() {
+    let mut hkm = HotkeyManager::new();
+
+    // Hold the right mouse button and draw an L-shape. A right click without moving the cursor
+    // still opens the context menu as usual
+    hkm.register_gesture(
+        MouseButton::Right,
+        &[],
+        &[Stroke::Down, Stroke::Right],
+        || println!("Gesture Down, Right was drawn"),
+    )
+    .unwrap();
+
+    hkm.register_gesture(MouseButton::Right, &[], &[Stroke::Left], || {
+        println!("Gesture Left was drawn");
+    })
+    .unwrap();
+
+    hkm.event_loop();
+}
@@ -0,0 +1,20 @@
+use windows_hotkeys::hook::KeyboardListener;
+
+fn main() {
+    // Install a low-level keyboard hook that reports all keyboard events in the system
+    let listener = KeyboardListener::new().unwrap();
+
+    // Print the full low-level information of every received event
+    for event in listener.iter() {
+        println!(
+            "{} {} scan_code: 0x{:x} extended: {} injected: {} time: {} extra_info: {}",
+            if event.is_keydown() { "DOWN" } else { "UP  " },
+            event.vkey,
+            event.scan_code,
+            event.is_extended(),
+            event.is_injected(),
+            event.time,
+            event.extra_info,
+        );
+    }
+}
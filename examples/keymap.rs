@@ -0,0 +1,33 @@
+use windows_hotkeys::{keymap::Keymap, singlethreaded::HotkeyManager, HotkeyManagerImpl};
+
+/// The bindings would usually be read from a file with `Keymap::load`
+const KEYMAP: &str = r#"
+"ctrl+alt+k" = "greet"
+"win+shift+oem_3" = "greet"
+"ctrl+alt+nope" = "broken"
+"ctrl+alt+q" = "quit"
+"#;
+
+fn main() {
+    let keymap = Keymap::from_toml(KEYMAP).unwrap();
+
+    // All bindings execute the same dispatcher, which decides what to do based on the action name.
+    // Returning false stops the event loop
+    let mut hkm = HotkeyManager::new();
+    let report = keymap.register(&mut hkm, |action| match action {
+        "greet" => {
+            println!("Hello!");
+            true
+        }
+        "quit" => false,
+        action => {
+            println!("Unknown action {}", action);
+            true
+        }
+    });
+
+    // The invalid key name is reported with its line in the keymap
+    print!("{}", report);
+
+    while let Some(true) = hkm.handle_hotkey() {}
+}
@@ -0,0 +1,27 @@
+use std::time::Duration;
+
+use windows_hotkeys::{
+    combo::HotkeyCombo,
+    keys::{ModKey, VKey},
+    singlethreaded::HotkeyManager,
+    HotkeyManagerImpl,
+};
+
+fn main() {
+    let mut hkm = HotkeyManager::new();
+
+    // CTRL + SPACE enters the leader mode, after which the action keys can be pressed for 1.5s
+    hkm.set_leader(
+        HotkeyCombo::new(VKey::Space, &[ModKey::Ctrl]),
+        Duration::from_millis(1500),
+    )
+    .unwrap();
+
+    // The action keys are only registered while the leader mode is active
+    hkm.register_leader_action(VKey::T, &[], || println!("Leader action T: open terminal"))
+        .unwrap();
+    hkm.register_leader_action(VKey::B, &[], || println!("Leader action B: open browser"))
+        .unwrap();
+
+    hkm.event_loop();
+}
@@ -0,0 +1,37 @@
+use windows_hotkeys::{
+    keys::ModKey,
+    mouse::{MouseButton, MouseWheel},
+    singlethreaded::HotkeyManager,
+    HotkeyManagerImpl,
+};
+
+fn main() {
+    let mut hkm = HotkeyManager::new();
+
+    // The middle click with CTRL held is blocked, so the application under the cursor doesn't
+    // receive it
+    hkm.register_mouse(MouseButton::Middle, &[ModKey::Ctrl], true, || {
+        println!("Hotkey CTRL + MiddleClick was pressed");
+    })
+    .unwrap();
+
+    // With `block = false`, the context menu still opens as usual
+    hkm.register_mouse(MouseButton::Right, &[ModKey::Win], false, || {
+        println!("Hotkey WIN + RightClick was pressed");
+    })
+    .unwrap();
+
+    // The side buttons can also be used without any modifiers
+    hkm.register_mouse(MouseButton::XButton1, &[], true, || {
+        println!("Side button 1 was pressed, the browser didn't navigate back");
+    })
+    .unwrap();
+
+    // Every scroll step with ALT held triggers the hotkey once
+    hkm.register_wheel(MouseWheel::Up, &[ModKey::Alt], true, || {
+        println!("Hotkey ALT + WheelUp was scrolled");
+    })
+    .unwrap();
+
+    hkm.event_loop();
+}
@@ -0,0 +1,22 @@
+use windows_hotkeys::mouse::MouseListener;
+
+fn main() {
+    // Install a low-level mouse hook that reports all mouse events in the system. Movements are
+    // throttled to the default interval
+    let listener = MouseListener::new().unwrap();
+
+    for event in listener.iter() {
+        if let Some(button) = event.button() {
+            let state = if event.is_button_down() {
+                "DOWN"
+            } else {
+                "UP  "
+            };
+            println!("{} {:?} at ({}, {})", state, button, event.x, event.y);
+        } else if let Some((direction, delta)) = event.wheel() {
+            println!("WHEEL {:?} delta: {}", direction, delta);
+        } else if event.is_move() {
+            println!("MOVE ({}, {})", event.x, event.y);
+        }
+    }
+}
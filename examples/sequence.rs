@@ -0,0 +1,37 @@
+use std::time::Duration;
+
+use windows_hotkeys::{
+    keys::{ModKey, VKey},
+    sequence::SequenceStep,
+    singlethreaded::HotkeyManager,
+    HotkeyManagerImpl,
+};
+
+fn main() {
+    let mut hkm = HotkeyManager::new();
+
+    // Allow up to two seconds between the steps of a sequence
+    hkm.set_sequence_timeout(Duration::from_secs(2));
+
+    // Both sequences start with CTRL + K. Only CTRL + K is registered until it is pressed, so
+    // CTRL + C and CTRL + U keep working normally in other applications
+    hkm.register_sequence(
+        &[
+            SequenceStep::new(VKey::K, &[ModKey::Ctrl]),
+            SequenceStep::new(VKey::C, &[ModKey::Ctrl]),
+        ],
+        || println!("Sequence CTRL + K, CTRL + C was pressed"),
+    )
+    .unwrap();
+
+    hkm.register_sequence(
+        &[
+            SequenceStep::new(VKey::K, &[ModKey::Ctrl]),
+            SequenceStep::new(VKey::U, &[ModKey::Ctrl]),
+        ],
+        || println!("Sequence CTRL + K, CTRL + U was pressed"),
+    )
+    .unwrap();
+
+    hkm.event_loop();
+}
@@ -75,5 +75,14 @@ fn main() {
     })
     .unwrap();
 
+    // Multiple ModKeys can be combined into a `Modifiers` set
+    let mods = ModKey::Ctrl | ModKey::Shift;
+    assert!(mods.contains(ModKey::Ctrl));
+
+    hkm.register(VKey::B, &mods.to_vec(), || {
+        println!("Hotkey CTRL + SHIFT + B pressed");
+    })
+    .unwrap();
+
     hkm.event_loop();
 }
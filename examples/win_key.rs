@@ -0,0 +1,24 @@
+use windows_hotkeys::{
+    keys::{ModKey, VKey},
+    singlethreaded::HotkeyManager,
+    HotkeyManagerImpl,
+};
+
+fn main() {
+    let mut hkm = HotkeyManager::new();
+
+    // WIN + E is reserved by Explorer, so it can't be registered normally. Registering it through
+    // the keyboard hook with `block = true` replaces the default action
+    hkm.register_hooked(VKey::E, &[ModKey::Win], true, || {
+        println!("Hotkey WIN + E was pressed, Explorer was not opened");
+    })
+    .unwrap();
+
+    // With `block = false`, the callback is executed and Explorer still handles WIN + R
+    hkm.register_hooked(VKey::R, &[ModKey::Win], false, || {
+        println!("Hotkey WIN + R was pressed, the run dialog opens as usual");
+    })
+    .unwrap();
+
+    hkm.event_loop();
+}
@@ -0,0 +1,145 @@
+use std::sync::{Mutex, MutexGuard, Once, OnceLock, TryLockError};
+
+use winapi::ctypes::c_int;
+use winapi::shared::windef::{HHOOK, HWND};
+use winapi::um::errhandlingapi::{SetUnhandledExceptionFilter, PTOP_LEVEL_EXCEPTION_FILTER};
+use winapi::um::winnt::{EXCEPTION_POINTERS, LONG};
+use winapi::um::winuser::{RegisterHotKey, UnhookWindowsHookEx, UnregisterHotKey};
+use winapi::vc::excpt::EXCEPTION_CONTINUE_SEARCH;
+
+use crate::error::HkError;
+
+/// The installed low-level hooks. The handles are stored as `usize`, since raw pointers are not
+/// `Send`
+///
+static HOOKS: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+/// The registered hotkeys as `(HWND, id)` pairs
+///
+static HOTKEYS: Mutex<Vec<(usize, c_int)>> = Mutex::new(Vec::new());
+
+/// The exception filter that was set before this crate installed its own, so it can be chained
+///
+static PREVIOUS_FILTER: OnceLock<PTOP_LEVEL_EXCEPTION_FILTER> = OnceLock::new();
+
+static INSTALL: Once = Once::new();
+
+extern "C" {
+    /// Provided by the C runtime that every rust program on windows links against
+    fn atexit(callback: extern "C" fn()) -> c_int;
+}
+
+/// Unhook all low-level hooks and unregister all hotkeys that are still installed by this crate.
+///
+/// Hooks and hotkeys are normally released when their `HotkeyManager` or hook is dropped. If the
+/// process crashes instead, windows only cleans them up once the process is gone, and a hanging
+/// process can keep a low-level hook installed that delays every key press in the system. So
+/// this is called automatically from an unhandled exception filter and an `atexit` handler, which
+/// are set up the first time a hook or hotkey is installed. Applications with their own crash
+/// handling, like a panic hook with `panic = "abort"`, can call it themselves.
+///
+/// Hotkeys can only be unregistered from the thread that registered them, so the ones
+/// registered on other threads are left to windows. The `HotkeyManager`s and hooks that are still
+/// alive stop receiving events after this.
+///
+/// # Windows API Functions used
+/// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-unhookwindowshookex>
+/// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-unregisterhotkey>
+///
+pub fn release_all() {
+    // Never block here, the lock might be held by the thread that crashed
+    if let Some(mut hooks) = try_lock(&HOOKS) {
+        for hook in hooks.drain(..) {
+            unsafe { UnhookWindowsHookEx(hook as HHOOK) };
+        }
+    }
+    if let Some(mut hotkeys) = try_lock(&HOTKEYS) {
+        for (hwnd, id) in hotkeys.drain(..) {
+            unsafe { UnregisterHotKey(hwnd as HWND, id) };
+        }
+    }
+}
+
+/// Remember a low-level hook, so it is removed by `release_all`
+///
+pub(crate) fn track_hook(hook: HHOOK) {
+    install();
+    lock(&HOOKS).push(hook as usize);
+}
+
+/// Forget a low-level hook right before it is removed
+///
+pub(crate) fn untrack_hook(hook: HHOOK) {
+    lock(&HOOKS).retain(|&h| h != hook as usize);
+}
+
+/// Register a hotkey with windows and remember it, so it is unregistered by `release_all`.
+///
+/// # Windows API Functions used
+/// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+///
+pub(crate) fn register_hotkey(
+    hwnd: HWND,
+    id: c_int,
+    modifiers: u32,
+    vk: u32,
+) -> Result<(), HkError> {
+    install();
+    if unsafe { RegisterHotKey(hwnd, id, modifiers, vk) } == 0 {
+        return Err(HkError::last_registration_error());
+    }
+    lock(&HOTKEYS).push((hwnd as usize, id));
+    Ok(())
+}
+
+/// Unregister a hotkey that was registered with `register_hotkey`. Returns false if the
+/// unregistration failed.
+///
+/// # Windows API Functions used
+/// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-unregisterhotkey>
+///
+pub(crate) fn unregister_hotkey(hwnd: HWND, id: c_int) -> bool {
+    lock(&HOTKEYS).retain(|&entry| entry != (hwnd as usize, id));
+    unsafe { UnregisterHotKey(hwnd, id) != 0 }
+}
+
+/// Set up the exception filter and the `atexit` handler, once per process
+///
+/// # Windows API Functions used
+/// - <https://docs.microsoft.com/en-us/windows/win32/api/errhandlingapi/nf-errhandlingapi-setunhandledexceptionfilter>
+///
+fn install() {
+    INSTALL.call_once(|| unsafe {
+        let previous = SetUnhandledExceptionFilter(Some(exception_filter));
+        let _ = PREVIOUS_FILTER.set(previous);
+        atexit(exit_handler);
+    });
+}
+
+unsafe extern "system" fn exception_filter(info: *mut EXCEPTION_POINTERS) -> LONG {
+    release_all();
+    match PREVIOUS_FILTER.get() {
+        Some(Some(previous)) => previous(info),
+        _ => EXCEPTION_CONTINUE_SEARCH,
+    }
+}
+
+extern "C" fn exit_handler() {
+    release_all();
+}
+
+/// Lock the mutex, ignoring poisoning since the tracked handles stay valid
+///
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Same as `lock`, but returns `None` instead of blocking if the mutex is locked
+///
+fn try_lock<T>(mutex: &Mutex<T>) -> Option<MutexGuard<'_, T>> {
+    match mutex.try_lock() {
+        Ok(guard) => Some(guard),
+        Err(TryLockError::Poisoned(e)) => Some(e.into_inner()),
+        Err(TryLockError::WouldBlock) => None,
+    }
+}
@@ -0,0 +1,176 @@
+use std::{fmt, str::FromStr};
+
+use crate::{
+    error::HkError,
+    keys::{ModKey, Modifiers, VKey},
+};
+
+/// The main key and the modifier code without `ModKey::NoRepeat`, which identifies a key
+/// combination regardless of the order of the modifiers
+///
+pub(crate) type ComboCode = (VKey, u32);
+
+/// Offset basis of the 64-bit FNV-1a hash that is used by `HotkeyCombo::stable_hash`
+///
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// Prime of the 64-bit FNV-1a hash that is used by `HotkeyCombo::stable_hash`
+///
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// A key combination consisting of a main key, its modifier keys and optionally extra keys that
+/// need to be held as well, for example `CTRL + SHIFT + K`.
+///
+/// The combination can be passed to `HotkeyManagerImpl::register_combo`, parsed from a string
+/// and formatted as a string, so it can be stored and compared as one value. With the `serde`
+/// feature, it is serialized as that string as well.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HotkeyCombo {
+    /// The main key
+    pub key: VKey,
+    /// The modifier keys
+    pub modifiers: Vec<ModKey>,
+    /// The additional keys that need to be pressed for the callback to be executed
+    pub extra_keys: Vec<VKey>,
+}
+
+impl HotkeyCombo {
+    /// Create a new key combination from the main key and the modifier keys.
+    ///
+    pub fn new(key: VKey, modifiers: &[ModKey]) -> Self {
+        Self {
+            key,
+            modifiers: modifiers.to_vec(),
+            extra_keys: Vec::new(),
+        }
+    }
+
+    /// Set the extra keys of the key combination.
+    ///
+    pub fn with_extra_keys(mut self, extra_keys: &[VKey]) -> Self {
+        self.extra_keys = extra_keys.to_vec();
+        self
+    }
+
+    /// Parse a key combination from a string like `"CTRL + ALT + M"`. The key names are separated
+    /// by `+` and surrounding whitespace is ignored. The leading names are the modifiers and are
+    /// parsed with `ModKey::from_keyname`. The first name that is not a modifier is the main key,
+    /// all names after it are extra keys. Those are parsed with `VKey::from_keyname`.
+    ///
+    /// If a name is invalid, the returned `HkError::InvalidKey` contains the name as it was
+    /// written and its byte position within the string, so the error can point at it. If the
+    /// string only contains modifiers, `HkError::MissingMainKey` is returned.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use windows_hotkeys::{combo::HotkeyCombo, keys::{ModKey, VKey}};
+    ///
+    /// let combo: HotkeyCombo = "win+shift+oem_3".parse().unwrap();
+    /// assert_eq!(combo.key, VKey::Oem3);
+    /// assert_eq!(combo.modifiers, vec![ModKey::Win, ModKey::Shift]);
+    ///
+    /// let combo = HotkeyCombo::parse("CTRL + ALT + M + LEFT").unwrap();
+    /// assert_eq!(combo.key, VKey::M);
+    /// assert_eq!(combo.extra_keys, vec![VKey::Left]);
+    /// ```
+    ///
+    pub fn parse(combo: &str) -> Result<Self, HkError> {
+        let mut names = Vec::new();
+        let mut offset = 0;
+        for part in combo.split('+') {
+            let trimmed = part.trim_start();
+            let start = offset + part.len() - trimmed.len();
+            names.push((start, trimmed.trim_end()));
+            offset += part.len() + 1;
+        }
+
+        let mut names = names.into_iter();
+        let mut modifiers = Vec::new();
+        let key = loop {
+            // Point out the actual mistake for strings like "CTRL + ALT"
+            let Some((pos, name)) = names.next() else {
+                return Err(HkError::MissingMainKey(combo.to_string()));
+            };
+            match ModKey::from_keyname(name) {
+                Ok(modifier) => modifiers.push(modifier),
+                Err(_) => break VKey::from_keyname(name).map_err(|err| err.at_position(pos))?,
+            }
+        };
+        let extra_keys = names
+            .map(|(pos, name)| VKey::from_keyname(name).map_err(|err| err.at_position(pos)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            key,
+            modifiers,
+            extra_keys,
+        })
+    }
+
+    /// Get a 64-bit hash of the key combination that is stable across program runs, platforms
+    /// and versions of this crate, unlike the `Hash` implementation. This allows using the hash
+    /// as a key for data that is persisted per binding, and comparing or deduplicating large
+    /// configs without comparing the combos one by one.
+    ///
+    /// Combos that are the same key combination have the same hash. The order and duplicates of
+    /// the modifiers and extra keys don't matter and `ModKey::NoRepeat` is ignored, so
+    /// `CTRL + ALT + K` and `ALT + CTRL + K` have the same hash. Different combos can have the
+    /// same hash in rare cases, so equal hashes should be confirmed with `==` where that matters.
+    ///
+    pub fn stable_hash(&self) -> u64 {
+        let (key, modifiers) = self.code();
+
+        let mut extra_keys: Vec<_> = self.extra_keys.iter().map(VKey::to_vk_code).collect();
+        extra_keys.sort_unstable();
+        extra_keys.dedup();
+
+        // FNV-1a over the virtual key codes and the modifier code in little endian byte order
+        let words = [key.to_vk_code() as u32, modifiers]
+            .into_iter()
+            .chain(extra_keys.into_iter().map(|code| code as u32));
+        words
+            .flat_map(u32::to_le_bytes)
+            .fold(FNV_OFFSET_BASIS, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+            })
+    }
+
+    pub(crate) fn code(&self) -> ComboCode {
+        (
+            self.key,
+            ModKey::combine(&self.modifiers) & !ModKey::NoRepeat.to_mod_code(),
+        )
+    }
+}
+
+impl FromStr for HotkeyCombo {
+    type Err = HkError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for HotkeyCombo {
+    /// Format the key combination in its canonical form, for example `CONTROL + ALT + K`. The
+    /// modifiers are always written in the order `CTRL`, `ALT`, `SHIFT`, `WIN`, regardless of the
+    /// order they were given in, and duplicates are left out. `ModKey::NoRepeat` is left out as
+    /// well, since it doesn't change the key combination. The extra keys follow the main key.
+    ///
+    /// The result can be parsed again with `HotkeyCombo::parse`.
+    ///
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut modifiers = Modifiers::from(&self.modifiers[..]);
+        modifiers.remove(ModKey::NoRepeat);
+
+        for modifier in modifiers {
+            write!(f, "{} + ", modifier)?;
+        }
+        write!(f, "{}", self.key)?;
+        for extra_key in &self.extra_keys {
+            write!(f, " + {}", extra_key)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,214 @@
+use std::{
+    os::windows::process::CommandExt,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+use winapi::um::winbase::{CREATE_NEW_CONSOLE, CREATE_NEW_PROCESS_GROUP, CREATE_NO_WINDOW};
+
+use crate::error::HkError;
+
+/// The shell that executes the command line of a `ShellCommand`
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Shell {
+    /// `cmd.exe /C`, the command line is passed on exactly as it is written
+    #[default]
+    Cmd,
+    /// Windows PowerShell, `powershell.exe -NoProfile -Command`
+    PowerShell,
+    /// PowerShell 7 and newer, `pwsh.exe -NoProfile -Command`
+    Pwsh,
+}
+
+impl Shell {
+    /// Get the shell for a name like `cmd`, `powershell` or `pwsh`, as it is used in the `.shell`
+    /// directive of hotkey scripts. The name is case insensitive and may end with `.exe`.
+    ///
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        Some(match name.trim_end_matches(".exe") {
+            "cmd" => Shell::Cmd,
+            "powershell" => Shell::PowerShell,
+            "pwsh" => Shell::Pwsh,
+            _ => return None,
+        })
+    }
+
+    fn command(&self, command_line: &str) -> Command {
+        match self {
+            Shell::Cmd => {
+                // cmd doesn't follow the usual quoting rules, so the command line must not be
+                // escaped as an argument
+                let mut cmd = Command::new("cmd.exe");
+                cmd.arg("/C").raw_arg(command_line);
+                cmd
+            }
+            Shell::PowerShell | Shell::Pwsh => {
+                let program = match self {
+                    Shell::PowerShell => "powershell.exe",
+                    _ => "pwsh.exe",
+                };
+                let mut cmd = Command::new(program);
+                cmd.args(["-NoProfile", "-Command", command_line]);
+                cmd
+            }
+        }
+    }
+}
+
+/// A command line that is executed by a shell when a hotkey is triggered, like the bindings of
+/// hotkey daemons. The command is configured like a `std::process::Command` and turned into a
+/// hotkey callback with `callback`.
+///
+/// By default, the command is started with `CREATE_NO_WINDOW`, so console programs don't flash a
+/// console window, and detached, so the callback returns right away and the process keeps running
+/// on its own.
+///
+/// # Example
+/// ```no_run
+/// use windows_hotkeys::{
+///     command::{Shell, ShellCommand},
+///     keys::{ModKey, VKey},
+///     singlethreaded::HotkeyManager,
+///     HotkeyManagerImpl,
+/// };
+///
+/// let mut hkm = HotkeyManager::new();
+/// hkm.register(
+///     VKey::Return,
+///     ModKey::Alt | ModKey::Shift,
+///     ShellCommand::new("wt").callback(),
+/// )
+/// .unwrap();
+/// hkm.register(
+///     VKey::H,
+///     ModKey::Alt,
+///     ShellCommand::new("Get-Date | Out-File dates.txt -Append")
+///         .shell(Shell::Pwsh)
+///         .current_dir("C:\\Temp")
+///         .callback(),
+/// )
+/// .unwrap();
+///
+/// while let Some(result) = hkm.handle_hotkey() {
+///     if let Err(err) = result {
+///         eprintln!("{}", err);
+///     }
+/// }
+/// ```
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ShellCommand {
+    command_line: String,
+    shell: Shell,
+    current_dir: Option<PathBuf>,
+    env: Vec<(String, String)>,
+    detach: bool,
+    show_console: bool,
+}
+
+impl ShellCommand {
+    /// Create a new command that runs the command line with `cmd.exe`
+    ///
+    pub fn new(command_line: impl Into<String>) -> Self {
+        Self {
+            command_line: command_line.into(),
+            shell: Shell::Cmd,
+            current_dir: None,
+            env: Vec::new(),
+            detach: true,
+            show_console: false,
+        }
+    }
+
+    /// Set the shell that executes the command line
+    ///
+    pub fn shell(mut self, shell: Shell) -> Self {
+        self.shell = shell;
+        self
+    }
+
+    /// Set the working directory of the command. By default, it inherits the working directory of
+    /// the current process.
+    ///
+    pub fn current_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.current_dir = Some(dir.into());
+        self
+    }
+
+    /// Set an environment variable for the command, in addition to the inherited environment
+    ///
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    /// Set if the command runs detached, which is the default. A detached command is started in
+    /// its own process group without standard streams, so it isn't affected by `CTRL + C` in the
+    /// console of the current process and keeps running after it exits.
+    ///
+    /// If the command is not detached, running it waits until it has finished and fails if it
+    /// exits with an error code. This blocks the event loop while the command is running.
+    ///
+    pub fn detach(mut self, detach: bool) -> Self {
+        self.detach = detach;
+        self
+    }
+
+    /// Set if the command gets a visible console window. By default, the command is started with
+    /// `CREATE_NO_WINDOW`, which hides the console of console programs. Programs with their own
+    /// windows are shown either way.
+    ///
+    pub fn show_console(mut self, show: bool) -> Self {
+        self.show_console = show;
+        self
+    }
+
+    /// Start the command. See `detach` for when this returns.
+    ///
+    /// # Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-createprocessw>
+    ///
+    pub fn run(&self) -> Result<(), HkError> {
+        let mut cmd = self.shell.command(&self.command_line);
+
+        if let Some(dir) = &self.current_dir {
+            cmd.current_dir(dir);
+        }
+        cmd.envs(self.env.iter().map(|(key, value)| (key, value)));
+
+        let mut flags = match self.show_console {
+            true => CREATE_NEW_CONSOLE,
+            false => CREATE_NO_WINDOW,
+        };
+        if self.detach {
+            flags |= CREATE_NEW_PROCESS_GROUP;
+            cmd.stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null());
+        }
+        cmd.creation_flags(flags);
+
+        let failed =
+            |reason: String| HkError::CommandFailed(format!("`{}`: {}", self.command_line, reason));
+
+        let mut child = cmd.spawn().map_err(|err| failed(err.to_string()))?;
+        if self.detach {
+            return Ok(());
+        }
+
+        let status = child.wait().map_err(|err| failed(err.to_string()))?;
+        match status.success() {
+            true => Ok(()),
+            false => Err(failed(status.to_string())),
+        }
+    }
+
+    /// Turn the command into a hotkey callback that runs it every time the hotkey is triggered.
+    /// The result of `run` is the return value of the callback.
+    ///
+    pub fn callback(self) -> impl Fn() -> Result<(), HkError> + Send + 'static {
+        move || self.run()
+    }
+}
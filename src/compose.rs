@@ -0,0 +1,166 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    error::HkError,
+    hook::{HookAction, KeyboardHook, KeyboardHookEvent},
+    keys::{ModKey, VKey},
+    send::send_char,
+};
+
+/// Table of key sequences and the characters that they are replaced with when composing.
+///
+#[derive(Debug, Clone, Default)]
+pub struct ComposeTable {
+    sequences: HashMap<Vec<VKey>, char>,
+}
+
+impl ComposeTable {
+    /// Create an empty `ComposeTable`
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a key sequence that is replaced by the given character. An existing entry for the same
+    /// sequence is overwritten.
+    ///
+    pub fn insert(&mut self, sequence: &[VKey], ch: char) -> &mut Self {
+        self.sequences.insert(sequence.to_vec(), ch);
+        self
+    }
+
+    /// Get the character for a complete key sequence
+    ///
+    pub fn get(&self, sequence: &[VKey]) -> Option<char> {
+        self.sequences.get(sequence).copied()
+    }
+
+    /// Check if the given sequence is the start of at least one longer sequence in the table
+    ///
+    fn is_prefix(&self, prefix: &[VKey]) -> bool {
+        self.sequences
+            .keys()
+            .any(|seq| seq.len() > prefix.len() && seq.starts_with(prefix))
+    }
+}
+
+/// State of the compose key state machine
+///
+enum ComposeState {
+    /// Waiting for the compose key to be tapped
+    Idle,
+    /// The compose key is held down
+    Pressed,
+    /// The compose key was tapped and the keys of the sequence are being recorded
+    Composing(Vec<VKey>),
+}
+
+/// A compose key similar to the X11 compose key. After tapping the compose key, the following
+/// keys are swallowed until they form a sequence from the `ComposeTable`, which is then replaced
+/// by the mapped unicode character.
+///
+/// While composing, modifier keys are ignored and passed on as usual. `Escape` or any sequence
+/// that isn't in the table cancels composing. Holding the compose key while pressing another key
+/// doesn't count as a tap.
+///
+/// The compose key is removed when the `ComposeKey` is dropped.
+///
+/// # Example
+/// ```no_run
+/// use windows_hotkeys::compose::{ComposeKey, ComposeTable};
+/// use windows_hotkeys::keys::VKey;
+///
+/// let mut table = ComposeTable::new();
+/// table.insert(&[VKey::O, VKey::E], 'œ');
+/// table.insert(&[VKey::A, VKey::E], 'æ');
+///
+/// let _compose = ComposeKey::new(VKey::RMenu, table).unwrap();
+/// ```
+///
+pub struct ComposeKey {
+    _hook: KeyboardHook,
+}
+
+impl ComposeKey {
+    /// Install a compose key using the given key and table of sequences.
+    ///
+    pub fn new(key: VKey, table: ComposeTable) -> Result<Self, HkError> {
+        let mut composer = Composer {
+            key,
+            table,
+            state: ComposeState::Idle,
+            swallowed: HashSet::new(),
+        };
+
+        let hook = KeyboardHook::new(move |event| composer.handle(event))?;
+
+        Ok(Self { _hook: hook })
+    }
+}
+
+/// The compose state machine that is running inside of the keyboard hook
+///
+struct Composer {
+    key: VKey,
+    table: ComposeTable,
+    state: ComposeState,
+    /// Keys where the key press was blocked, so the matching key release must be blocked as well
+    swallowed: HashSet<VKey>,
+}
+
+impl Composer {
+    fn handle(&mut self, event: &KeyboardHookEvent) -> HookAction {
+        // Don't interfere with injected input, this includes the composed characters
+        if event.is_injected() {
+            return HookAction::Pass;
+        }
+
+        let vk = event.vkey;
+
+        if event.is_keyup() {
+            if vk == self.key && matches!(self.state, ComposeState::Pressed) {
+                self.state = ComposeState::Composing(Vec::new());
+            }
+            return if self.swallowed.remove(&vk) {
+                HookAction::Block
+            } else {
+                HookAction::Pass
+            };
+        }
+
+        match &mut self.state {
+            ComposeState::Idle if vk == self.key => {
+                self.state = ComposeState::Pressed;
+            }
+            ComposeState::Idle => return HookAction::Pass,
+            // Automatic key repeat of the held compose key
+            ComposeState::Pressed if vk == self.key => (),
+            // The compose key is used together with another key, so it isn't a tap
+            ComposeState::Pressed => {
+                self.state = ComposeState::Idle;
+                return HookAction::Pass;
+            }
+            ComposeState::Composing(_) if TryInto::<ModKey>::try_into(vk).is_ok() => {
+                return HookAction::Pass;
+            }
+            ComposeState::Composing(_) if vk == VKey::Escape => {
+                self.state = ComposeState::Idle;
+            }
+            ComposeState::Composing(sequence) => {
+                sequence.push(vk);
+
+                if let Some(ch) = self.table.get(sequence) {
+                    // Don't send input from within the hook procedure, since the injected events
+                    // need to pass through this hook as well
+                    std::thread::spawn(move || send_char(ch));
+                    self.state = ComposeState::Idle;
+                } else if !self.table.is_prefix(sequence) {
+                    self.state = ComposeState::Idle;
+                }
+            }
+        }
+
+        self.swallowed.insert(vk);
+        HookAction::Block
+    }
+}
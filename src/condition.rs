@@ -0,0 +1,375 @@
+use winapi::shared::minwindef::BOOL;
+use winapi::shared::windef::POINT;
+use winapi::um::winuser::{
+    GetMonitorInfoW, GetSystemMetrics, MonitorFromPoint, SystemParametersInfoW, HCF_HIGHCONTRASTON,
+    HIGHCONTRASTW, MONITORINFO, MONITORINFOEXW, MONITORINFOF_PRIMARY, MONITOR_DEFAULTTONULL,
+    SM_REMOTESESSION, SPI_GETHIGHCONTRAST, SPI_GETSCREENREADER,
+};
+
+use crate::window::ForegroundWindow;
+
+/// A screen region that the cursor must be in for a hotkey to trigger. The cursor position is
+/// taken at the time the hotkey was triggered.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CursorRegion {
+    /// A rectangle in virtual screen coordinates. `left` and `top` are inclusive, `right` and
+    /// `bottom` are exclusive
+    Rect {
+        left: i32,
+        top: i32,
+        right: i32,
+        bottom: i32,
+    },
+    /// The monitor with the given device name, for example `\\.\DISPLAY1`. The name of the monitor
+    /// at a specific position can be obtained with `monitor_name_at`
+    Monitor(String),
+    /// The primary monitor
+    PrimaryMonitor,
+}
+
+impl CursorRegion {
+    /// Check if the given point in virtual screen coordinates is inside of the region.
+    ///
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        match self {
+            CursorRegion::Rect {
+                left,
+                top,
+                right,
+                bottom,
+            } => x >= *left && x < *right && y >= *top && y < *bottom,
+            CursorRegion::Monitor(name) => {
+                monitor_info_at(x, y).is_some_and(|(monitor, _)| &monitor == name)
+            }
+            CursorRegion::PrimaryMonitor => {
+                monitor_info_at(x, y).is_some_and(|(_, primary)| primary)
+            }
+        }
+    }
+}
+
+/// The kind of session that the application is running in. Hotkeys can be restricted to one kind
+/// of session, since some of them, like display control or hardware volume, are meaningless or
+/// harmful inside of remote sessions.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SessionKind {
+    /// A local session on the physical console
+    Console,
+    /// A remote desktop (RDP) session
+    Remote,
+}
+
+impl SessionKind {
+    /// Get the kind of the session that the current process is running in. This is checked every
+    /// time a restricted hotkey is triggered, since the same session can be connected locally
+    /// and remotely over time.
+    ///
+    /// ## Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getsystemmetrics>
+    ///
+    pub fn current() -> Self {
+        match unsafe { GetSystemMetrics(SM_REMOTESESSION) } {
+            0 => SessionKind::Console,
+            _ => SessionKind::Remote,
+        }
+    }
+}
+
+/// A system wide accessibility setting that hotkeys can depend on, for example to only use
+/// hotkeys of an assistive tool while a screen reader is running.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccessibilityState {
+    /// The high contrast mode is on
+    HighContrast,
+    /// A screen reader like Narrator is running. Screen readers announce themselves with the
+    /// system wide screen reader flag, which is what is checked here
+    ScreenReader,
+}
+
+impl AccessibilityState {
+    /// Check if the setting is currently active. This is checked every time a restricted hotkey
+    /// is triggered, so the hotkeys adapt as soon as the setting changes.
+    ///
+    /// ## Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-systemparametersinfow>
+    ///
+    pub fn is_active(&self) -> bool {
+        match self {
+            AccessibilityState::HighContrast => {
+                let mut contrast: HIGHCONTRASTW = unsafe { std::mem::zeroed() };
+                contrast.cbSize = std::mem::size_of::<HIGHCONTRASTW>() as u32;
+                let ok = unsafe {
+                    SystemParametersInfoW(
+                        SPI_GETHIGHCONTRAST,
+                        contrast.cbSize,
+                        &mut contrast as *mut _ as *mut _,
+                        0,
+                    )
+                };
+                ok != 0 && contrast.dwFlags & HCF_HIGHCONTRASTON != 0
+            }
+            AccessibilityState::ScreenReader => {
+                let mut running: BOOL = 0;
+                let ok = unsafe {
+                    SystemParametersInfoW(
+                        SPI_GETSCREENREADER,
+                        0,
+                        &mut running as *mut _ as *mut _,
+                        0,
+                    )
+                };
+                ok != 0 && running != 0
+            }
+        }
+    }
+}
+
+/// A requirement on an `AccessibilityState` that a hotkey can be restricted to, either that the
+/// setting is active or that it is not.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AccessibilityCondition {
+    /// The accessibility setting
+    pub state: AccessibilityState,
+    /// If the setting needs to be active or inactive
+    pub active: bool,
+}
+
+impl AccessibilityCondition {
+    /// Only trigger while the setting is active
+    ///
+    pub fn active(state: AccessibilityState) -> Self {
+        Self {
+            state,
+            active: true,
+        }
+    }
+
+    /// Only trigger while the setting is not active
+    ///
+    pub fn inactive(state: AccessibilityState) -> Self {
+        Self {
+            state,
+            active: false,
+        }
+    }
+
+    /// Check if the condition is currently met
+    ///
+    pub fn is_met(&self) -> bool {
+        self.state.is_active() == self.active
+    }
+}
+
+/// A pattern for the title or class of a window in a `WindowCondition`. The matching is case
+/// sensitive.
+///
+#[derive(Debug, Clone)]
+pub enum TextPattern {
+    /// The text is exactly the same
+    Exact(String),
+    /// The text contains the string
+    Contains(String),
+    /// The text matches the regular expression. Only available with the `regex` feature
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+impl TextPattern {
+    /// Check if the text matches the pattern
+    ///
+    pub fn is_match(&self, text: &str) -> bool {
+        match self {
+            TextPattern::Exact(pattern) => text == pattern,
+            TextPattern::Contains(pattern) => text.contains(pattern.as_str()),
+            #[cfg(feature = "regex")]
+            TextPattern::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+impl PartialEq for TextPattern {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (TextPattern::Exact(a), TextPattern::Exact(b)) => a == b,
+            (TextPattern::Contains(a), TextPattern::Contains(b)) => a == b,
+            // Regular expressions are compared by their source
+            #[cfg(feature = "regex")]
+            (TextPattern::Regex(a), TextPattern::Regex(b)) => a.as_str() == b.as_str(),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for TextPattern {}
+
+/// A requirement on the foreground window that a hotkey can be restricted to, similar to
+/// `#IfWinActive` in AutoHotkey. The foreground window is captured when the hotkey is triggered,
+/// see `ForegroundWindow`.
+///
+/// The window can also be matched by the executable name of its process, for per-application
+/// bindings. If several patterns are set, all of them have to match.
+///
+/// # Example
+/// ```no_run
+/// use windows_hotkeys::condition::{TextPattern, WindowCondition};
+///
+/// // Only when Firefox is focused
+/// let firefox = WindowCondition::class(TextPattern::Exact("MozillaWindowClass".to_string()));
+/// // Only in the windows of the process with the executable `notepad.exe`
+/// let notepad = WindowCondition::process(TextPattern::Exact("notepad.exe".to_string()));
+/// // Everywhere except in windows with "Visual Studio Code" in the title
+/// let not_vscode =
+///     WindowCondition::title(TextPattern::Contains("Visual Studio Code".to_string())).inactive();
+/// ```
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowCondition {
+    /// The pattern for the window title
+    pub title: Option<TextPattern>,
+    /// The pattern for the window class name
+    pub class: Option<TextPattern>,
+    /// The pattern for the executable file name of the process of the window, like `firefox.exe`
+    pub process: Option<TextPattern>,
+    /// If the matching window needs to be in the foreground, or must not be in the foreground
+    pub active: bool,
+}
+
+impl WindowCondition {
+    /// Only trigger while a window with a matching title is in the foreground
+    ///
+    pub fn title(pattern: TextPattern) -> Self {
+        Self {
+            title: Some(pattern),
+            class: None,
+            process: None,
+            active: true,
+        }
+    }
+
+    /// Only trigger while a window with a matching class name is in the foreground
+    ///
+    pub fn class(pattern: TextPattern) -> Self {
+        Self {
+            title: None,
+            class: Some(pattern),
+            process: None,
+            active: true,
+        }
+    }
+
+    /// Only trigger while a window of a process with a matching executable file name is in the
+    /// foreground. The file name includes the extension, like `firefox.exe`.
+    ///
+    /// The executable name is looked up when the condition is checked. Windows of elevated
+    /// processes don't match if this process isn't elevated, since their name can't be queried.
+    ///
+    pub fn process(pattern: TextPattern) -> Self {
+        Self {
+            title: None,
+            class: None,
+            process: Some(pattern),
+            active: true,
+        }
+    }
+
+    /// Additionally require the title of the window to match
+    ///
+    pub fn with_title(mut self, pattern: TextPattern) -> Self {
+        self.title = Some(pattern);
+        self
+    }
+
+    /// Additionally require the class name of the window to match
+    ///
+    pub fn with_class(mut self, pattern: TextPattern) -> Self {
+        self.class = Some(pattern);
+        self
+    }
+
+    /// Additionally require the executable file name of the process of the window to match
+    ///
+    pub fn with_process(mut self, pattern: TextPattern) -> Self {
+        self.process = Some(pattern);
+        self
+    }
+
+    /// Invert the condition, so the hotkey only triggers while no matching window is in the
+    /// foreground, similar to `#IfWinNotActive`
+    ///
+    pub fn inactive(mut self) -> Self {
+        self.active = false;
+        self
+    }
+
+    /// Check if the condition is met for the given foreground window. If there is no foreground
+    /// window, no window matches.
+    ///
+    pub fn is_met(&self, window: Option<&ForegroundWindow>) -> bool {
+        let matches = window.is_some_and(|window| {
+            self.title
+                .as_ref()
+                .is_none_or(|pattern| pattern.is_match(&window.title))
+                && self
+                    .class
+                    .as_ref()
+                    .is_none_or(|pattern| pattern.is_match(&window.class))
+                // The process name is only looked up if it is needed, since that is the slowest
+                && self.process.as_ref().is_none_or(|pattern| {
+                    window
+                        .process_name()
+                        .is_some_and(|name| pattern.is_match(&name))
+                })
+        });
+        matches == self.active
+    }
+
+    /// Check if the condition is met for the current foreground window
+    ///
+    /// ## Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getforegroundwindow>
+    ///
+    pub fn is_met_now(&self) -> bool {
+        self.is_met(ForegroundWindow::capture().as_ref())
+    }
+}
+
+/// Get the device name of the monitor at the given point in virtual screen coordinates. Returns
+/// `None` if the point is not on any monitor.
+///
+/// ## Windows API Functions used
+/// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-monitorfrompoint>
+/// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getmonitorinfow>
+///
+pub fn monitor_name_at(x: i32, y: i32) -> Option<String> {
+    monitor_info_at(x, y).map(|(name, _)| name)
+}
+
+/// Get the device name of the monitor at the given point and whether it is the primary monitor
+///
+fn monitor_info_at(x: i32, y: i32) -> Option<(String, bool)> {
+    let monitor = unsafe { MonitorFromPoint(POINT { x, y }, MONITOR_DEFAULTTONULL) };
+    if monitor.is_null() {
+        return None;
+    }
+
+    let mut info: MONITORINFOEXW = unsafe { std::mem::zeroed() };
+    info.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+
+    let ok = unsafe { GetMonitorInfoW(monitor, &mut info as *mut _ as *mut MONITORINFO) };
+    if ok == 0 {
+        return None;
+    }
+
+    let name_len = info
+        .szDevice
+        .iter()
+        .position(|&c| c == 0)
+        .unwrap_or(info.szDevice.len());
+    let name = String::from_utf16_lossy(&info.szDevice[..name_len]);
+
+    Some((name, info.dwFlags & MONITORINFOF_PRIMARY != 0))
+}
@@ -0,0 +1,113 @@
+use std::time::{Duration, Instant};
+
+use crate::{error::HkError, keys::VKey, HotkeyId};
+
+/// Unassigned virtual key code that is injected as the probe of the hook diagnostics. It is
+/// registered as a hotkey without modifiers, so it doesn't collide with `hook::MASK_KEY`.
+///
+pub(crate) const PROBE_KEY: VKey = VKey::CustomKeyCode(0x97);
+
+/// The findings of the hook diagnostics, see `HotkeyManager::enable_hook_diagnostics`. A snapshot
+/// is included in the `Metrics` while the diagnostics are enabled.
+///
+/// Windows passes every key press through the chain of all low-level keyboard hooks in the
+/// system, like the ones of AutoHotkey, anti-cheat software or accessibility tools, before it
+/// detects registered hotkeys. A hook that takes long delays all hotkeys, and a hook that
+/// swallows the key presses makes them stop working entirely. Lost or slow probes point to such a
+/// hook.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HookDiagnostics {
+    /// The number of injected probe key presses
+    pub probes_sent: u64,
+    /// The number of probes that reached the event loop as a hotkey
+    pub probes_received: u64,
+    /// The number of probes that didn't arrive before the next probe was due, because they were
+    /// swallowed on the way
+    pub probes_lost: u64,
+    /// The number of probes that arrived later than the latency threshold
+    pub probes_slow: u64,
+    /// The average time from injecting a probe until it arrived in the event loop
+    pub average_latency: Duration,
+    /// The longest time from injecting a probe until it arrived in the event loop
+    pub max_latency: Duration,
+}
+
+/// The state of the hook diagnostics of a `HotkeyManager`
+///
+pub(crate) struct HookProbe {
+    /// The id of the hotkey that the probe key is registered with
+    pub(crate) id: HotkeyId,
+    /// The timer that sends the probes
+    pub(crate) timer: Option<usize>,
+    /// Probes that take longer than this to arrive are reported as slow
+    threshold: Duration,
+    /// When the probe that hasn't arrived yet was sent
+    pending: Option<Instant>,
+    diagnostics: HookDiagnostics,
+    /// The sum of all probe latencies, used to calculate the average
+    total_latency: Duration,
+}
+
+impl HookProbe {
+    pub(crate) fn new(id: HotkeyId, threshold: Duration) -> Self {
+        Self {
+            id,
+            timer: None,
+            threshold,
+            pending: None,
+            diagnostics: HookDiagnostics::default(),
+            total_latency: Duration::ZERO,
+        }
+    }
+
+    /// Count a probe that is about to be injected. Returns an error if the previous probe never
+    /// arrived.
+    ///
+    pub(crate) fn sent(&mut self) -> Result<(), HkError> {
+        let lost = self.pending.replace(Instant::now()).is_some();
+        self.diagnostics.probes_sent += 1;
+
+        if lost {
+            self.diagnostics.probes_lost += 1;
+            return Err(HkError::HookChainBlocked);
+        }
+        Ok(())
+    }
+
+    /// Count a probe that arrived in the event loop. Returns an error if it arrived later than
+    /// the threshold. Probes that were already counted as lost are ignored.
+    ///
+    pub(crate) fn received(&mut self) -> Result<(), HkError> {
+        let Some(sent) = self.pending.take() else {
+            return Ok(());
+        };
+
+        let latency = sent.elapsed();
+        self.diagnostics.probes_received += 1;
+        self.diagnostics.max_latency = self.diagnostics.max_latency.max(latency);
+        self.total_latency += latency;
+
+        if latency > self.threshold {
+            self.diagnostics.probes_slow += 1;
+            return Err(HkError::HookChainSlow(latency));
+        }
+        Ok(())
+    }
+
+    /// Get a snapshot of the current findings
+    ///
+    pub(crate) fn snapshot(&self) -> HookDiagnostics {
+        let average_latency = match self.diagnostics.probes_received {
+            0 => Duration::ZERO,
+            received => {
+                Duration::from_nanos((self.total_latency.as_nanos() / received as u128) as u64)
+            }
+        };
+
+        HookDiagnostics {
+            average_latency,
+            ..self.diagnostics.clone()
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{combo::HotkeyCombo, error::HkError};
+
+/// A hotkey definition file in the text format that is used by hotkey daemons like whkd and
+/// sxhkd. Every line binds a key combination to a command, separated by `:`. Lines starting with
+/// `#` are comments and lines starting with `.` are directives for the daemon, like the shell that
+/// executes the commands:
+/// ```text
+/// .shell pwsh
+///
+/// # Focus windows
+/// alt + h : komorebic focus left
+/// alt + l : komorebic focus right
+/// alt + shift + return : wt
+/// ```
+///
+/// The key combinations are parsed with `HotkeyCombo::parse`, the commands are kept as they are
+/// written. Running them is left to the daemon.
+///
+/// # Example
+/// ```no_run
+/// use windows_hotkeys::{dsl::HotkeyScript, HotkeyManager, HotkeyManagerImpl};
+///
+/// let script = HotkeyScript::parse("alt + shift + return : wt").unwrap();
+///
+/// let mut hkm = HotkeyManager::new();
+/// for binding in script.bindings {
+///     let command = binding.command.clone();
+///     hkm.register_combo(&binding.combo, move || println!("Run: {}", command))
+///         .unwrap();
+/// }
+/// hkm.event_loop();
+/// ```
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HotkeyScript {
+    /// The directives in the order of the file
+    pub directives: Vec<Directive>,
+    /// The bindings in the order of the file
+    pub bindings: Vec<ScriptBinding>,
+}
+
+/// A directive line like `.shell pwsh`
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Directive {
+    /// The name of the directive without the `.`, like `shell`
+    pub name: String,
+    /// Everything after the name, like `pwsh`. Empty if the directive has no value
+    pub value: String,
+    /// The line of the directive, starting at 1
+    pub line: usize,
+}
+
+/// A binding line like `alt + h : komorebic focus left`
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ScriptBinding {
+    /// The key combination in front of the `:`
+    pub combo: HotkeyCombo,
+    /// The command after the `:`, without surrounding whitespace
+    pub command: String,
+    /// The line of the binding, starting at 1
+    pub line: usize,
+}
+
+impl HotkeyScript {
+    /// Parse the text of a hotkey definition file. Stops at the first line that can't be parsed
+    /// and returns `HkError::InvalidScript` with its line number.
+    ///
+    pub fn parse(script: &str) -> Result<Self, HkError> {
+        let mut parsed = Self::default();
+
+        for (index, text) in script.lines().enumerate() {
+            let line = index + 1;
+            let trimmed = text.trim();
+
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            if let Some(directive) = trimmed.strip_prefix('.') {
+                let (name, value) = directive
+                    .split_once(char::is_whitespace)
+                    .unwrap_or((directive, ""));
+                if name.is_empty() {
+                    return Err(invalid_line(line, "the directive has no name"));
+                }
+                parsed.directives.push(Directive {
+                    name: name.to_string(),
+                    value: value.trim().to_string(),
+                    line,
+                });
+                continue;
+            }
+
+            let Some((combo, command)) = text.split_once(':') else {
+                return Err(invalid_line(line, "expected `<combo> : <command>`"));
+            };
+
+            let command = command.trim();
+            if command.is_empty() {
+                return Err(invalid_line(line, "the command is missing"));
+            }
+
+            // The positions of invalid key names are relative to the start of the line
+            let combo =
+                HotkeyCombo::parse(combo).map_err(|err| invalid_line(line, err.to_string()))?;
+
+            parsed.bindings.push(ScriptBinding {
+                combo,
+                command: command.to_string(),
+                line,
+            });
+        }
+
+        Ok(parsed)
+    }
+
+    /// Get the value of the last directive with the given name, like the shell for `"shell"`
+    ///
+    pub fn directive(&self, name: &str) -> Option<&str> {
+        self.directives
+            .iter()
+            .rev()
+            .find(|directive| directive.name == name)
+            .map(|directive| directive.value.as_str())
+    }
+}
+
+/// Create the error for a line that can't be parsed
+///
+fn invalid_line(line: usize, reason: impl Into<String>) -> HkError {
+    HkError::InvalidScript {
+        line,
+        reason: reason.into(),
+    }
+}
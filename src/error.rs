@@ -1,17 +1,276 @@
+use std::borrow::Cow;
+use std::time::Duration;
+
 use thiserror::Error;
+#[cfg(windows)]
+use winapi::shared::winerror::{
+    ERROR_ACCESS_DENIED, ERROR_HOTKEY_ALREADY_REGISTERED, ERROR_INVALID_FLAGS,
+    ERROR_INVALID_PARAMETER, ERROR_INVALID_WINDOW_HANDLE, ERROR_NOT_ENOUGH_MEMORY,
+    ERROR_NO_SYSTEM_RESOURCES,
+};
+#[cfg(windows)]
+use winapi::um::errhandlingapi::GetLastError;
+
+#[cfg(not(windows))]
+use codes::*;
+
+/// Copies of the windows error codes that are explained by `HkError::advice`, since
+/// `winapi::shared::winerror` is only available when building for windows
+///
+#[cfg(not(windows))]
+mod codes {
+    pub const ERROR_ACCESS_DENIED: u32 = 5;
+    pub const ERROR_NOT_ENOUGH_MEMORY: u32 = 8;
+    pub const ERROR_INVALID_PARAMETER: u32 = 87;
+    pub const ERROR_INVALID_FLAGS: u32 = 1004;
+    pub const ERROR_INVALID_WINDOW_HANDLE: u32 = 1400;
+    pub const ERROR_HOTKEY_ALREADY_REGISTERED: u32 = 1409;
+    pub const ERROR_NO_SYSTEM_RESOURCES: u32 = 1450;
+}
 
-use crate::keys::VKey;
+use crate::{
+    keys::{ModKey, VKey},
+    HotkeyId,
+};
 
 #[derive(Debug, Error)]
 pub enum HkError {
-    #[error("invalid key name `{0}`")]
-    InvalidKey(String),
+    /// The key name exactly as it was given, and its byte position if it was part of a combo
+    /// string like `"CTRL + ALT + M"`
+    #[error(
+        "invalid key name `{name}`{}",
+        position.map(|p| format!(" at position {}", p)).unwrap_or_default()
+    )]
+    InvalidKey {
+        name: Cow<'static, str>,
+        position: Option<usize>,
+    },
     #[error("invalid key char `{0}`")]
     InvalidKeyChar(char),
     #[error("VKey is not a ModKey `{0}`")]
     NotAModkey(VKey),
-    #[error("Hotkey registration failed. Hotkey or Id might be in use already")]
-    RegistrationFailed,
+    /// The windows error code, if the registration was rejected by windows. See `advice` for a
+    /// user-facing explanation
+    #[error(
+        "Hotkey registration failed{}. Hotkey or Id might be in use already",
+        .0.map(|code| format!(" with windows error {}", code)).unwrap_or_default()
+    )]
+    RegistrationFailed(Option<u32>),
     #[error("Hotkey unregistration failed")]
     UnregistrationFailed,
+    #[error("No hotkey is registered for the id `{0:?}`")]
+    InvalidHotkeyId(HotkeyId),
+    /// The id of the hotkey that was removed, because neither its new nor its old key combination
+    /// could be registered, see `rebind`
+    #[error("The hotkey `{0:?}` was removed, since its old key combination could not be registered again")]
+    NotRegistered(HotkeyId),
+    /// The id of a hotkey that was registered with `register_once`
+    #[error("The trigger limit of the one-shot hotkey `{0:?}` can't be changed")]
+    OneShotHotkey(HotkeyId),
+    #[error("ModKey `{0:?}` can't be tapped on its own")]
+    NotTappable(ModKey),
+    #[error("The key combination `{0}` has no main key, only modifiers")]
+    MissingMainKey(String),
+    #[error("At least one key combination is required")]
+    NoCombos,
+    #[error("The hotkey definition has no action name")]
+    NoAction,
+    #[error("At least one action is required")]
+    NoActions,
+    #[error("A mouse gesture needs at least one stroke")]
+    EmptyGesture,
+    #[error("The keymap could not be loaded: {0}")]
+    InvalidKeymap(String),
+    /// The schema version of the keymap file and the newest version that the application
+    /// supports, see `Keymap::load_with`
+    #[error(
+        "The keymap has the version {found}, but only versions up to {supported} are supported"
+    )]
+    UnsupportedKeymapVersion { found: u32, supported: u32 },
+    /// What is wrong with the exported binding set
+    #[error("The binding set could not be read: {0}")]
+    InvalidBindingSet(String),
+    /// The format version of the binding set, which is newer than `export::WIRE_VERSION`
+    #[error("The binding set has the unsupported format version {0}")]
+    UnsupportedWireVersion(u64),
+    /// The line of the hotkey script, starting at 1, and what is wrong with it
+    #[error("invalid hotkey script line {line}: {reason}")]
+    InvalidScript { line: usize, reason: String },
+    #[error("No leader key combination is set")]
+    NoLeader,
+    #[error("Installing the low-level hook failed")]
+    HookFailed,
+    #[error("A probe key press of the hook diagnostics was swallowed by another low-level hook")]
+    HookChainBlocked,
+    /// The time that the probe key press took to reach the event loop
+    #[error("A probe key press of the hook diagnostics was delayed by {0:?}")]
+    HookChainSlow(Duration),
+    #[error("Sending input failed")]
+    SendInputFailed,
+    /// The command line and why it couldn't be run or why it failed
+    #[error("Running the command failed: {0}")]
+    CommandFailed(String),
+    /// The windows error code of the failed mutex creation
+    #[error("Creating the single instance guard failed with windows error {0}")]
+    InstanceFailed(u32),
+    /// The name of the instance
+    #[error("The running instance `{0}` doesn't accept commands")]
+    InstanceNotFound(String),
+    /// The name of the requested context type
+    #[error("No context of type `{0}` is set")]
+    NoContext(&'static str),
+    /// The id of the hotkey whose callback panicked while a `PanicPolicy` was set
+    #[error("The callback of hotkey `{0:?}` panicked")]
+    CallbackPanicked(HotkeyId),
+    /// The id of the hotkey and the error that its callback returned, see `register_fallible`
+    #[error("The callback of hotkey `{0:?}` failed: {1}")]
+    CallbackFailed(
+        HotkeyId,
+        #[source] Box<dyn std::error::Error + Send + Sync + 'static>,
+    ),
+    /// The action name of the triggered hotkey
+    #[error("No action handler is set for the action `{0}`")]
+    NoActionHandler(String),
+    /// The error of a hotkey that was registered with a label, see `RegisterOptions::label`
+    #[error("hotkey `{label}`: {source}")]
+    Labeled {
+        label: String,
+        #[source]
+        source: Box<HkError>,
+    },
+}
+
+impl HkError {
+    /// Create an `InvalidKey` error without a position
+    ///
+    pub(crate) fn invalid_key(name: impl Into<Cow<'static, str>>) -> Self {
+        HkError::InvalidKey {
+            name: name.into(),
+            position: None,
+        }
+    }
+
+    /// Create a `RegistrationFailed` error with the last windows error code of the thread
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/errhandlingapi/nf-errhandlingapi-getlasterror>
+    ///
+    #[cfg(windows)]
+    pub(crate) fn last_registration_error() -> Self {
+        HkError::RegistrationFailed(Some(unsafe { GetLastError() }))
+    }
+
+    /// The windows error code that caused the error, if there is one
+    ///
+    pub fn os_error_code(&self) -> Option<u32> {
+        match self {
+            HkError::RegistrationFailed(code) => *code,
+            HkError::Labeled { source, .. } => source.os_error_code(),
+            _ => None,
+        }
+    }
+
+    /// An actionable explanation of the error that can be shown to users, for example in a
+    /// dialog next to the hotkey setting that failed. The most common reasons for rejected
+    /// registrations are mapped by their windows error code. `None` if there is no advice for
+    /// the error.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use windows_hotkeys::{keys::{ModKey, VKey}, HotkeyManager, HotkeyManagerImpl};
+    ///
+    /// let mut hkm = HotkeyManager::new();
+    /// if let Err(err) = hkm.register(VKey::S, ModKey::Win | ModKey::Shift, || ()) {
+    ///     match (err.os_error_code(), err.advice()) {
+    ///         (Some(code), Some(advice)) => eprintln!("{}: {}", code, advice),
+    ///         _ => eprintln!("{}", err),
+    ///     }
+    /// }
+    /// ```
+    ///
+    pub fn advice(&self) -> Option<&'static str> {
+        let advice = match self {
+            HkError::Labeled { source, .. } => return source.advice(),
+            HkError::RegistrationFailed(Some(code)) => match *code {
+                ERROR_HOTKEY_ALREADY_REGISTERED => {
+                    "The key combination is already registered, most likely by another \
+                     application like OneDrive or Snip & Sketch, or it is reserved by windows. \
+                     Try a different combination"
+                }
+                ERROR_INVALID_PARAMETER | ERROR_INVALID_FLAGS => {
+                    "The key or the modifiers are not supported by windows. Try a different key"
+                }
+                ERROR_INVALID_WINDOW_HANDLE => {
+                    "The window of the HotkeyManager doesn't exist anymore. Hotkeys must be \
+                     registered on the thread that created the HotkeyManager"
+                }
+                ERROR_ACCESS_DENIED => {
+                    "Windows denied the registration. The key combination might be protected \
+                     by the system"
+                }
+                ERROR_NOT_ENOUGH_MEMORY | ERROR_NO_SYSTEM_RESOURCES => {
+                    "Windows ran out of resources. Close some applications and try again"
+                }
+                _ => return None,
+            },
+            HkError::RegistrationFailed(None) => {
+                "The key combination could not be registered. Try a different combination"
+            }
+            HkError::InvalidKey { .. } => {
+                "Check the spelling of the key name. Key names like `A`, `F5`, `VK_RETURN` or \
+                 hex codes like `0x41` are supported"
+            }
+            HkError::MissingMainKey(_) => {
+                "Add a main key to the combination, like the `K` in `CTRL + ALT + K`"
+            }
+            HkError::HookFailed => {
+                "The keyboard or mouse hook could not be installed. Security software might \
+                 block low-level hooks"
+            }
+            HkError::HookChainBlocked => {
+                "Another application with a low-level keyboard hook, like AutoHotkey, an \
+                 anti-cheat or an accessibility tool, swallows key presses before they reach \
+                 the hotkeys. Try closing these applications one by one"
+            }
+            HkError::HookChainSlow(_) => {
+                "Another application with a low-level keyboard hook delays the key presses, or \
+                 the event loop is blocked by a slow callback. Try closing applications like \
+                 AutoHotkey, anti-cheats or accessibility tools one by one"
+            }
+            _ => return None,
+        };
+        Some(advice)
+    }
+
+    /// Wrap the error into a `Labeled` error with the label of the hotkey that caused it
+    ///
+    #[cfg(windows)]
+    pub(crate) fn with_label(self, label: &str) -> Self {
+        HkError::Labeled {
+            label: label.to_string(),
+            source: Box::new(self),
+        }
+    }
+
+    /// Get the error without the label of a `Labeled` error. Other errors are returned as they are
+    ///
+    pub fn unlabeled(&self) -> &HkError {
+        match self {
+            HkError::Labeled { source, .. } => source.unlabeled(),
+            err => err,
+        }
+    }
+
+    /// Set the position of an `InvalidKey` error within a combo string. Other errors are returned
+    /// unchanged
+    ///
+    pub(crate) fn at_position(self, offset: usize) -> Self {
+        match self {
+            HkError::InvalidKey { name, .. } => HkError::InvalidKey {
+                name,
+                position: Some(offset),
+            },
+            err => err,
+        }
+    }
 }
@@ -0,0 +1,266 @@
+use serde_json::{Map, Value};
+
+use crate::{
+    combo::HotkeyCombo, error::HkError, keys::ModKey, options::RegisterOptions, HotkeyInfo,
+};
+
+/// The value of the `format` field, which marks a JSON document as an exported binding set
+///
+const FORMAT_NAME: &str = "windows-hotkeys-bindings";
+
+/// The version of the wire format that is written by `BindingSet::to_json` and the newest version
+/// that `BindingSet::from_json` can read.
+///
+/// The version is only increased for changes that older readers would misinterpret. New optional
+/// fields don't increase it, since readers ignore the fields that they don't know. This way, older
+/// versions of the crate can read the binding sets that are exported by newer versions, as long
+/// as the format version is the same.
+///
+pub const WIRE_VERSION: u64 = 1;
+
+/// A set of key bindings in a stable, versioned JSON wire format, to sync hotkey configurations
+/// between machines that use different versions of this crate.
+///
+/// The format of version 1 looks like this, where every field of a binding except `combo` is
+/// optional:
+/// ```json
+/// {
+///   "format": "windows-hotkeys-bindings",
+///   "version": 1,
+///   "bindings": [
+///     {
+///       "combo": "CONTROL + ALT + K",
+///       "action": "show_window",
+///       "label": "Show",
+///       "description": "Show the main window",
+///       "group": "window",
+///       "mode": "normal",
+///       "no_repeat": true,
+///       "enabled": false
+///     }
+///   ]
+/// }
+/// ```
+///
+/// The key combinations are stored as their canonical names (see `HotkeyCombo`'s `Display`
+/// implementation) instead of key codes, and they are only parsed with `parse_combo`. So a
+/// combination with a key name that an older version doesn't know only fails for that binding.
+///
+/// The enabled state of the hotkeys is included, so bindings that the user disabled stay disabled
+/// after an import. Bindings without `enabled` are enabled.
+///
+/// # Example
+/// ```no_run
+/// use windows_hotkeys::{export::BindingSet, HotkeyManager};
+///
+/// let hkm = HotkeyManager::<()>::new();
+/// let exported = BindingSet::from_hotkeys(&hkm.hotkeys()).to_json();
+///
+/// for binding in BindingSet::from_json(&exported).unwrap().bindings {
+///     println!("{}: {:?}", binding.combo, binding.action);
+/// }
+/// ```
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BindingSet {
+    pub bindings: Vec<ExportedBinding>,
+}
+
+/// A single binding of a `BindingSet`
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ExportedBinding {
+    /// The canonical name of the key combination, like `"CONTROL + ALT + K"`
+    pub combo: String,
+    /// The action name of the binding, see `register_action`
+    pub action: Option<String>,
+    /// See `RegisterOptions::label`
+    pub label: Option<String>,
+    /// See `RegisterOptions::description`
+    pub description: Option<String>,
+    /// See `RegisterOptions::group`
+    pub group: Option<String>,
+    /// See `RegisterOptions::mode`
+    pub mode: Option<String>,
+    /// See `RegisterOptions::no_repeat`
+    pub no_repeat: Option<bool>,
+    /// If the hotkey is enabled, see `set_enabled`. `None` means enabled
+    pub enabled: Option<bool>,
+}
+
+impl BindingSet {
+    /// Create a binding set from the information about registered hotkeys, as returned by
+    /// `hotkeys`
+    ///
+    pub fn from_hotkeys(hotkeys: &[HotkeyInfo]) -> Self {
+        Self {
+            bindings: hotkeys.iter().map(ExportedBinding::from_info).collect(),
+        }
+    }
+
+    /// Serialize the binding set into the JSON wire format of the current `WIRE_VERSION`
+    ///
+    pub fn to_json(&self) -> String {
+        let bindings = self
+            .bindings
+            .iter()
+            .map(ExportedBinding::to_value)
+            .collect();
+
+        let mut set = Map::new();
+        set.insert("format".to_string(), FORMAT_NAME.into());
+        set.insert("version".to_string(), WIRE_VERSION.into());
+        set.insert("bindings".to_string(), Value::Array(bindings));
+        Value::Object(set).to_string()
+    }
+
+    /// Parse a binding set from the JSON wire format. Unknown fields are ignored, so binding sets
+    /// that were exported by newer versions of the crate can be read as long as their format
+    /// version is supported. Returns `HkError::UnsupportedWireVersion` for newer format versions.
+    ///
+    pub fn from_json(json: &str) -> Result<Self, HkError> {
+        let value: Value = serde_json::from_str(json).map_err(|err| invalid(&err.to_string()))?;
+        let set = value
+            .as_object()
+            .ok_or_else(|| invalid("expected an object"))?;
+
+        if set.get("format").and_then(Value::as_str) != Some(FORMAT_NAME) {
+            return Err(invalid("not an exported binding set"));
+        }
+        let version = set
+            .get("version")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| invalid("missing format version"))?;
+        if version > WIRE_VERSION {
+            return Err(HkError::UnsupportedWireVersion(version));
+        }
+
+        let bindings = match set.get("bindings") {
+            Some(Value::Array(bindings)) => bindings
+                .iter()
+                .map(ExportedBinding::from_value)
+                .collect::<Result<_, _>>()?,
+            Some(_) => return Err(invalid("`bindings` is not a list")),
+            None => Vec::new(),
+        };
+        Ok(Self { bindings })
+    }
+}
+
+impl ExportedBinding {
+    /// Create the binding of a registered hotkey
+    ///
+    pub fn from_info(info: &HotkeyInfo) -> Self {
+        let combo = HotkeyCombo::new(info.key, &info.modifiers).with_extra_keys(&info.extra_keys);
+
+        Self {
+            combo: combo.to_string(),
+            action: info.action.clone(),
+            label: info.label.clone(),
+            description: info.description.clone(),
+            group: info.group.clone(),
+            mode: info.mode.clone(),
+            no_repeat: Some(info.modifiers.contains(&ModKey::NoRepeat)),
+            enabled: Some(info.enabled),
+        }
+    }
+
+    /// Check if the hotkey of the binding should be enabled. For bindings that are not registered
+    /// with `options`, like action bindings, restore the state with `set_enabled` after the
+    /// registration.
+    ///
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    /// Parse the key combination of the binding
+    ///
+    pub fn parse_combo(&self) -> Result<HotkeyCombo, HkError> {
+        HotkeyCombo::parse(&self.combo)
+    }
+
+    /// Get the options to register the binding with `register_with_options`, including the extra
+    /// keys of the key combination and the enabled state
+    ///
+    pub fn options(&self) -> Result<RegisterOptions, HkError> {
+        Ok(RegisterOptions {
+            no_repeat: self.no_repeat,
+            extra_keys: self.parse_combo()?.extra_keys,
+            label: self.label.clone(),
+            description: self.description.clone(),
+            group: self.group.clone(),
+            mode: self.mode.clone(),
+            enabled: self.is_enabled(),
+            ..Default::default()
+        })
+    }
+
+    fn to_value(&self) -> Value {
+        let mut binding = Map::new();
+        binding.insert("combo".to_string(), self.combo.clone().into());
+
+        let fields = [
+            ("action", &self.action),
+            ("label", &self.label),
+            ("description", &self.description),
+            ("group", &self.group),
+            ("mode", &self.mode),
+        ];
+        for (name, value) in fields {
+            if let Some(value) = value {
+                binding.insert(name.to_string(), value.clone().into());
+            }
+        }
+        let flags = [("no_repeat", self.no_repeat), ("enabled", self.enabled)];
+        for (name, value) in flags {
+            if let Some(value) = value {
+                binding.insert(name.to_string(), value.into());
+            }
+        }
+
+        Value::Object(binding)
+    }
+
+    fn from_value(value: &Value) -> Result<Self, HkError> {
+        let binding = value
+            .as_object()
+            .ok_or_else(|| invalid("a binding is not an object"))?;
+        let combo =
+            string_field(binding, "combo")?.ok_or_else(|| invalid("a binding has no `combo`"))?;
+
+        Ok(Self {
+            combo,
+            action: string_field(binding, "action")?,
+            label: string_field(binding, "label")?,
+            description: string_field(binding, "description")?,
+            group: string_field(binding, "group")?,
+            mode: string_field(binding, "mode")?,
+            no_repeat: bool_field(binding, "no_repeat")?,
+            enabled: bool_field(binding, "enabled")?,
+        })
+    }
+}
+
+/// Read an optional string field of a binding
+///
+fn string_field(binding: &Map<String, Value>, name: &str) -> Result<Option<String>, HkError> {
+    match binding.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(value)) => Ok(Some(value.clone())),
+        Some(_) => Err(invalid(&format!("`{}` is not a string", name))),
+    }
+}
+
+/// Read an optional boolean field of a binding
+///
+fn bool_field(binding: &Map<String, Value>, name: &str) -> Result<Option<bool>, HkError> {
+    match binding.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Bool(value)) => Ok(Some(*value)),
+        Some(_) => Err(invalid(&format!("`{}` is not a boolean", name))),
+    }
+}
+
+fn invalid(reason: &str) -> HkError {
+    HkError::InvalidBindingSet(reason.to_string())
+}
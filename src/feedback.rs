@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+
+use winapi::um::winuser::GetKeyState;
+
+use crate::{combo::HotkeyCombo, error::HkError, keys::VKey, send::tap_key};
+
+/// Receives the active mode and the executed hotkeys to show them on the keyboard itself, for
+/// example through the SDK of an RGB keyboard or with the lock LEDs (see `LockLedFeedback`). This
+/// lets modal setups show which layer is active without an on-screen display.
+///
+/// Set with `HotkeyManager::set_keyboard_feedback`. The methods are called on the thread of the
+/// event loop, so they should return quickly.
+///
+pub trait KeyboardFeedback: Send {
+    /// Called when the mode changes, with the name of the new mode or `None` for the default mode.
+    /// Also called once when the feedback is set, so the keyboard shows the current mode right
+    /// away.
+    ///
+    fn mode_changed(&mut self, mode: Option<&str>) {
+        let _ = mode;
+    }
+
+    /// Called whenever a hotkey callback is executed, with the key combination and the label of
+    /// the hotkey, for example to flash the keys of the combination.
+    ///
+    fn triggered(&mut self, combo: &HotkeyCombo, label: Option<&str>) {
+        let _ = (combo, label);
+    }
+}
+
+/// One of the lock LEDs that are built into most keyboards
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LockLed {
+    CapsLock,
+    NumLock,
+    ScrollLock,
+}
+
+impl LockLed {
+    /// The lock key that toggles the LED
+    ///
+    pub fn vkey(&self) -> VKey {
+        match self {
+            LockLed::CapsLock => VKey::Capital,
+            LockLed::NumLock => VKey::Numlock,
+            LockLed::ScrollLock => VKey::Scroll,
+        }
+    }
+
+    /// Check if the LED is currently on, meaning that the lock is toggled on
+    ///
+    /// # Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getkeystate>
+    ///
+    pub fn is_on(&self) -> bool {
+        // The lowest bit is set while the lock is toggled on
+        let state = unsafe { GetKeyState(self.vkey().to_vk_code()) };
+        state & 1 != 0
+    }
+
+    /// Switch the LED on or off by pressing the lock key if the LED is not in that state yet. This
+    /// also toggles the lock itself, so switching the Caps Lock LED changes the case of typed
+    /// text.
+    ///
+    /// # Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-sendinput>
+    ///
+    pub fn set(&self, on: bool) -> Result<(), HkError> {
+        if self.is_on() == on {
+            return Ok(());
+        }
+        tap_key(self.vkey())
+    }
+}
+
+/// A `KeyboardFeedback` that lights a lock LED while a mode is active, for keyboards without RGB
+/// lighting. The Scroll Lock LED is the best choice, since the Scroll Lock has no effect in most
+/// applications.
+///
+/// # Example
+/// ```no_run
+/// use windows_hotkeys::feedback::{LockLed, LockLedFeedback};
+/// use windows_hotkeys::singlethreaded::HotkeyManager;
+///
+/// let mut hkm = HotkeyManager::<()>::new();
+/// hkm.set_keyboard_feedback(LockLedFeedback::new().with_mode("resize", LockLed::ScrollLock));
+/// ```
+///
+#[derive(Debug, Default)]
+pub struct LockLedFeedback {
+    /// The LED for each mode
+    leds: HashMap<String, LockLed>,
+    /// The LED that was switched on for the current mode
+    lit: Option<LockLed>,
+}
+
+impl LockLedFeedback {
+    /// Create a new feedback without any LEDs
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Light the LED while the mode is active. Multiple modes can share an LED
+    ///
+    pub fn with_mode(mut self, mode: &str, led: LockLed) -> Self {
+        self.leds.insert(mode.to_string(), led);
+        self
+    }
+}
+
+impl KeyboardFeedback for LockLedFeedback {
+    fn mode_changed(&mut self, mode: Option<&str>) {
+        let led = mode.and_then(|mode| self.leds.get(mode)).copied();
+        if led == self.lit {
+            return;
+        }
+
+        if let Some(lit) = self.lit.take() {
+            let _ = lit.set(false);
+        }
+        if let Some(led) = led {
+            if led.set(true).is_ok() {
+                self.lit = Some(led);
+            }
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{keys::ModKey, mouse::MouseButton};
+
+/// The minimum distance in pixels that the cursor has to move in one direction to be recognized
+/// as a stroke of a mouse gesture.
+///
+pub const STROKE_DISTANCE: i32 = 30;
+
+/// One straight movement of a mouse gesture. A gesture is a sequence of strokes, for example
+/// `[Stroke::Down, Stroke::Right]` for an L-shaped movement.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stroke {
+    /// Movement to the left
+    Left,
+    /// Movement to the right
+    Right,
+    /// Movement towards the top of the screen
+    Up,
+    /// Movement towards the bottom of the screen
+    Down,
+}
+
+/// A mouse gesture that is detected by the mouse hook
+///
+#[derive(Debug, Clone)]
+pub(crate) struct GestureBinding {
+    /// The button that is held while drawing the gesture
+    pub(crate) button: MouseButton,
+    /// The modifier code of the required modifiers, excluding `ModKey::NoRepeat`
+    pub(crate) modifiers: u32,
+    /// The strokes of the gesture
+    pub(crate) strokes: Vec<Stroke>,
+}
+
+impl GestureBinding {
+    pub(crate) fn new(button: MouseButton, key_modifiers: &[ModKey], strokes: &[Stroke]) -> Self {
+        Self {
+            button,
+            modifiers: ModKey::combine(key_modifiers) & !ModKey::NoRepeat.to_mod_code(),
+            strokes: strokes.to_vec(),
+        }
+    }
+}
+
+/// Records the strokes of a gesture from the cursor movements while the gesture button is held.
+///
+/// The cursor movement is quantized into the four directions. A new stroke is only added if the
+/// direction changed, so jittery movement in the same direction results in a single stroke.
+///
+pub(crate) struct GestureRecorder {
+    pub(crate) button: MouseButton,
+    pub(crate) modifiers: u32,
+    /// The position where the current stroke started
+    anchor: (i32, i32),
+    pub(crate) strokes: Vec<Stroke>,
+}
+
+impl GestureRecorder {
+    pub(crate) fn new(button: MouseButton, modifiers: u32, x: i32, y: i32) -> Self {
+        Self {
+            button,
+            modifiers,
+            anchor: (x, y),
+            strokes: Vec::new(),
+        }
+    }
+
+    /// Add the movement to the given cursor position
+    ///
+    pub(crate) fn moved(&mut self, x: i32, y: i32) {
+        let (dx, dy) = (x - self.anchor.0, y - self.anchor.1);
+        if dx.abs().max(dy.abs()) < STROKE_DISTANCE {
+            return;
+        }
+
+        let stroke = match (dx.abs() > dy.abs(), dx > 0, dy > 0) {
+            (true, true, _) => Stroke::Right,
+            (true, false, _) => Stroke::Left,
+            (false, _, true) => Stroke::Down,
+            (false, _, false) => Stroke::Up,
+        };
+
+        if self.strokes.last() != Some(&stroke) {
+            self.strokes.push(stroke);
+        }
+        self.anchor = (x, y);
+    }
+
+    /// Check if the recorded strokes match the gesture
+    ///
+    pub(crate) fn matches(&self, gesture: &GestureBinding) -> bool {
+        gesture.button == self.button
+            && gesture.modifiers == self.modifiers
+            && gesture.strokes == self.strokes
+    }
+}
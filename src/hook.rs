@@ -0,0 +1,632 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{
+        mpsc::{channel, Receiver},
+        Arc, Mutex,
+    },
+    thread::{spawn, JoinHandle},
+};
+
+use winapi::ctypes::c_int;
+use winapi::shared::minwindef::{DWORD, LPARAM, LRESULT, WPARAM};
+use winapi::shared::windef::HWND;
+use winapi::um::libloaderapi::GetModuleHandleW;
+use winapi::um::processthreadsapi::GetCurrentThreadId;
+use winapi::um::winuser::{
+    CallNextHookEx, GetMessageW, PeekMessageW, PostMessageW, PostThreadMessageW, SetWindowsHookExW,
+    UnhookWindowsHookEx, HC_ACTION, HOOKPROC, KBDLLHOOKSTRUCT, LLKHF_ALTDOWN, LLKHF_EXTENDED,
+    LLKHF_INJECTED, LLKHF_LOWER_IL_INJECTED, LLKHF_UP, MSG, PM_NOREMOVE, WH_KEYBOARD_LL, WM_HOTKEY,
+    WM_KEYDOWN, WM_KEYUP, WM_QUIT, WM_SYSKEYDOWN, WM_SYSKEYUP, WM_USER,
+};
+
+use crate::{
+    cleanup,
+    condition::WindowCondition,
+    error::HkError,
+    get_global_keystate,
+    keys::{ModKey, ScanCode, VKey},
+    send::{tap_key, INJECTED_EXTRA_INFO},
+    HotkeyId, Predicate,
+};
+
+/// Handler function that is called by the keyboard hook for every keyboard event
+///
+type KeyboardHandler = Box<dyn FnMut(&KeyboardHookEvent) -> HookAction + 'static>;
+
+thread_local! {
+    /// The handler for the keyboard hook installed on the current thread. Low-level hooks are
+    /// always called on the thread that installed them, so a thread local is used to route the
+    /// events from the hook procedure to the handler.
+    static KEYBOARD_HANDLER: RefCell<Option<KeyboardHandler>> = RefCell::new(None);
+}
+
+/// The action that a hook handler decides on for an event.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HookAction {
+    /// Pass the event on to the next hook and finally the target application
+    Pass,
+    /// Block the event. Neither other hooks nor the target application will receive it
+    Block,
+}
+
+/// A keyboard event as received by the low-level keyboard hook. This contains the complete data
+/// of the `KBDLLHOOKSTRUCT`, allowing for logic that isn't covered by the hotkey APIs.
+///
+/// See: <https://learn.microsoft.com/en-us/windows/win32/api/winuser/ns-winuser-kbdllhookstruct>
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyboardHookEvent {
+    /// The keyboard message identifier. One of `WM_KEYDOWN`, `WM_KEYUP`, `WM_SYSKEYDOWN` or
+    /// `WM_SYSKEYUP`
+    pub message: u32,
+    /// The virtual key of the event
+    pub vkey: VKey,
+    /// The hardware scan code of the key
+    pub scan_code: u32,
+    /// The raw `LLKHF_*` flags of the event
+    pub flags: u32,
+    /// The message timestamp in milliseconds
+    pub time: u32,
+    /// Additional information associated with the message. This is mostly used by applications
+    /// to mark their own injected input
+    pub extra_info: usize,
+}
+
+impl KeyboardHookEvent {
+    fn from_raw(message: u32, raw: &KBDLLHOOKSTRUCT) -> Self {
+        Self {
+            message,
+            vkey: VKey::CustomKeyCode(raw.vkCode as i32),
+            scan_code: raw.scanCode,
+            flags: raw.flags,
+            time: raw.time,
+            extra_info: raw.dwExtraInfo,
+        }
+    }
+
+    /// Check if the event is a key press (`WM_KEYDOWN` or `WM_SYSKEYDOWN`)
+    ///
+    pub fn is_keydown(&self) -> bool {
+        self.message == WM_KEYDOWN || self.message == WM_SYSKEYDOWN
+    }
+
+    /// Check if the event is a key release (`WM_KEYUP` or `WM_SYSKEYUP`)
+    ///
+    pub fn is_keyup(&self) -> bool {
+        self.message == WM_KEYUP || self.message == WM_SYSKEYUP || self.flags & LLKHF_UP != 0
+    }
+
+    /// Check if the key is an extended key, like the right hand ALT and CTRL keys
+    ///
+    pub fn is_extended(&self) -> bool {
+        self.flags & LLKHF_EXTENDED != 0
+    }
+
+    /// Check if the event was injected, for example by `SendInput`
+    ///
+    pub fn is_injected(&self) -> bool {
+        self.flags & LLKHF_INJECTED != 0
+    }
+
+    /// Get the scan code of the event together with the extended flag
+    ///
+    pub fn scan(&self) -> ScanCode {
+        ScanCode {
+            code: self.scan_code as u16,
+            extended: self.is_extended(),
+        }
+    }
+
+    /// Check if the event was injected from a process running at a lower integrity level
+    ///
+    pub fn is_lower_il_injected(&self) -> bool {
+        self.flags & LLKHF_LOWER_IL_INJECTED != 0
+    }
+
+    /// Check if the ALT key was pressed when the event occurred
+    ///
+    pub fn is_alt_down(&self) -> bool {
+        self.flags & LLKHF_ALTDOWN != 0
+    }
+}
+
+/// A low-level keyboard hook (`WH_KEYBOARD_LL`) that calls the handler for every keyboard event
+/// in the system. The handler decides if the event is passed on or blocked.
+///
+/// The hook is installed on a dedicated background thread that runs the message loop required
+/// for low-level hooks. The hook is removed when the `KeyboardHook` is dropped.
+///
+/// # Note
+/// Windows removes low-level hooks that take too long to process events, so the handler should
+/// return as quickly as possible.
+///
+/// # Windows API Functions used
+/// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setwindowshookexw>
+///
+pub struct KeyboardHook {
+    thread: HookThread,
+}
+
+impl KeyboardHook {
+    /// Install a new low-level keyboard hook that calls the `handler` for every keyboard event.
+    ///
+    pub fn new(
+        handler: impl FnMut(&KeyboardHookEvent) -> HookAction + Send + 'static,
+    ) -> Result<Self, HkError> {
+        let thread = HookThread::spawn(
+            WH_KEYBOARD_LL,
+            Some(keyboard_hook_proc),
+            move || KEYBOARD_HANDLER.with(|h| *h.borrow_mut() = Some(Box::new(handler))),
+            || KEYBOARD_HANDLER.with(|h| *h.borrow_mut() = None),
+        )?;
+
+        Ok(Self { thread })
+    }
+
+    /// Get the id of the background thread that the hook is running on
+    ///
+    pub fn thread_id(&self) -> u32 {
+        self.thread.thread_id
+    }
+}
+
+/// A stream of all keyboard events in the system, received through a low-level keyboard hook.
+/// The events are never blocked, so the listener doesn't interfere with the input of other
+/// applications.
+///
+pub struct KeyboardListener {
+    _hook: KeyboardHook,
+    rec: Receiver<KeyboardHookEvent>,
+}
+
+impl KeyboardListener {
+    /// Install a low-level keyboard hook and start listening for keyboard events.
+    ///
+    pub fn new() -> Result<Self, HkError> {
+        let (snd, rec) = channel();
+        let hook = KeyboardHook::new(move |event| {
+            let _ = snd.send(*event);
+            HookAction::Pass
+        })?;
+
+        Ok(Self { _hook: hook, rec })
+    }
+
+    /// Block until the next keyboard event is received.
+    ///
+    pub fn recv(&self) -> Option<KeyboardHookEvent> {
+        self.rec.recv().ok()
+    }
+
+    /// Get the next keyboard event if one is available, without blocking.
+    ///
+    pub fn try_recv(&self) -> Option<KeyboardHookEvent> {
+        self.rec.try_recv().ok()
+    }
+
+    /// Get a blocking iterator over the received keyboard events.
+    ///
+    pub fn iter(&self) -> impl Iterator<Item = KeyboardHookEvent> + '_ {
+        self.rec.iter()
+    }
+}
+
+/// The hook procedure for `WH_KEYBOARD_LL` that forwards the events to the thread local handler
+///
+unsafe extern "system" fn keyboard_hook_proc(
+    code: c_int,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if code == HC_ACTION {
+        let raw = &*(lparam as *const KBDLLHOOKSTRUCT);
+        let event = KeyboardHookEvent::from_raw(wparam as u32, raw);
+
+        // The handler might be borrowed already if it causes the hook to be called recursively,
+        // for example by sending input. Recursive events are simply passed on
+        let action = KEYBOARD_HANDLER.with(|h| match h.try_borrow_mut() {
+            Ok(mut handler) => handler.as_mut().map(|handler| handler(&event)),
+            Err(_) => None,
+        });
+
+        if action == Some(HookAction::Block) {
+            return 1;
+        }
+    }
+
+    CallNextHookEx(std::ptr::null_mut(), code, wparam, lparam)
+}
+
+/// Background thread that installs a low-level hook and runs the message loop that is required
+/// for the hook to receive events. The hook is removed and the thread is stopped on drop.
+///
+pub(crate) struct HookThread {
+    pub(crate) thread_id: DWORD,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl HookThread {
+    /// Spawn the hook thread. The `setup` function is called on the new thread before installing
+    /// the hook and `teardown` is called after the hook was removed.
+    ///
+    pub(crate) fn spawn(
+        hook_id: c_int,
+        hook_proc: HOOKPROC,
+        setup: impl FnOnce() + Send + 'static,
+        teardown: fn(),
+    ) -> Result<Self, HkError> {
+        let (snd, rec) = channel();
+
+        let handle = spawn(move || {
+            setup();
+
+            let mut msg = std::mem::MaybeUninit::<MSG>::uninit();
+
+            let hook = unsafe {
+                // Make sure that the message queue for the thread exists, so that the quit message
+                // can be posted to the thread at any point after this
+                PeekMessageW(
+                    msg.as_mut_ptr(),
+                    std::ptr::null_mut(),
+                    WM_USER,
+                    WM_USER,
+                    PM_NOREMOVE,
+                );
+
+                SetWindowsHookExW(hook_id, hook_proc, GetModuleHandleW(std::ptr::null()), 0)
+            };
+
+            if hook.is_null() {
+                teardown();
+                let _ = snd.send(Err(HkError::HookFailed));
+                return;
+            }
+
+            cleanup::track_hook(hook);
+            let _ = snd.send(Ok(unsafe { GetCurrentThreadId() }));
+
+            // Run the message loop until WM_QUIT is received. The hook procedure is called from
+            // within GetMessageW
+            while unsafe { GetMessageW(msg.as_mut_ptr(), std::ptr::null_mut(), 0, 0) } > 0 {}
+
+            cleanup::untrack_hook(hook);
+            unsafe { UnhookWindowsHookEx(hook) };
+            teardown();
+        });
+
+        match rec.recv() {
+            Ok(Ok(thread_id)) => Ok(Self {
+                thread_id,
+                handle: Some(handle),
+            }),
+            Ok(Err(err)) => {
+                let _ = handle.join();
+                Err(err)
+            }
+            Err(_) => {
+                let _ = handle.join();
+                Err(HkError::HookFailed)
+            }
+        }
+    }
+}
+
+impl Drop for HookThread {
+    fn drop(&mut self) {
+        unsafe { PostThreadMessageW(self.thread_id, WM_QUIT, 0, 0) };
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Virtual key code that is unassigned and used to mask the release of the Win and ALT keys. See
+/// `HookMatcher` for details.
+///
+pub(crate) const MASK_KEY: VKey = VKey::CustomKeyCode(0xE8);
+
+/// A key combination that is detected by `HookHotkeys`
+///
+#[derive(Clone)]
+pub(crate) struct HookBinding {
+    /// The main key
+    pub(crate) key: VKey,
+    /// The modifier code of the required modifiers, excluding `ModKey::NoRepeat`
+    pub(crate) modifiers: u32,
+    /// Ignore the automatic key repeat when the key is held down
+    pub(crate) no_repeat: bool,
+    /// Block the key event so that it doesn't reach other applications or the OS
+    pub(crate) block: bool,
+    /// Triggered by tapping the modifier in `modifiers` on its own instead of by the main key
+    pub(crate) modifier_tap: bool,
+    /// The binding is ignored and the key event is passed on while the condition is false
+    pub(crate) condition: Option<Predicate>,
+    /// The binding is ignored and the key event is passed on while the foreground window doesn't
+    /// meet the condition
+    pub(crate) window: Option<WindowCondition>,
+}
+
+impl HookBinding {
+    pub(crate) fn new(key: VKey, key_modifiers: &[ModKey], block: bool) -> Self {
+        Self {
+            key,
+            modifiers: ModKey::combine(key_modifiers) & !ModKey::NoRepeat.to_mod_code(),
+            no_repeat: key_modifiers.contains(&ModKey::NoRepeat),
+            block,
+            modifier_tap: false,
+            condition: None,
+            window: None,
+        }
+    }
+
+    /// Create a binding that is triggered when the modifier key is pressed and released without
+    /// any other key in between. If `block` is true, the default action of the tap, like opening
+    /// the start menu for the Win key, is suppressed.
+    ///
+    pub(crate) fn modifier_tap(modifier: ModKey, block: bool) -> Self {
+        Self {
+            key: modifier.into(),
+            modifiers: modifier.to_mod_code(),
+            no_repeat: true,
+            block,
+            modifier_tap: true,
+            condition: None,
+            window: None,
+        }
+    }
+
+    /// Set the conditions of the binding
+    ///
+    pub(crate) fn with_conditions(
+        mut self,
+        condition: Option<Predicate>,
+        window: Option<WindowCondition>,
+    ) -> Self {
+        self.condition = condition;
+        self.window = window;
+        self
+    }
+
+    /// Check if the conditions of the binding are met. Bindings without conditions are always
+    /// allowed. A condition that panics counts as not met, since the panic must not unwind
+    /// through the keyboard hook.
+    ///
+    pub(crate) fn is_allowed(&self) -> bool {
+        catch_unwind(AssertUnwindSafe(|| {
+            self.window
+                .as_ref()
+                .is_none_or(|window| window.is_met_now())
+                && self.condition.as_ref().is_none_or(|condition| condition())
+        }))
+        .unwrap_or(false)
+    }
+}
+
+/// Flag in the low word of the `lParam` of the hotkey messages posted by the keyboard hook, which
+/// marks triggers caused by the automatic key repeat. This bit is not used by any modifier code.
+///
+pub(crate) const REPEAT_FLAG: u32 = 0x8000;
+
+/// Hotkeys that are detected using a low-level keyboard hook instead of `RegisterHotKey`. When a
+/// key combination is detected, a `WM_HOTKEY` message is posted to the target window, exactly as
+/// windows would do for registered hotkeys. This allows intercepting key combinations that are
+/// reserved by the OS, like most Win key combinations.
+///
+pub(crate) struct HookHotkeys {
+    bindings: Arc<Mutex<HashMap<HotkeyId, HookBinding>>>,
+    _hook: KeyboardHook,
+}
+
+impl HookHotkeys {
+    /// Install the keyboard hook and post the detected hotkeys to the given window
+    ///
+    pub(crate) fn new(hwnd: HWND) -> Result<Self, HkError> {
+        let bindings = Arc::new(Mutex::new(HashMap::new()));
+
+        let mut matcher = HookMatcher {
+            // The window handle is not Send, but it is only used to post messages
+            hwnd: hwnd as usize,
+            bindings: bindings.clone(),
+            pressed: HashSet::new(),
+            swallowed: HashSet::new(),
+            mask_win: false,
+            mask_alt: false,
+            tap_candidate: None,
+        };
+        let hook = KeyboardHook::new(move |event| matcher.handle(event))?;
+
+        Ok(Self {
+            bindings,
+            _hook: hook,
+        })
+    }
+
+    pub(crate) fn insert(&self, id: HotkeyId, binding: HookBinding) {
+        self.bindings
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(id, binding);
+    }
+
+    /// Remove a binding. Returns `false` if no binding was registered for the id
+    ///
+    pub(crate) fn remove(&self, id: HotkeyId) -> bool {
+        self.bindings
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&id)
+            .is_some()
+    }
+
+    /// Replace the conditions of a binding, if a binding is registered for the id
+    ///
+    pub(crate) fn set_conditions(
+        &self,
+        id: HotkeyId,
+        condition: Option<Predicate>,
+        window: Option<WindowCondition>,
+    ) {
+        if let Some(binding) = self
+            .bindings
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_mut(&id)
+        {
+            binding.condition = condition;
+            binding.window = window;
+        }
+    }
+}
+
+/// The hotkey detection state machine that is running inside of the keyboard hook
+///
+/// When a key combination with the Win or ALT key is blocked, the OS would still see the Win or
+/// ALT key being tapped on its own, which opens the start menu or activates the menu bar of the
+/// focused window. To prevent that, an unassigned key is tapped before the release of the Win or
+/// ALT key is passed on, the same way AutoHotkey masks these keys.
+///
+struct HookMatcher {
+    hwnd: usize,
+    bindings: Arc<Mutex<HashMap<HotkeyId, HookBinding>>>,
+    /// Keys that are currently held down, used to detect key repeat
+    pressed: HashSet<VKey>,
+    /// Keys where the key press was blocked, so the matching key release must be blocked as well
+    swallowed: HashSet<VKey>,
+    mask_win: bool,
+    mask_alt: bool,
+    /// Modifier key that was pressed without any other key so far, used to detect modifier taps
+    tap_candidate: Option<VKey>,
+}
+
+impl HookMatcher {
+    fn handle(&mut self, event: &KeyboardHookEvent) -> HookAction {
+        let vk = event.vkey;
+
+        if event.is_keyup() {
+            self.pressed.remove(&vk);
+
+            if self.tap_candidate.take() == Some(vk) {
+                self.modifier_tapped(vk);
+            }
+
+            if (vk == VKey::LWin || vk == VKey::RWin) && std::mem::take(&mut self.mask_win)
+                || (vk == VKey::LMenu || vk == VKey::RMenu) && std::mem::take(&mut self.mask_alt)
+            {
+                let _ = tap_key(MASK_KEY);
+            }
+
+            return if self.swallowed.remove(&vk) {
+                HookAction::Block
+            } else {
+                HookAction::Pass
+            };
+        }
+
+        // Ignore the input sent by this crate
+        if event.extra_info == INJECTED_EXTRA_INFO {
+            return HookAction::Pass;
+        }
+
+        let is_repeat = !self.pressed.insert(vk);
+        let modifiers = current_modifiers();
+
+        // A modifier tap is only detected if no other keys are pressed while the modifier is held
+        if !(is_repeat && self.tap_candidate == Some(vk)) {
+            self.tap_candidate = modifier_of(vk)
+                .filter(|mk| modifiers & !mk.to_mod_code() == 0)
+                .map(|_| vk);
+        }
+
+        let bindings = self.bindings.lock().unwrap_or_else(|e| e.into_inner());
+        let mut action = HookAction::Pass;
+
+        for (id, binding) in bindings.iter() {
+            if binding.modifier_tap || binding.key != vk || binding.modifiers != modifiers {
+                continue;
+            }
+
+            // The key event is passed on unchanged while the conditions are not met
+            if !binding.is_allowed() {
+                continue;
+            }
+
+            if !(is_repeat && binding.no_repeat) {
+                let repeat_flag = if is_repeat { REPEAT_FLAG } else { 0 };
+                let lparam = (modifiers | repeat_flag | (vk.to_vk_code() as u32) << 16) as LPARAM;
+                unsafe { PostMessageW(self.hwnd as HWND, WM_HOTKEY, id.0 as WPARAM, lparam) };
+            }
+
+            if binding.block {
+                action = HookAction::Block;
+            }
+        }
+
+        if action == HookAction::Block {
+            self.swallowed.insert(vk);
+            self.mask_win |= modifiers & ModKey::Win.to_mod_code() != 0;
+            self.mask_alt |= modifiers & ModKey::Alt.to_mod_code() != 0;
+        }
+
+        action
+    }
+
+    /// Post the hotkey messages for all bindings of the modifier that was tapped on its own
+    ///
+    fn modifier_tapped(&mut self, vk: VKey) {
+        let Some(modifier) = modifier_of(vk) else {
+            return;
+        };
+
+        let bindings = self.bindings.lock().unwrap_or_else(|e| e.into_inner());
+
+        for (id, binding) in bindings.iter() {
+            if !binding.modifier_tap
+                || binding.modifiers != modifier.to_mod_code()
+                || !binding.is_allowed()
+            {
+                continue;
+            }
+
+            let lparam = (binding.modifiers | (vk.to_vk_code() as u32) << 16) as LPARAM;
+            unsafe { PostMessageW(self.hwnd as HWND, WM_HOTKEY, id.0 as WPARAM, lparam) };
+
+            // The release of the tapped key is passed on, so the same masking as for blocked
+            // combinations is used to suppress the default action
+            if binding.block {
+                self.mask_win |= modifier == ModKey::Win;
+                self.mask_alt |= modifier == ModKey::Alt;
+            }
+        }
+    }
+}
+
+/// Get the `ModKey` for a modifier key, including the left and right variants.
+///
+fn modifier_of(vk: VKey) -> Option<ModKey> {
+    match vk {
+        VKey::LWin | VKey::RWin => Some(ModKey::Win),
+        VKey::Menu | VKey::LMenu | VKey::RMenu => Some(ModKey::Alt),
+        VKey::Control | VKey::LControl | VKey::RControl => Some(ModKey::Ctrl),
+        VKey::Shift | VKey::LShift | VKey::RShift => Some(ModKey::Shift),
+        _ => None,
+    }
+}
+
+/// Get the modifier code for the currently pressed modifier keys. Since the modifier keys are
+/// never blocked by the hook, the global key state is up to date for them.
+///
+pub(crate) fn current_modifiers() -> u32 {
+    [
+        (ModKey::Alt, VKey::Menu),
+        (ModKey::Ctrl, VKey::Control),
+        (ModKey::Shift, VKey::Shift),
+        (ModKey::Win, VKey::LWin),
+        (ModKey::Win, VKey::RWin),
+    ]
+    .iter()
+    .filter(|(_, vk)| get_global_keystate(*vk))
+    .fold(0, |mods, (mk, _)| mods | mk.to_mod_code())
+}
@@ -0,0 +1,227 @@
+use crate::{
+    error::HkError, keys::*, singlethreaded, ExitReason, HotkeyBackend, HotkeyId, HotkeyInfo,
+    HotkeyManagerImpl, InterruptHandle,
+};
+
+/// A HotkeyManager that detects all hotkeys with a low-level keyboard hook instead of
+/// `RegisterHotKey`. This allows using key combinations that are reserved by windows or already
+/// registered by other applications, like `WIN + E`, in the same way AutoHotKey does.
+///
+/// Since it implements `HotkeyManagerImpl`, it can be used in place of the other HotkeyManagers
+/// without changing the registration code. By default the key events of triggered hotkeys are
+/// blocked, so the reserved action is not executed. This can be changed with `set_block`.
+///
+/// # Note
+/// The extra keys are checked after the hotkey was detected, so the key event is blocked even if
+/// the extra keys are not pressed.
+///
+/// Same as the `singlethreaded::HotkeyManager`, this can't be moved to other threads.
+///
+/// # Windows API Functions used
+/// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setwindowshookexw>
+///
+pub struct HookHotkeyManager<T> {
+    hkm: singlethreaded::HotkeyManager<T>,
+    block: bool,
+}
+
+impl<T> HookHotkeyManager<T> {
+    /// Enable or disable the automatic `ModKey::NoRepeat` for all hotkeys registered after this.
+    /// See `singlethreaded::HotkeyManager::set_no_repeat`.
+    ///
+    pub fn set_no_repeat(&mut self, no_repeat: bool) {
+        self.hkm.set_no_repeat(no_repeat);
+    }
+
+    /// Set if the key events of hotkeys registered after this are blocked from reaching other
+    /// applications. The default is `true`.
+    ///
+    pub fn set_block(&mut self, block: bool) {
+        self.block = block;
+    }
+
+    /// Get the information about all registered hotkeys. See
+    /// `singlethreaded::HotkeyManager::hotkeys`.
+    ///
+    pub fn hotkeys(&self) -> Vec<HotkeyInfo> {
+        self.hkm.hotkeys()
+    }
+
+    /// Suspend all hotkeys until `resume` is called. See
+    /// `singlethreaded::HotkeyManager::suspend`.
+    ///
+    pub fn suspend(&mut self) {
+        self.hkm.suspend();
+    }
+
+    /// Detect all hotkeys again after they were suspended. See
+    /// `singlethreaded::HotkeyManager::resume`.
+    ///
+    pub fn resume(&mut self) -> Vec<(HotkeyId, HkError)> {
+        self.hkm.resume()
+    }
+
+    /// Check if the hotkeys are currently suspended. See
+    /// `singlethreaded::HotkeyManager::is_suspended`.
+    ///
+    pub fn is_suspended(&self) -> bool {
+        self.hkm.is_suspended()
+    }
+}
+
+impl<T> Default for HookHotkeyManager<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> HotkeyManagerImpl<T> for HookHotkeyManager<T> {
+    fn new() -> Self {
+        HookHotkeyManager {
+            hkm: singlethreaded::HotkeyManager::new(),
+            block: true,
+        }
+    }
+
+    fn register_extrakeys(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        extra_keys: &[VKey],
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        self.hkm.register_backend(
+            key,
+            key_modifiers,
+            extra_keys,
+            HotkeyBackend::Hook { block: self.block },
+            None,
+            callback,
+        )
+    }
+
+    fn unregister(&mut self, id: HotkeyId) -> Result<(), HkError> {
+        self.hkm.unregister(id)
+    }
+
+    fn unregister_all(&mut self) -> Result<(), HkError> {
+        self.hkm.unregister_all()
+    }
+
+    fn handle_hotkey(&self) -> Option<T> {
+        self.hkm.handle_hotkey()
+    }
+
+    fn event_loop(&self) -> ExitReason {
+        self.hkm.event_loop()
+    }
+
+    fn interrupt_handle(&self) -> InterruptHandle {
+        self.hkm.interrupt_handle()
+    }
+}
+
+/// A HotkeyManager that registers hotkeys with `RegisterHotKey` when possible and transparently
+/// falls back to a low-level keyboard hook for key combinations that windows refuses to register,
+/// like the ones reserved by windows or already registered by other applications.
+///
+/// Hotkeys that use the fallback block their key events, so they behave the same as hotkeys that
+/// are registered with `RegisterHotKey`.
+///
+/// Same as the `singlethreaded::HotkeyManager`, this can't be moved to other threads.
+///
+/// # Windows API Functions used
+/// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+/// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setwindowshookexw>
+///
+pub struct HybridHotkeyManager<T> {
+    hkm: singlethreaded::HotkeyManager<T>,
+}
+
+impl<T> HybridHotkeyManager<T> {
+    /// Enable or disable the automatic `ModKey::NoRepeat` for all hotkeys registered after this.
+    /// See `singlethreaded::HotkeyManager::set_no_repeat`.
+    ///
+    pub fn set_no_repeat(&mut self, no_repeat: bool) {
+        self.hkm.set_no_repeat(no_repeat);
+    }
+
+    /// Get the information about all registered hotkeys. See
+    /// `singlethreaded::HotkeyManager::hotkeys`.
+    ///
+    pub fn hotkeys(&self) -> Vec<HotkeyInfo> {
+        self.hkm.hotkeys()
+    }
+
+    /// Suspend all hotkeys until `resume` is called. See
+    /// `singlethreaded::HotkeyManager::suspend`.
+    ///
+    pub fn suspend(&mut self) {
+        self.hkm.suspend();
+    }
+
+    /// Detect all hotkeys again after they were suspended. See
+    /// `singlethreaded::HotkeyManager::resume`.
+    ///
+    pub fn resume(&mut self) -> Vec<(HotkeyId, HkError)> {
+        self.hkm.resume()
+    }
+
+    /// Check if the hotkeys are currently suspended. See
+    /// `singlethreaded::HotkeyManager::is_suspended`.
+    ///
+    pub fn is_suspended(&self) -> bool {
+        self.hkm.is_suspended()
+    }
+}
+
+impl<T> Default for HybridHotkeyManager<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> HotkeyManagerImpl<T> for HybridHotkeyManager<T> {
+    fn new() -> Self {
+        HybridHotkeyManager {
+            hkm: singlethreaded::HotkeyManager::new(),
+        }
+    }
+
+    fn register_extrakeys(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        extra_keys: &[VKey],
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        self.hkm.register_backend(
+            key,
+            key_modifiers,
+            extra_keys,
+            HotkeyBackend::RegisterHotKey,
+            Some(HotkeyBackend::Hook { block: true }),
+            callback,
+        )
+    }
+
+    fn unregister(&mut self, id: HotkeyId) -> Result<(), HkError> {
+        self.hkm.unregister(id)
+    }
+
+    fn unregister_all(&mut self) -> Result<(), HkError> {
+        self.hkm.unregister_all()
+    }
+
+    fn handle_hotkey(&self) -> Option<T> {
+        self.hkm.handle_hotkey()
+    }
+
+    fn event_loop(&self) -> ExitReason {
+        self.hkm.event_loop()
+    }
+
+    fn interrupt_handle(&self) -> InterruptHandle {
+        self.hkm.interrupt_handle()
+    }
+}
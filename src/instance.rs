@@ -0,0 +1,201 @@
+use winapi::shared::winerror::ERROR_ALREADY_EXISTS;
+use winapi::um::errhandlingapi::GetLastError;
+use winapi::um::handleapi::CloseHandle;
+use winapi::um::synchapi::CreateMutexW;
+use winapi::um::winnt::HANDLE;
+use winapi::um::winuser::{FindWindowExW, PostMessageW, HWND_MESSAGE, WM_NULL};
+
+use crate::{error::HkError, INSTANCE_COMMAND};
+
+/// The first custom command code, the codes below are used by the predefined commands
+///
+const CUSTOM_BASE: isize = 0x1_0000;
+
+/// A command that is sent from a new instance of a hotkey daemon to the instance that is already
+/// running, see `SingleInstance`.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InstanceCommand {
+    /// Reload the configuration
+    Reload,
+    /// Show the user interface of the running instance, for example a settings window
+    ShowUi,
+    /// An application specific command
+    Custom(u16),
+}
+
+impl InstanceCommand {
+    /// The code of the command in the `lParam` of the message
+    ///
+    pub(crate) fn to_code(self) -> isize {
+        match self {
+            InstanceCommand::Reload => 1,
+            InstanceCommand::ShowUi => 2,
+            InstanceCommand::Custom(command) => CUSTOM_BASE + command as isize,
+        }
+    }
+
+    /// Get the command for the code in the `lParam` of the message. Returns `None` for unknown
+    /// codes.
+    ///
+    pub(crate) fn from_code(code: isize) -> Option<Self> {
+        match code {
+            1 => Some(InstanceCommand::Reload),
+            2 => Some(InstanceCommand::ShowUi),
+            code if (CUSTOM_BASE..=CUSTOM_BASE + u16::MAX as isize).contains(&code) => {
+                Some(InstanceCommand::Custom((code - CUSTOM_BASE) as u16))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The result of `SingleInstance::acquire`
+///
+pub enum Instance {
+    /// This is the only instance. The guard has to be kept alive while the program runs
+    Acquired(SingleInstance),
+    /// Another instance with the same name is already running in the current session
+    AlreadyRunning(RunningInstance),
+}
+
+/// A guard that makes sure that only one instance of a hotkey daemon runs per session. The guard
+/// is a named mutex in the session namespace, so instances in other sessions, like other users
+/// that are logged in at the same time, are not affected.
+///
+/// The running instance can accept commands from new instances with
+/// `HotkeyManager::accept_instance_commands`. A new instance can then forward its purpose, like
+/// reloading the configuration, to the running instance before it exits. The commands are
+/// delivered to the hidden window of the `HotkeyManager`. Every process in the session can send
+/// them, so they should not trigger anything that needs to be protected.
+///
+/// # Example
+/// ```no_run
+/// use windows_hotkeys::{
+///     instance::{Instance, InstanceCommand, SingleInstance},
+///     HotkeyManager, HotkeyManagerImpl,
+/// };
+///
+/// let instance = match SingleInstance::acquire("my-hotkey-daemon").unwrap() {
+///     Instance::Acquired(instance) => instance,
+///     Instance::AlreadyRunning(running) => {
+///         running.send(InstanceCommand::Reload).unwrap();
+///         return;
+///     }
+/// };
+///
+/// let mut hkm = HotkeyManager::<()>::new();
+/// hkm.accept_instance_commands(&instance, |command| match command {
+///     InstanceCommand::Reload => println!("Reload the config"),
+///     _ => (),
+/// });
+/// hkm.event_loop();
+/// ```
+///
+pub struct SingleInstance {
+    mutex: HANDLE,
+    name: String,
+}
+
+impl SingleInstance {
+    /// Try to become the only instance with the given name in the current session. The name
+    /// should be unique to the application, for example its name.
+    ///
+    /// # Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/synchapi/nf-synchapi-createmutexw>
+    ///
+    pub fn acquire(name: &str) -> Result<Instance, HkError> {
+        let mutex_name = to_wide(&format!("Local\\windows-hotkeys-{}", name));
+        let mutex = unsafe { CreateMutexW(std::ptr::null_mut(), 0, mutex_name.as_ptr()) };
+        if mutex.is_null() {
+            return Err(HkError::InstanceFailed(unsafe { GetLastError() }));
+        }
+
+        // The mutex is opened instead of created if it already exists
+        if unsafe { GetLastError() } == ERROR_ALREADY_EXISTS {
+            unsafe { CloseHandle(mutex) };
+            return Ok(Instance::AlreadyRunning(RunningInstance {
+                name: name.to_string(),
+            }));
+        }
+
+        Ok(Instance::Acquired(SingleInstance {
+            mutex,
+            name: name.to_string(),
+        }))
+    }
+
+    /// The name of the instance
+    ///
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The title of the hidden window of the `HotkeyManager` that accepts the commands
+    ///
+    pub(crate) fn window_title(&self) -> String {
+        window_title(&self.name)
+    }
+}
+
+impl Drop for SingleInstance {
+    fn drop(&mut self) {
+        unsafe { CloseHandle(self.mutex) };
+    }
+}
+
+/// The instance that is already running, returned by `SingleInstance::acquire`
+///
+#[derive(Debug, Clone)]
+pub struct RunningInstance {
+    name: String,
+}
+
+impl RunningInstance {
+    /// The name of the instance
+    ///
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Send a command to the running instance. This only queues the command and returns right
+    /// away. Returns `HkError::InstanceNotFound` if the running instance doesn't accept commands.
+    ///
+    /// # Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-findwindowexw>
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-postmessagew>
+    ///
+    pub fn send(&self, command: InstanceCommand) -> Result<(), HkError> {
+        let class = to_wide("Static");
+        let title = to_wide(&window_title(&self.name));
+        let hwnd = unsafe {
+            FindWindowExW(
+                HWND_MESSAGE,
+                std::ptr::null_mut(),
+                class.as_ptr(),
+                title.as_ptr(),
+            )
+        };
+        if hwnd.is_null() {
+            return Err(HkError::InstanceNotFound(self.name.clone()));
+        }
+
+        let posted = unsafe { PostMessageW(hwnd, WM_NULL, INSTANCE_COMMAND, command.to_code()) };
+        match posted {
+            0 => Err(HkError::InstanceNotFound(self.name.clone())),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The title of the hidden window that accepts the commands for the instance with the name
+///
+fn window_title(name: &str) -> String {
+    format!("windows-hotkeys-instance:{}", name)
+}
+
+/// Convert the string into a null terminated UTF-16 string
+///
+pub(crate) fn to_wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(std::iter::once(0)).collect()
+}
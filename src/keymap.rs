@@ -0,0 +1,382 @@
+use std::{fmt, marker::PhantomData, path::Path, sync::Arc};
+
+use serde_json::Value;
+
+use serde::{
+    de::{MapAccess, Visitor},
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{
+    error::HkError,
+    keys::ModKey,
+    validate::{HotkeyDef, LoadReport},
+    HotkeyManagerImpl,
+};
+
+/// The key of the schema version in a keymap file
+///
+const VERSION_KEY: &str = "version";
+
+/// A single binding of a keymap
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Binding {
+    /// The key combination as it was written, like `"ctrl+alt+k"`. The names are parsed the same
+    /// way as for `HotkeyCombo::parse`
+    pub combo: String,
+    /// The name of the action that the key combination triggers
+    pub action: String,
+    /// The line in the keymap file, if it is known. This is only used to point at the binding
+    /// in the `LoadReport`
+    pub line: Option<usize>,
+}
+
+/// A set of key bindings that map key combinations to action names, as they are written in a
+/// keymap config file. The bindings are registered with `register`, which executes a single
+/// dispatcher function with the action name whenever one of the key combinations is pressed.
+///
+/// A keymap file is a flat table of key combinations and action names, either in TOML:
+/// ```toml
+/// "ctrl+alt+k" = "show_window"
+/// "ctrl+alt+h" = "hide_window"
+/// "win+shift+oem_3" = "show_window"
+/// ```
+/// or in JSON:
+/// ```json
+/// { "ctrl+alt+k": "show_window", "ctrl+alt+h": "hide_window" }
+/// ```
+///
+/// Multiple key combinations can trigger the same action. The bindings keep the order of the
+/// file, so the first binding of an action is its primary binding (see
+/// `HotkeyManagerImpl::register_actions`).
+///
+/// The optional `version` key holds the schema version of the file, like `version = 2`. It is
+/// up to the application what its versions mean: `load_with` upgrades files of older versions
+/// with a migration function before the bindings are read, and rejects files of newer versions.
+/// The other loaders ignore the version.
+///
+/// # Example
+/// ```no_run
+/// use windows_hotkeys::{keymap::Keymap, HotkeyManager, HotkeyManagerImpl};
+///
+/// let keymap = Keymap::from_toml(r#"
+///     "ctrl+alt+k" = "greet"
+///     "ctrl+alt+q" = "quit"
+/// "#).unwrap();
+///
+/// let mut hkm = HotkeyManager::new();
+/// let report = keymap.register(&mut hkm, |action| println!("Action: {}", action));
+/// print!("{}", report);
+///
+/// hkm.event_loop();
+/// ```
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Keymap {
+    /// The schema version of the keymap file, if it has one
+    pub version: Option<u32>,
+    pub bindings: Vec<Binding>,
+}
+
+impl Keymap {
+    /// Create an empty keymap
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a binding of the key combination to the action
+    ///
+    pub fn bind(&mut self, combo: impl Into<String>, action: impl Into<String>) {
+        self.bindings.push(Binding {
+            combo: combo.into(),
+            action: action.into(),
+            line: None,
+        });
+    }
+
+    /// Parse a keymap in TOML format. The bindings remember their line in the file.
+    ///
+    pub fn from_toml(keymap: &str) -> Result<Self, HkError> {
+        let Bindings { version, entries } =
+            toml::from_str::<Bindings<toml::Spanned<String>>>(keymap)
+                .map_err(|err| HkError::InvalidKeymap(err.to_string()))?;
+
+        let bindings = entries
+            .into_iter()
+            .map(|(combo, action)| {
+                // The span of the action is on the same line as the combo
+                let line = keymap[..action.span().start].matches('\n').count() + 1;
+                Binding {
+                    combo,
+                    action: action.into_inner(),
+                    line: Some(line),
+                }
+            })
+            .collect();
+
+        Ok(Self { version, bindings })
+    }
+
+    /// Parse a keymap in JSON format
+    ///
+    pub fn from_json(keymap: &str) -> Result<Self, HkError> {
+        serde_json::from_str(keymap).map_err(|err| HkError::InvalidKeymap(err.to_string()))
+    }
+
+    /// Read a keymap file. Files with the `.json` extension are parsed as JSON, all others as
+    /// TOML.
+    ///
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, HkError> {
+        let path = path.as_ref();
+        let keymap = std::fs::read_to_string(path)
+            .map_err(|err| HkError::InvalidKeymap(format!("{}: {}", path.display(), err)))?;
+
+        match path.extension() {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::from_json(&keymap),
+            _ => Self::from_toml(&keymap),
+        }
+    }
+
+    /// Parse a keymap in TOML format and upgrade it to the schema `version` of the application
+    /// first. See `load_with`.
+    ///
+    pub fn from_toml_with(
+        keymap: &str,
+        version: u32,
+        migrate: impl Fn(u32, Value) -> Result<Value, HkError>,
+    ) -> Result<Self, HkError> {
+        let table = toml::from_str::<toml::Value>(keymap)
+            .map_err(|err| HkError::InvalidKeymap(err.to_string()))?;
+        let table =
+            serde_json::to_value(table).map_err(|err| HkError::InvalidKeymap(err.to_string()))?;
+
+        // Files of the current version are parsed directly, so the bindings keep their lines
+        match upgrade(table, version, migrate)? {
+            None => Self::from_toml(keymap),
+            Some(upgraded) => Self::from_value(upgraded),
+        }
+    }
+
+    /// Parse a keymap in JSON format and upgrade it to the schema `version` of the application
+    /// first. See `load_with`.
+    ///
+    pub fn from_json_with(
+        keymap: &str,
+        version: u32,
+        migrate: impl Fn(u32, Value) -> Result<Value, HkError>,
+    ) -> Result<Self, HkError> {
+        let table = serde_json::from_str::<Value>(keymap)
+            .map_err(|err| HkError::InvalidKeymap(err.to_string()))?;
+
+        match upgrade(table, version, migrate)? {
+            None => Self::from_json(keymap),
+            Some(upgraded) => Self::from_value(upgraded),
+        }
+    }
+
+    /// Read a keymap file like `load`, but upgrade it to the schema `version` of the application
+    /// before the bindings are read. Files without a `version` key have the version 0.
+    ///
+    /// The `migrate` function is called with the version of the file and its content, for TOML
+    /// files converted to JSON, and returns the content in the next version. It is called once
+    /// for every version step until the file has the current version, so a file of version 0 is
+    /// migrated to 1 and then to 2 when `version` is 2. Files of a newer version than `version`
+    /// are rejected with `HkError::UnsupportedKeymapVersion`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use windows_hotkeys::keymap::Keymap;
+    ///
+    /// // Version 1 renamed the `show` action to `show_window`
+    /// let keymap = Keymap::load_with("keymap.toml", 1, |_, mut table| {
+    ///     for (_, action) in table.as_object_mut().unwrap() {
+    ///         if *action == "show" {
+    ///             *action = "show_window".into();
+    ///         }
+    ///     }
+    ///     Ok(table)
+    /// })
+    /// .unwrap();
+    /// ```
+    ///
+    pub fn load_with(
+        path: impl AsRef<Path>,
+        version: u32,
+        migrate: impl Fn(u32, Value) -> Result<Value, HkError>,
+    ) -> Result<Self, HkError> {
+        let path = path.as_ref();
+        let keymap = std::fs::read_to_string(path)
+            .map_err(|err| HkError::InvalidKeymap(format!("{}: {}", path.display(), err)))?;
+
+        match path.extension() {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => {
+                Self::from_json_with(&keymap, version, migrate)
+            }
+            _ => Self::from_toml_with(&keymap, version, migrate),
+        }
+    }
+
+    fn from_value(keymap: Value) -> Result<Self, HkError> {
+        serde_json::from_value(keymap).map_err(|err| HkError::InvalidKeymap(err.to_string()))
+    }
+
+    /// Convert the bindings into hotkey definitions, which can be checked with
+    /// `validate::validate` before registering them.
+    ///
+    pub fn defs(&self) -> Vec<HotkeyDef> {
+        self.bindings.iter().map(binding_def).collect()
+    }
+
+    /// Register all bindings with the manager. When one of the key combinations is pressed, the
+    /// `dispatcher` is executed with the action name of the binding.
+    ///
+    /// Every binding is attempted, even if some of them fail. The returned `LoadReport` lists
+    /// the outcome for every binding in order. See `HotkeyManagerImpl::register_actions`.
+    ///
+    pub fn register<T, M>(
+        &self,
+        hkm: &mut M,
+        dispatcher: impl Fn(&str) -> T + Send + Sync + 'static,
+    ) -> LoadReport
+    where
+        M: HotkeyManagerImpl<T>,
+    {
+        let dispatcher = Arc::new(dispatcher);
+        hkm.register_actions(&self.defs(), |action, _| {
+            let dispatcher = dispatcher.clone();
+            let action = action.to_string();
+            move || dispatcher(&action)
+        })
+    }
+}
+
+/// Migrate the content of a keymap file step by step to the schema version. Returns `None` if the
+/// file already has the version
+///
+fn upgrade(
+    mut keymap: Value,
+    version: u32,
+    migrate: impl Fn(u32, Value) -> Result<Value, HkError>,
+) -> Result<Option<Value>, HkError> {
+    let mut found = match keymap.get(VERSION_KEY) {
+        None => 0,
+        Some(found) => found
+            .as_u64()
+            .and_then(|found| u32::try_from(found).ok())
+            .ok_or_else(|| HkError::InvalidKeymap("`version` is not a number".to_string()))?,
+    };
+
+    if found > version {
+        return Err(HkError::UnsupportedKeymapVersion {
+            found,
+            supported: version,
+        });
+    }
+    if found == version {
+        return Ok(None);
+    }
+
+    while found < version {
+        keymap = migrate(found, keymap)?;
+        found += 1;
+    }
+    if let Some(table) = keymap.as_object_mut() {
+        table.insert(VERSION_KEY.to_string(), version.into());
+    }
+    Ok(Some(keymap))
+}
+
+/// Split the key combination of a binding into the key names of a `HotkeyDef`. The leading names
+/// that are modifiers are the modifiers, the first other name is the main key and the remaining
+/// names are extra keys.
+///
+fn binding_def(binding: &Binding) -> HotkeyDef {
+    let mut names = binding.combo.split('+').map(|name| name.trim().to_string());
+    let mut modifiers = Vec::new();
+    let mut key = String::new();
+    for name in names.by_ref() {
+        if ModKey::from_keyname(&name).is_ok() {
+            modifiers.push(name);
+        } else {
+            key = name;
+            break;
+        }
+    }
+
+    HotkeyDef {
+        key,
+        modifiers,
+        extra_keys: names.collect(),
+        action: Some(binding.action.clone()),
+        line: binding.line,
+    }
+}
+
+impl Serialize for Keymap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let len = self.bindings.len() + self.version.is_some() as usize;
+        let mut map = serializer.serialize_map(Some(len))?;
+        if let Some(version) = self.version {
+            map.serialize_entry(VERSION_KEY, &version)?;
+        }
+        for binding in &self.bindings {
+            map.serialize_entry(&binding.combo, &binding.action)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Keymap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let Bindings { version, entries } = Bindings::<String>::deserialize(deserializer)?;
+        let bindings = entries
+            .into_iter()
+            .map(|(combo, action)| Binding {
+                combo,
+                action,
+                line: None,
+            })
+            .collect();
+        Ok(Self { version, bindings })
+    }
+}
+
+/// The entries of a keymap table in the order of the file, without the schema version. A
+/// `HashMap` would lose the order, which decides the primary binding of an action
+///
+struct Bindings<V> {
+    version: Option<u32>,
+    entries: Vec<(String, V)>,
+}
+
+impl<'de, V: Deserialize<'de>> Deserialize<'de> for Bindings<V> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct BindingsVisitor<V>(PhantomData<V>);
+
+        impl<'de, V: Deserialize<'de>> Visitor<'de> for BindingsVisitor<V> {
+            type Value = Bindings<V>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a table of key combinations and action names")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut version = None;
+                let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0));
+                while let Some(key) = map.next_key::<String>()? {
+                    if key == VERSION_KEY {
+                        version = Some(map.next_value()?);
+                    } else {
+                        entries.push((key, map.next_value()?));
+                    }
+                }
+                Ok(Bindings { version, entries })
+            }
+        }
+
+        deserializer.deserialize_map(BindingsVisitor(PhantomData))
+    }
+}
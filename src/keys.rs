@@ -1,14 +1,26 @@
+/// Copies of the `VK_*` and `MOD_*` constants of `winapi::um::winuser`, which only has them when
+/// building for windows. Used by the `parser` feature on other systems
+///
+#[cfg(not(windows))]
+mod codes;
+mod modifiers;
 mod modkey;
 mod vkey;
 
+pub use modifiers::*;
 pub use modkey::*;
 pub use vkey::*;
 
 /// Reexport of all `VK_*` and `MOD_*` constants from the `winapi` crate (`winapi::um::winuser`).
 /// Unless there is an actual special reason for using these codes directly, the variants of the
-/// `VKey` and `ModKey` enums should be used to specify keys instead.
+/// `VKey` and `ModKey` enums should be used to specify keys instead. With the `parser` feature on
+/// other systems, these are copies with the same values.
 ///
 pub mod winapi_keycodes {
+    #[cfg(not(windows))]
+    pub use super::codes::*;
+
+    #[cfg(windows)]
     pub use winapi::um::winuser::{
         VK_ACCEPT, VK_ADD, VK_APPS, VK_ATTN, VK_BACK, VK_BROWSER_BACK, VK_BROWSER_FAVORITES,
         VK_BROWSER_FORWARD, VK_BROWSER_HOME, VK_BROWSER_REFRESH, VK_BROWSER_SEARCH,
@@ -45,5 +57,6 @@ pub mod winapi_keycodes {
         VK_VOLUME_MUTE, VK_VOLUME_UP, VK_XBUTTON1, VK_XBUTTON2, VK_ZOOM,
     };
 
+    #[cfg(windows)]
     pub use winapi::um::winuser::{MOD_ALT, MOD_CONTROL, MOD_NOREPEAT, MOD_SHIFT, MOD_WIN};
 }
@@ -0,0 +1,263 @@
+use std::{
+    fmt::Display,
+    ops::{BitOr, BitOrAssign},
+};
+
+use crate::error::HkError;
+
+use super::ModKey;
+
+/// A set of modifier keys, stored as the modifier code that is used by the winapi. This can be
+/// created by combining `ModKey`s with `|`, for example `ModKey::Ctrl | ModKey::Alt`.
+///
+/// Functions that take a slice of `ModKey`s can be called with `&modifiers.to_vec()`.
+///
+/// See: `fsModifiers` from <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Modifiers(u32);
+
+/// All modifier keys in the order that they are iterated. This is the conventional order of
+/// shortcuts like `CTRL + ALT + DELETE`
+///
+pub(super) const ALL: [ModKey; 5] = [
+    ModKey::Ctrl,
+    ModKey::Alt,
+    ModKey::Shift,
+    ModKey::Win,
+    ModKey::NoRepeat,
+];
+
+impl Modifiers {
+    /// Create an empty set of modifiers
+    ///
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Create the set of modifiers from a modifier code. Bits that don't belong to a `ModKey`
+    /// are dropped.
+    ///
+    pub fn from_mod_code(code: u32) -> Self {
+        Self(code & Self::from(&ALL[..]).0)
+    }
+
+    /// Get the modifier code for usage with winapi functions, like `fsModifiers` of
+    /// `RegisterHotKey`
+    ///
+    pub const fn to_mod_code(&self) -> u32 {
+        self.0
+    }
+
+    /// Check if the modifier is contained in the set
+    ///
+    pub const fn contains(&self, modifier: ModKey) -> bool {
+        self.0 & modifier.to_mod_code() != 0
+    }
+
+    /// Check if the set doesn't contain any modifiers
+    ///
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Add the modifier to the set
+    ///
+    pub fn insert(&mut self, modifier: ModKey) {
+        self.0 |= modifier.to_mod_code();
+    }
+
+    /// Remove the modifier from the set
+    ///
+    pub fn remove(&mut self, modifier: ModKey) {
+        self.0 &= !modifier.to_mod_code();
+    }
+
+    /// Iterate over the contained modifiers in the order `Ctrl`, `Alt`, `Shift`, `Win`,
+    /// `NoRepeat`
+    ///
+    pub fn iter(&self) -> ModifiersIter {
+        ModifiersIter {
+            modifiers: *self,
+            index: 0,
+        }
+    }
+
+    /// Get the contained modifiers as a `Vec`
+    ///
+    pub fn to_vec(&self) -> Vec<ModKey> {
+        self.iter().collect()
+    }
+}
+
+impl From<ModKey> for Modifiers {
+    fn from(modifier: ModKey) -> Self {
+        Self(modifier.to_mod_code())
+    }
+}
+
+impl From<&[ModKey]> for Modifiers {
+    fn from(modifiers: &[ModKey]) -> Self {
+        Self(ModKey::combine(modifiers))
+    }
+}
+
+impl From<Modifiers> for Vec<ModKey> {
+    fn from(modifiers: Modifiers) -> Self {
+        modifiers.to_vec()
+    }
+}
+
+impl FromIterator<ModKey> for Modifiers {
+    fn from_iter<I: IntoIterator<Item = ModKey>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::empty(), |mods, mk| mods | mk)
+    }
+}
+
+impl IntoIterator for Modifiers {
+    type Item = ModKey;
+    type IntoIter = ModifiersIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the modifiers of a `Modifiers` set, created by `Modifiers::iter`
+///
+#[derive(Debug, Clone)]
+pub struct ModifiersIter {
+    modifiers: Modifiers,
+    index: usize,
+}
+
+impl Iterator for ModifiersIter {
+    type Item = ModKey;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(&modifier) = ALL.get(self.index) {
+            self.index += 1;
+            if self.modifiers.contains(modifier) {
+                return Some(modifier);
+            }
+        }
+        None
+    }
+}
+
+impl BitOr for Modifiers {
+    type Output = Modifiers;
+
+    fn bitor(self, rhs: Modifiers) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOr<ModKey> for Modifiers {
+    type Output = Modifiers;
+
+    fn bitor(self, rhs: ModKey) -> Self::Output {
+        self | Modifiers::from(rhs)
+    }
+}
+
+impl BitOr for ModKey {
+    type Output = Modifiers;
+
+    fn bitor(self, rhs: ModKey) -> Self::Output {
+        Modifiers::from(self) | rhs
+    }
+}
+
+impl BitOrAssign<ModKey> for Modifiers {
+    fn bitor_assign(&mut self, rhs: ModKey) {
+        self.insert(rhs);
+    }
+}
+
+impl Display for Modifiers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, modifier) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, " + ")?;
+            }
+            write!(f, "{}", modifier)?;
+        }
+        Ok(())
+    }
+}
+
+/// Conversion into a set of `Modifiers`. This is accepted by `HotkeyManagerImpl::register`, so
+/// the modifiers can be given in the most convenient form:
+///
+/// - A single `ModKey`, like `ModKey::Alt`
+/// - A slice, array or `Vec` of `ModKey`s, like `&[ModKey::Ctrl, ModKey::Alt]`
+/// - A `Modifiers` set, like `ModKey::Ctrl | ModKey::Alt`
+/// - A string with modifier names separated by `+`, like `"ctrl+alt"`. The names are parsed
+///   with `ModKey::from_keyname`. An empty string means no modifiers
+///
+pub trait IntoModifiers {
+    /// Convert into `Modifiers`. This only fails for strings with invalid modifier names
+    ///
+    fn into_modifiers(self) -> Result<Modifiers, HkError>;
+}
+
+impl IntoModifiers for Modifiers {
+    fn into_modifiers(self) -> Result<Modifiers, HkError> {
+        Ok(self)
+    }
+}
+
+impl IntoModifiers for ModKey {
+    fn into_modifiers(self) -> Result<Modifiers, HkError> {
+        Ok(self.into())
+    }
+}
+
+impl IntoModifiers for &[ModKey] {
+    fn into_modifiers(self) -> Result<Modifiers, HkError> {
+        Ok(self.into())
+    }
+}
+
+impl<const N: usize> IntoModifiers for &[ModKey; N] {
+    fn into_modifiers(self) -> Result<Modifiers, HkError> {
+        Ok(self[..].into())
+    }
+}
+
+impl<const N: usize> IntoModifiers for [ModKey; N] {
+    fn into_modifiers(self) -> Result<Modifiers, HkError> {
+        Ok(self[..].into())
+    }
+}
+
+impl IntoModifiers for &Vec<ModKey> {
+    fn into_modifiers(self) -> Result<Modifiers, HkError> {
+        Ok(self[..].into())
+    }
+}
+
+impl IntoModifiers for Vec<ModKey> {
+    fn into_modifiers(self) -> Result<Modifiers, HkError> {
+        Ok(self[..].into())
+    }
+}
+
+impl IntoModifiers for &str {
+    fn into_modifiers(self) -> Result<Modifiers, HkError> {
+        if self.trim().is_empty() {
+            return Ok(Modifiers::empty());
+        }
+
+        let mut offset = 0;
+        self.split('+')
+            .map(|part| {
+                let name = part.trim();
+                let position = offset + part.len() - part.trim_start().len();
+                offset += part.len() + 1;
+                ModKey::from_keyname(name).map_err(|err| err.at_position(position))
+            })
+            .collect()
+    }
+}
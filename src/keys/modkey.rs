@@ -1,6 +1,8 @@
-use std::fmt::Display;
+use std::{cmp::Ordering, fmt::Display};
 
-use crate::{error::HkError, VKey};
+use crate::error::HkError;
+
+use super::{modifiers::ALL, VKey};
 
 /// Modifier Key for hotkeys.
 ///
@@ -33,7 +35,7 @@ impl ModKey {
             "SHIFT" => ModKey::Shift,
             "WIN" | "WINDOWS" | "SUPER" => ModKey::Win,
             "NOREPEAT" | "NO_REPEAT" => ModKey::NoRepeat,
-            val => return Err(HkError::InvalidKey(val.to_string())),
+            _ => return Err(HkError::invalid_key(val.to_string())),
         })
     }
 
@@ -42,7 +44,7 @@ impl ModKey {
     /// See: `fsModifiers` from <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
     ///
     pub const fn to_mod_code(&self) -> u32 {
-        use winapi::um::winuser::*;
+        use super::winapi_keycodes::*;
 
         match self {
             ModKey::Alt => MOD_ALT as u32,
@@ -53,11 +55,44 @@ impl ModKey {
         }
     }
 
+    /// Get the `ModKey`s that are contained in a modifier code, for example the low word of the
+    /// `lParam` of a `WM_HOTKEY` message.
+    ///
+    /// See: <https://learn.microsoft.com/en-us/windows/win32/inputdev/wm-hotkey>
+    ///
+    pub fn from_mod_code(code: u32) -> Vec<ModKey> {
+        [
+            ModKey::Alt,
+            ModKey::Ctrl,
+            ModKey::Shift,
+            ModKey::Win,
+            ModKey::NoRepeat,
+        ]
+        .into_iter()
+        .filter(|mk| code & mk.to_mod_code() != 0)
+        .collect()
+    }
+
     /// Combine multiple `ModKey`s using bitwise OR
     ///
     pub(crate) fn combine(keys: &[ModKey]) -> u32 {
         keys.iter().fold(0, |a, b| a | b.to_mod_code())
     }
+
+    /// Add or remove `ModKey::NoRepeat`, so that the hotkey repeats exactly if `repeat` is true
+    ///
+    #[cfg(windows)]
+    pub(crate) fn with_repeat(keys: &[ModKey], repeat: bool) -> Vec<ModKey> {
+        let mut keys: Vec<_> = keys
+            .iter()
+            .copied()
+            .filter(|&mk| mk != ModKey::NoRepeat)
+            .collect();
+        if !repeat {
+            keys.push(ModKey::NoRepeat);
+        }
+        keys
+    }
 }
 
 impl Display for ModKey {
@@ -73,6 +108,30 @@ impl Display for ModKey {
     }
 }
 
+impl ModKey {
+    /// The position of the modifier in the conventional order of shortcuts
+    ///
+    fn sort_index(&self) -> usize {
+        ALL.iter().position(|mk| mk == self).unwrap_or(ALL.len())
+    }
+}
+
+/// Modifier keys are ordered in the conventional order of shortcuts like `CTRL + ALT + DELETE`:
+/// `Ctrl`, `Alt`, `Shift`, `Win` and finally `NoRepeat`. This is the same order that `Modifiers`
+/// iterates in and that key combinations are displayed in.
+///
+impl Ord for ModKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sort_index().cmp(&other.sort_index())
+    }
+}
+
+impl PartialOrd for ModKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl From<ModKey> for VKey {
     fn from(mk: ModKey) -> VKey {
         match mk {
@@ -1,4 +1,4 @@
-use std::{fmt::Display, hash::Hash};
+use std::{cmp::Ordering, fmt::Display, hash::Hash};
 
 use crate::error::HkError;
 
@@ -357,7 +357,7 @@ impl VKey {
     /// Get the actual windows virtual keycode for the `VKey` for usage with winapi functions
     ///
     pub const fn to_vk_code(&self) -> i32 {
-        use winapi::um::winuser::*;
+        use super::winapi_keycodes::*;
         match self {
             VKey::Back => VK_BACK,
             VKey::Tab => VK_TAB,
@@ -521,19 +521,19 @@ impl VKey {
     /// Returns the VK code as i32 on success (a key representation was recognized).
     ///
     /// - For single character strings the ASCII code is used as VK, this is used to represent
-    /// alphanumeric keys
+    ///   alphanumeric keys
     /// - Many of the most common VKs are represented by their constant name. For example
-    /// VK_SPACE => spacebar key
+    ///   VK_SPACE => spacebar key
     /// - Any other key can be represented by directly specifying the VK keycode value in 2
-    /// digit hex representation. For example 0x08 == VK_TAB (Tab key)
+    ///   digit hex representation. For example 0x08 == VK_TAB (Tab key)
     ///
     /// See <https://docs.microsoft.com/en-us/windows/win32/inputdev/virtual-key-codes>
     ///
-    pub fn from_keyname(val: &str) -> Result<Self, HkError> {
-        let val = val.to_ascii_uppercase();
+    pub fn from_keyname(name: &str) -> Result<Self, HkError> {
+        let val = name.to_ascii_uppercase();
 
         // Single letter => Simply use the ASCII Code
-        if val.as_bytes().len() == 1 {
+        if val.len() == 1 {
             let val = val.as_bytes()[0];
             if val.is_ascii_uppercase() || val.is_ascii_digit() {
                 return Ok(Self::CustomKeyCode(val as i32));
@@ -545,7 +545,7 @@ impl VKey {
             if let Ok(val) = i32::from_str_radix(&val[2..], 16) {
                 return Ok(Self::CustomKeyCode(val));
             } else {
-                return Err(HkError::InvalidKey(val));
+                return Err(HkError::invalid_key(name.to_string()));
             }
         }
 
@@ -668,14 +668,83 @@ impl VKey {
             "PA1" => Self::Pa1,
             "OEM_CLEAR" => Self::OemClear,
 
-            _ => return Err(HkError::InvalidKey(val)),
+            _ => return Err(HkError::invalid_key(name.to_string())),
         })
     }
+
+    /// Get the hardware scan code of the key with the current keyboard layout. Returns `None` if
+    /// the key has no scan code, like the mouse buttons.
+    ///
+    /// Keys that share a scan code with a key of the main block, like the arrow keys and the right
+    /// CTRL key, are marked as extended.
+    ///
+    /// # Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-mapvirtualkeyw>
+    ///
+    #[cfg(windows)]
+    pub fn to_scan_code(&self) -> Option<ScanCode> {
+        use winapi::um::winuser::{MapVirtualKeyW, MAPVK_VK_TO_VSC_EX};
+
+        let scan = unsafe { MapVirtualKeyW(self.to_vk_code() as u32, MAPVK_VK_TO_VSC_EX) };
+        match scan {
+            0 => None,
+            scan => Some(ScanCode {
+                code: (scan & 0xff) as u16,
+                extended: scan & 0xff00 != 0,
+            }),
+        }
+    }
+
+    /// Get the key for a hardware scan code with the current keyboard layout. Returns `None` if
+    /// the scan code doesn't belong to a key.
+    ///
+    /// The extended flag distinguishes keys with the same scan code, for example the arrow keys
+    /// from the numpad keys. The result is always a `CustomKeyCode`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-mapvirtualkeyw>
+    ///
+    #[cfg(windows)]
+    pub fn from_scan_code(scan: ScanCode) -> Option<Self> {
+        use winapi::um::winuser::{MapVirtualKeyW, MAPVK_VSC_TO_VK_EX};
+
+        let code = scan.code as u32 | if scan.extended { 0xe000 } else { 0 };
+        match unsafe { MapVirtualKeyW(code, MAPVK_VSC_TO_VK_EX) } {
+            0 => None,
+            vk => Some(Self::CustomKeyCode(vk as i32)),
+        }
+    }
+
+    /// Check if the key is an extended key, meaning that its scan code is prefixed with `0xE0`.
+    /// This is the case for example for the arrow keys, the navigation block and the right CTRL
+    /// and ALT keys. Input for extended keys needs the `KEYEVENTF_EXTENDEDKEY` flag.
+    ///
+    /// # Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-mapvirtualkeyw>
+    ///
+    #[cfg(windows)]
+    pub fn is_extended(&self) -> bool {
+        self.to_scan_code().is_some_and(|scan| scan.extended)
+    }
+}
+
+/// A hardware scan code together with the extended key flag. The low byte of the scan code is
+/// the same for some keys, like the arrow keys and the numpad keys, which are only distinguished
+/// by the extended flag.
+///
+/// See: <https://learn.microsoft.com/en-us/windows/win32/inputdev/about-keyboard-input#scan-codes>
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScanCode {
+    /// The scan code without the `0xE0` prefix
+    pub code: u16,
+    /// If the key is an extended key with the `0xE0` prefix
+    pub extended: bool,
 }
 
 impl Display for VKey {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        use winapi::um::winuser::*;
+        use super::winapi_keycodes::*;
 
         let code = self.to_vk_code();
 
@@ -819,6 +888,22 @@ impl PartialEq<VKey> for VKey {
 
 impl Eq for VKey {}
 
+/// Virtual keys are ordered by their keycode, the same value that is used for equality. This
+/// makes the order stable across the variants, so `VKey::A` and `VKey::CustomKeyCode(0x41)` sort
+/// the same and the order doesn't change between versions of this crate.
+///
+impl Ord for VKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.to_vk_code().cmp(&other.to_vk_code())
+    }
+}
+
+impl PartialOrd for VKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Hash for VKey {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.to_vk_code().hash(state);
@@ -829,7 +914,7 @@ impl TryInto<ModKey> for VKey {
     type Error = ();
 
     fn try_into(self) -> Result<ModKey, Self::Error> {
-        use winapi::um::winuser::*;
+        use super::winapi_keycodes::*;
 
         Ok(match self.to_vk_code() {
             VK_MENU | VK_LMENU | VK_RMENU => ModKey::Alt,
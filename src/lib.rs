@@ -1,15 +1,75 @@
-#[cfg(not(target_os = "windows"))]
-compile_error!("Only supported on windows");
+#[cfg(not(any(target_os = "windows", feature = "parser")))]
+compile_error!("Only supported on windows, or with just the `parser` feature on other systems");
 
 #[cfg(windows)]
+pub mod cleanup;
+#[cfg(any(windows, feature = "parser"))]
+pub mod combo;
+#[cfg(all(windows, feature = "command"))]
+pub mod command;
+#[cfg(windows)]
+pub mod compose;
+#[cfg(windows)]
+pub mod condition;
+#[cfg(windows)]
+pub mod diagnostics;
+#[cfg(all(windows, feature = "dsl"))]
+pub mod dsl;
+#[cfg(any(windows, feature = "parser"))]
 pub mod error;
+#[cfg(all(windows, feature = "export"))]
+pub mod export;
+#[cfg(windows)]
+pub mod feedback;
 #[cfg(windows)]
+pub mod gesture;
+#[cfg(windows)]
+pub mod hook;
+#[cfg(windows)]
+pub mod hookmanager;
+#[cfg(windows)]
+pub mod instance;
+#[cfg(all(windows, feature = "keymap"))]
+pub mod keymap;
+#[cfg(any(windows, feature = "parser"))]
 pub mod keys;
+#[cfg(windows)]
+mod macros;
+#[cfg(windows)]
+pub mod metrics;
+#[cfg(windows)]
+mod mode;
+#[cfg(windows)]
+pub mod mouse;
+#[cfg(windows)]
+pub mod options;
+#[cfg(windows)]
+pub mod panic_button;
+#[cfg(windows)]
+pub mod registry;
+#[cfg(windows)]
+pub mod repeat;
+#[cfg(windows)]
+pub mod scope;
+#[cfg(windows)]
+pub mod send;
+#[cfg(windows)]
+pub mod sequence;
+#[cfg(all(any(windows, feature = "parser"), feature = "serde"))]
+mod serialize;
 
+#[cfg(all(windows, feature = "threadsafe"))]
+pub mod sharded;
 #[cfg(windows)]
 pub mod singlethreaded;
+#[cfg(all(windows, feature = "test-support"))]
+pub mod test_support;
 #[cfg(all(windows, feature = "threadsafe"))]
 pub mod threadsafe;
+#[cfg(windows)]
+pub mod validate;
+#[cfg(windows)]
+pub mod window;
 
 #[cfg(all(windows, feature = "threadsafe"))]
 pub use threadsafe::HotkeyManager;
@@ -17,31 +77,680 @@ pub use threadsafe::HotkeyManager;
 #[cfg(all(windows, not(feature = "threadsafe")))]
 pub use singlethreaded::HotkeyManager;
 
+#[cfg(all(windows, feature = "macros"))]
+pub use windows_hotkeys_macros::hotkey;
+
+#[cfg(windows)]
+use std::{
+    any::Any,
+    cell::Cell,
+    sync::{atomic::AtomicU64, Arc, Mutex},
+};
+
 #[cfg(windows)]
 use winapi::shared::windef::HWND;
 #[cfg(windows)]
-use winapi::um::winuser::{GetAsyncKeyState, PostMessageW, WM_NULL};
+use winapi::um::winuser::{GetAsyncKeyState, PostMessageW, MSG, WM_NULL};
 
 #[cfg(windows)]
-use crate::{error::HkError, keys::*};
+use crate::{
+    combo::HotkeyCombo,
+    condition::{AccessibilityCondition, CursorRegion, SessionKind, WindowCondition},
+    error::HkError,
+    gesture::Stroke,
+    instance::InstanceCommand,
+    keys::*,
+    options::{RegisterOptions, TriggerLimit},
+    repeat::RepeatPolicy,
+    scope::HotkeyScope,
+    validate::{HotkeyDef, LoadReport},
+    window::{trigger_window, ForegroundWindow},
+};
+
+/// The `wParam` of the `WM_NULL` message that is posted by `InterruptHandle::interrupt_after_drain`
+///
+#[cfg(windows)]
+const DRAIN_INTERRUPT: usize = 1;
+
+/// The `wParam` of the `WM_NULL` message that delivers an `InstanceCommand` from another process
+///
+#[cfg(windows)]
+const INSTANCE_COMMAND: usize = 2;
 
 /// Identifier of a registered hotkey. This is returned when registering a hotkey and can be used
 /// to unregister it later.
 ///
-#[cfg(windows)]
+#[cfg(any(windows, feature = "parser"))]
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 pub struct HotkeyId(i32);
 
+/// Information about a registered hotkey.
+///
+#[cfg(windows)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HotkeyInfo {
+    /// The id of the hotkey
+    pub id: HotkeyId,
+    /// The main key of the hotkey
+    pub key: VKey,
+    /// The modifier keys of the hotkey, including `ModKey::NoRepeat` if it was applied
+    pub modifiers: Vec<ModKey>,
+    /// The additional keys that need to be pressed for the callback to be executed
+    pub extra_keys: Vec<VKey>,
+    /// The label that was set with `RegisterOptions::label`
+    pub label: Option<String>,
+    /// The description that was set with `RegisterOptions::description`
+    pub description: Option<String>,
+    /// The named group of the hotkey, see `RegisterOptions::group`
+    pub group: Option<String>,
+    /// The mode that the hotkey belongs to, see `RegisterOptions::mode`
+    pub mode: Option<String>,
+    /// The action name of hotkeys that were registered with `register_action`
+    pub action: Option<String>,
+    /// If the hotkey is enabled, see `set_enabled`. This is the state of the hotkey itself, a
+    /// disabled named group doesn't change it
+    pub enabled: bool,
+}
+
+/// A message that was received by the event loop of a `HotkeyManager`. This is passed to the
+/// message tap set with `set_message_tap`.
+///
+#[cfg(windows)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TappedMessage {
+    /// A `WM_HOTKEY` message. `registered` is false if there is no hotkey registered for the id
+    Hotkey {
+        id: HotkeyId,
+        registered: bool,
+        lparam: isize,
+    },
+    /// A `WM_NULL` message, which is used to interrupt the event loop
+    Interrupt,
+    /// Any other message
+    Other {
+        message: u32,
+        wparam: usize,
+        lparam: isize,
+    },
+}
+
+/// An event about a triggered hotkey. This is passed to the event sink set with
+/// `set_event_sink`.
+///
+#[cfg(windows)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HotkeyEvent {
+    /// The id of the triggered hotkey
+    pub id: HotkeyId,
+    /// The key combination that triggered the hotkey. For hotkeys with aliases, this is the
+    /// alias that was pressed
+    pub combo: HotkeyCombo,
+    /// The modifier keys that were actually held when the hotkey was triggered, as reported by
+    /// windows in the hotkey message. This avoids querying the key state after the trigger
+    pub modifiers: Vec<ModKey>,
+    /// The distance that the wheel was rotated for mouse wheel hotkeys, in multiples or fractions
+    /// of `WHEEL_DELTA` (120). Positive values are scrolling up or to the right. `None` for all
+    /// other hotkeys
+    pub wheel_delta: Option<i16>,
+    /// True if the trigger was caused by holding the hotkey down instead of a fresh press
+    pub is_repeat: bool,
+    /// The number of repeats since the hotkey was pressed, `0` for the initial press
+    pub repeat_index: u32,
+    /// The label of the triggered hotkey, see `RegisterOptions::label`
+    pub label: Option<String>,
+    /// The window that was in the foreground when the hotkey was triggered. `None` if there was
+    /// no foreground window
+    pub foreground: Option<ForegroundWindow>,
+    /// The number of executed triggers since the hotkey was registered or enabled again,
+    /// including this one if it was executed. Always `0` for sequences
+    pub trigger_count: u64,
+    /// The number of triggers of the hotkey that are summarized by this event, when the event sink
+    /// is throttled with `set_event_throttle`. The other fields are the ones of the latest of
+    /// these triggers. `0` for events that are passed on directly
+    pub merged: u32,
+    /// What happened with the trigger
+    pub outcome: TriggerOutcome,
+}
+
+/// The details of a hotkey trigger that are passed to the callbacks registered with
+/// `register_with_trigger`, so the callback doesn't need to look them up separately.
+///
+#[cfg(windows)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TriggerContext {
+    /// The id of the triggered hotkey
+    pub id: HotkeyId,
+    /// The key combination of the triggered hotkey
+    pub combo: HotkeyCombo,
+    /// The time of the trigger in milliseconds since the system was started, as reported by
+    /// windows in the hotkey message. This can be compared with `GetTickCount`
+    pub time: u32,
+    /// The cursor position in screen coordinates at the time of the trigger
+    pub cursor: (i32, i32),
+}
+
+/// The outcome of a hotkey trigger.
+///
+#[cfg(windows)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TriggerOutcome {
+    /// All conditions were met and the callback was executed
+    Executed,
+    /// The callback was not executed because one of the conditions was not met
+    Rejected(RejectReason),
+}
+
+/// The reason why a hotkey trigger was rejected.
+///
+#[cfg(windows)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RejectReason {
+    /// The listed extra keys were not pressed
+    MissingExtraKeys(Vec<VKey>),
+    /// The cursor was outside of the required region
+    CursorOutsideRegion,
+    /// The hotkey is restricted to a different kind of session
+    WrongSession,
+    /// The accessibility condition of the hotkey was not met
+    AccessibilityCondition,
+    /// The hotkey is disabled
+    Disabled,
+    /// The named group of the hotkey is disabled
+    GroupDisabled,
+    /// The condition function of the hotkey returned false
+    ConditionFailed,
+    /// The foreground window didn't meet the window condition of the hotkey
+    WrongWindow,
+}
+
+/// What a `HotkeyManager` does when a callback panics. Without a policy, the panic unwinds out of
+/// the event loop, the same as for any other function call.
+///
+/// The policy only applies to callbacks that are executed by the manager itself, not to callbacks
+/// that are handed to a dispatcher.
+///
+#[cfg(windows)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PanicPolicy {
+    /// Abort the process immediately
+    Abort,
+    /// Ignore the panic and continue with the next hotkey
+    Ignore,
+    /// Disable the hotkey whose callback panicked, like `set_enabled(id, false)`, and continue
+    /// with the next hotkey. Sequences can't be disabled, so this is the same as `Ignore` for them
+    DisableHotkey,
+    /// Stop the event loop, which then returns `ExitReason::CallbackPanicked`
+    StopLoop,
+}
+
+/// Why the event loop of a `HotkeyManager` stopped. This is returned by `event_loop`.
+///
+#[cfg(windows)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExitReason {
+    /// The loop was interrupted with an `InterruptHandle`
+    Interrupted,
+    /// A callback requested the loop to stop with `stop_event_loop`
+    CallbackRequested,
+    /// A callback panicked with `PanicPolicy::StopLoop`
+    CallbackPanicked,
+    /// A `WM_QUIT` message was posted to the thread, for example with `PostQuitMessage`
+    Quit,
+    /// The hidden window of the manager was destroyed, so no more hotkeys can be received
+    WindowDestroyed,
+    /// The windows session was ending when the loop stopped. The hidden window is a message-only
+    /// window, which doesn't receive the end session messages, so this is only reported if the
+    /// loop was interrupted, quit or lost its window while the session was shutting down
+    SessionEnding,
+}
+
+#[cfg(windows)]
+thread_local! {
+    /// Set by `stop_event_loop` and checked by the event loop running on the same thread
+    static STOP_REQUESTED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Function that is called for every message received by the event loop
+///
+#[cfg(windows)]
+type MessageTap = Box<dyn Fn(&TappedMessage) + Send + 'static>;
+
+/// Function that is called with the events about triggered hotkeys
+///
+#[cfg(windows)]
+type EventSink = Box<dyn Fn(&HotkeyEvent) + Send + 'static>;
+
+/// Function that is called with the formatted key combination and the label of every executed
+/// hotkey
+///
+#[cfg(windows)]
+type TriggerFeedback = Box<dyn Fn(&str, Option<&str>) + Send + 'static>;
+
+/// Function that binds the action handler that is set with `set_action_handler` to the action name
+/// and the event of a trigger. The returned callback is executed or handed to a dispatcher like
+/// any other callback.
+///
+#[cfg(windows)]
+type ActionHandler<T> = Box<dyn Fn(String, HotkeyEvent) -> SharedCallback<T> + Send + 'static>;
+
+/// Function that receives the errors of a single hotkey, see `set_error_handler`
+///
+#[cfg(windows)]
+type ErrorHandler = Arc<dyn Fn(&HkError) + Send + Sync + 'static>;
+
+/// Function that receives the errors of all hotkeys without an error handler of their own, along
+/// with the id of the hotkey, see `set_error_sink`
+///
+#[cfg(windows)]
+type ErrorSink = Arc<dyn Fn(HotkeyId, &HkError) + Send + Sync + 'static>;
+
+/// The error of a callback that was registered with `register_fallible`
+///
+#[cfg(windows)]
+type CallbackError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Function that creates the callback for a trigger of a hotkey that was registered with
+/// `register_with_trigger`, see `wrap_trigger_callback`
+///
+#[cfg(windows)]
+type TriggerCallback<T> = Box<dyn Fn(TriggerContext) -> SharedCallback<T> + Send + 'static>;
+
+/// Function that is called with the commands from other instances, see `SingleInstance`
+///
+#[cfg(windows)]
+type InstanceHandler = Box<dyn Fn(InstanceCommand) + Send + 'static>;
+
+/// Observer function that is called when hotkeys are registered or unregistered
+///
+#[cfg(windows)]
+type RegistrationObserver = Box<dyn Fn(&HotkeyInfo) + Send + 'static>;
+
+/// The context that is passed to the callbacks registered with `register_with_context`
+///
+#[cfg(windows)]
+type SharedContext = Arc<dyn Any + Send + Sync + 'static>;
+
+/// Get the context of a manager as the type `C`. Returns `HkError::NoContext` if no context is set
+/// or if it has a different type.
+///
+#[cfg(windows)]
+fn downcast_context<C: Send + Sync + 'static>(
+    context: &Option<SharedContext>,
+) -> Result<Arc<C>, HkError> {
+    context
+        .clone()
+        .and_then(|context| context.downcast::<C>().ok())
+        .ok_or(HkError::NoContext(std::any::type_name::<C>()))
+}
+
+/// The mechanism that is used to detect a hotkey
+///
+#[cfg(windows)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum HotkeyBackend {
+    /// Registered with windows using `RegisterHotKey`
+    RegisterHotKey,
+    /// Detected by a low-level keyboard hook. If `block` is true, the key event is blocked
+    Hook { block: bool },
+    /// Detected by a low-level keyboard hook when the modifier is tapped on its own. The default
+    /// action of the tap is always suppressed
+    ModifierTap,
+    /// Detected by a low-level mouse hook, with a mouse button as the main key. If `block` is
+    /// true, the mouse button event is blocked
+    Mouse { block: bool },
+    /// A mouse gesture that is drawn while the main key, a mouse button, is held
+    Gesture,
+}
+
 /// HotkeyCallback contains the callback function and a list of extra_keys that need to be pressed
 /// together with the hotkey when executing the callback.
 ///
 #[cfg(windows)]
 struct HotkeyCallback<T> {
-    /// Callback function to execute  when the hotkey & extrakeys match
-    callback: Box<dyn Fn() -> T + 'static>,
+    /// What to execute when the hotkey & extrakeys match
+    target: HotkeyTarget<T>,
+    /// The main key of the hotkey
+    key: VKey,
+    /// The modifiers that the hotkey was registered with
+    modifiers: Vec<ModKey>,
+    /// How the hotkey is detected
+    backend: HotkeyBackend,
     /// List of additional VKeys that are required to be pressed to execute
     /// the callback
     extra_keys: Vec<VKey>,
+    /// Screen region that the cursor must be in to execute the callback
+    cursor_region: Option<CursorRegion>,
+    /// The kind of session that the hotkey is restricted to
+    session: Option<SessionKind>,
+    /// The accessibility setting that the hotkey depends on
+    accessibility: Option<AccessibilityCondition>,
+    /// The foreground window that the hotkey is restricted to
+    window: Option<WindowCondition>,
+    /// Function that decides if the hotkey is currently allowed to trigger
+    condition: Option<Predicate>,
+    /// Policy for repeating the callback while the hotkey is held down
+    repeat: Option<RepeatPolicy>,
+    /// Separate callback for pressing the hotkey twice in a short time
+    double_tap: Option<DoubleTap<T>>,
+    /// The time that the hotkey has to be held down before the callback is executed
+    long_press: Option<std::time::Duration>,
+    /// The strokes of a mouse gesture, which are drawn while the main key is held
+    gesture: Option<Vec<Stroke>>,
+    /// A name for the hotkey, reported in the `HotkeyInfo`, events and errors
+    label: Option<String>,
+    /// A longer explanation of the hotkey, reported in the `HotkeyInfo`
+    description: Option<String>,
+    /// The number of executed triggers, shared by all aliases of an alias group
+    triggers: Arc<AtomicU64>,
+    /// Disabled hotkeys stay registered, but their callback is not executed
+    enabled: Cell<bool>,
+    /// The named group that the hotkey belongs to
+    group: Option<String>,
+    /// False while the named group of the hotkey is disabled. This is kept separately from
+    /// `enabled`, so enabling the group doesn't enable hotkeys that were disabled on their own
+    group_enabled: Cell<bool>,
+    /// The mode that the hotkey belongs to. The hotkey is only registered while the mode is active
+    mode: Option<String>,
+    /// Unregister the hotkey while the game mode detects a fullscreen application
+    suppress_in_fullscreen: Cell<bool>,
+    /// Receives the errors that only concern this hotkey, see `set_error_handler`
+    on_error: Option<ErrorHandler>,
+    /// Unregister or disable the hotkey after a number of triggers, see `set_trigger_limit`
+    limit: Option<TriggerLimit>,
+    /// True after the hotkey was unregistered by its trigger limit. Spent hotkeys stay
+    /// unregistered
+    spent: Cell<bool>,
+    /// The hotkey was registered with `register_once`, so its callback can only be executed once
+    /// and its trigger limit can't be changed
+    once: bool,
+}
+
+/// What a hotkey executes when it is triggered
+///
+#[cfg(windows)]
+enum HotkeyTarget<T> {
+    /// A regular callback function
+    Callback(SharedCallback<T>),
+    /// Creates the callback with the context of the trigger, for hotkeys registered with
+    /// `register_with_trigger`
+    Trigger(TriggerCallback<T>),
+    /// The action name of hotkeys registered with `register_action`, which are executed by the
+    /// action handler
+    Action(String),
+}
+
+/// The double-tap trigger of a hotkey
+///
+#[cfg(windows)]
+struct DoubleTap<T> {
+    /// The maximum time between the two presses
+    window: std::time::Duration,
+    /// Callback function to execute instead of the regular callback on a double-tap
+    callback: SharedCallback<T>,
+}
+
+#[cfg(windows)]
+impl<T> HotkeyCallback<T> {
+    /// Create the handler of a hotkey for the key, which is registered with `RegisterHotKey`
+    /// without modifiers or any other settings
+    ///
+    fn new(callback: SharedCallback<T>, key: VKey) -> Self {
+        Self::for_target(HotkeyTarget::Callback(callback), key)
+    }
+
+    /// Same as `new`, but for hotkeys that don't execute a regular callback
+    ///
+    fn for_target(target: HotkeyTarget<T>, key: VKey) -> Self {
+        Self {
+            target,
+            key,
+            modifiers: Vec::new(),
+            backend: HotkeyBackend::RegisterHotKey,
+            extra_keys: Vec::new(),
+            cursor_region: None,
+            session: None,
+            accessibility: None,
+            window: None,
+            condition: None,
+            repeat: None,
+            double_tap: None,
+            long_press: None,
+            gesture: None,
+            label: None,
+            description: None,
+            triggers: Arc::default(),
+            enabled: Cell::new(true),
+            group: None,
+            group_enabled: Cell::new(true),
+            mode: None,
+            suppress_in_fullscreen: Cell::new(false),
+            on_error: None,
+            limit: None,
+            spent: Cell::new(false),
+            once: false,
+        }
+    }
+
+    /// Apply the per-hotkey options of a registration. The modifiers are not changed, since
+    /// `RegisterOptions::no_repeat` depends on the settings of the manager
+    ///
+    fn with_options(mut self, options: RegisterOptions) -> Self {
+        self.extra_keys = options.extra_keys;
+        self.cursor_region = options.cursor_region;
+        self.session = options.session;
+        self.accessibility = options.accessibility;
+        self.window = options.window;
+        self.label = options.label;
+        self.description = options.description;
+        self.enabled = Cell::new(options.enabled);
+        self.group = options.group;
+        self.mode = options.mode;
+        self.suppress_in_fullscreen = Cell::new(options.suppress_in_fullscreen);
+        self.limit = options.trigger_limit;
+        self
+    }
+
+    /// Get the public information about the hotkey
+    ///
+    fn info(&self, id: HotkeyId) -> HotkeyInfo {
+        HotkeyInfo {
+            id,
+            key: self.key,
+            modifiers: self.modifiers.clone(),
+            extra_keys: self.extra_keys.clone(),
+            label: self.label.clone(),
+            description: self.description.clone(),
+            group: self.group.clone(),
+            mode: self.mode.clone(),
+            action: self.action().map(str::to_string),
+            enabled: self.enabled.get(),
+        }
+    }
+
+    /// Get the action name of an action hotkey
+    ///
+    fn action(&self) -> Option<&str> {
+        match &self.target {
+            HotkeyTarget::Action(action) => Some(action),
+            _ => None,
+        }
+    }
+
+    /// Check if the hotkey and its named group are enabled
+    ///
+    fn is_enabled(&self) -> bool {
+        self.enabled.get() && self.group_enabled.get()
+    }
+
+    /// Get the key combination of the hotkey
+    ///
+    fn combo(&self) -> HotkeyCombo {
+        HotkeyCombo::new(self.key, &self.modifiers).with_extra_keys(&self.extra_keys)
+    }
+
+    /// Check if the main key and all modifiers of the hotkey are currently held down
+    ///
+    fn is_held(&self) -> bool {
+        let modifier_held = |mk: &ModKey| match mk {
+            ModKey::NoRepeat => true,
+            ModKey::Win => get_global_keystate(VKey::LWin) || get_global_keystate(VKey::RWin),
+            mk => get_global_keystate((*mk).into()),
+        };
+
+        get_global_keystate(self.key) && self.modifiers.iter().all(modifier_held)
+    }
+
+    /// Check if all additional conditions for executing the callback are met for the received
+    /// hotkey message. Returns the reason if one of the conditions is not met.
+    ///
+    fn check_conditions(&self, msg: &MSG) -> Result<(), RejectReason> {
+        if !self.enabled.get() {
+            return Err(RejectReason::Disabled);
+        }
+        if !self.group_enabled.get() {
+            return Err(RejectReason::GroupDisabled);
+        }
+
+        // Check if all extra keys are pressed
+        let missing: Vec<_> = self
+            .extra_keys
+            .iter()
+            .copied()
+            .filter(|vk| !get_global_keystate(*vk))
+            .collect();
+        if !missing.is_empty() {
+            return Err(RejectReason::MissingExtraKeys(missing));
+        }
+
+        // Check if the cursor was in the required region when the hotkey was triggered
+        if let Some(region) = &self.cursor_region {
+            if !region.contains(msg.pt.x, msg.pt.y) {
+                return Err(RejectReason::CursorOutsideRegion);
+            }
+        }
+
+        // Check if the hotkey is allowed in the current session
+        if let Some(session) = self.session {
+            if SessionKind::current() != session {
+                return Err(RejectReason::WrongSession);
+            }
+        }
+
+        // Check if the accessibility setting is in the required state
+        if let Some(condition) = self.accessibility {
+            if !condition.is_met() {
+                return Err(RejectReason::AccessibilityCondition);
+            }
+        }
+
+        // Check if the foreground window at the time of the trigger meets the condition
+        if let Some(condition) = &self.window {
+            if !condition.is_met(trigger_window().as_ref()) {
+                return Err(RejectReason::WrongWindow);
+            }
+        }
+
+        // Check the condition function last, since it can be arbitrarily expensive
+        if let Some(condition) = &self.condition {
+            if !condition() {
+                return Err(RejectReason::ConditionFailed);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Shared handle to a registered callback function. The callback is wrapped in an `Arc<Mutex<_>>`
+/// so that it can be handed to a dispatcher and executed on a different thread.
+///
+#[cfg(windows)]
+type SharedCallback<T> = Arc<Mutex<Box<dyn Fn() -> T + Send + 'static>>>;
+
+/// Function that decides if a hotkey is allowed to trigger, see `set_condition`. For hotkeys that
+/// are detected by a hook, the function is called from the hook thread.
+///
+#[cfg(windows)]
+type Predicate = Arc<dyn Fn() -> bool + Send + Sync + 'static>;
+
+/// Function that receives matched callbacks instead of executing them directly. This is the
+/// internal representation of a dispatcher set with `set_dispatcher`.
+///
+#[cfg(windows)]
+type DispatchFn<T> = Box<dyn Fn(SharedCallback<T>) -> T + Send + 'static>;
+
+/// Wrap a user provided dispatcher into a `DispatchFn`. The matched callback is passed to the
+/// dispatcher as a boxed closure that can be executed on any thread.
+///
+#[cfg(windows)]
+fn wrap_dispatcher(
+    dispatcher: impl Fn(Box<dyn FnOnce() + Send>) + Send + 'static,
+) -> DispatchFn<()> {
+    Box::new(move |callback: SharedCallback<()>| {
+        dispatcher(Box::new(move || {
+            let callback = callback.lock().unwrap_or_else(|e| e.into_inner());
+            callback()
+        }))
+    })
+}
+
+/// Wrap a user provided callback that receives the trigger context into a `TriggerCallback`
+///
+#[cfg(windows)]
+fn wrap_trigger_callback<T: 'static>(
+    callback: impl Fn(&TriggerContext) -> T + Send + 'static,
+) -> TriggerCallback<T> {
+    let callback = Arc::new(Mutex::new(callback));
+    Box::new(move |context: TriggerContext| {
+        let callback = callback.clone();
+        Arc::new(Mutex::new(Box::new(move || {
+            let callback = callback.lock().unwrap_or_else(|e| e.into_inner());
+            callback(&context)
+        })))
+    })
+}
+
+/// Wrap a user provided action handler into an `ActionHandler`
+///
+#[cfg(windows)]
+fn wrap_action_handler<T: 'static>(
+    handler: impl Fn(&str, &HotkeyEvent) -> T + Send + 'static,
+) -> ActionHandler<T> {
+    let handler = Arc::new(Mutex::new(handler));
+    Box::new(move |action: String, event: HotkeyEvent| {
+        let handler = handler.clone();
+        Arc::new(Mutex::new(Box::new(move || {
+            let handler = handler.lock().unwrap_or_else(|e| e.into_inner());
+            handler(&action, &event)
+        })))
+    })
+}
+
+/// Create a callback that executes the actions in turn, one per call. If `reset_after` is set and
+/// the previous call is longer ago, the cycle starts again with the first action.
+///
+#[cfg(windows)]
+fn cycle_callback<T: 'static>(
+    actions: Vec<Box<dyn Fn() -> T + Send + 'static>>,
+    reset_after: Option<std::time::Duration>,
+) -> impl Fn() -> T + Send + 'static {
+    // The index of the next action and the time of the previous call
+    let state = Mutex::new((0, None::<std::time::Instant>));
+
+    move || {
+        let index = {
+            let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+            let (next, last) = &mut *state;
+            if reset_after.is_some_and(|reset| last.is_some_and(|last| last.elapsed() >= reset)) {
+                *next = 0;
+            }
+            let index = *next;
+            *next = (index + 1) % actions.len();
+            *last = Some(std::time::Instant::now());
+            index
+        };
+        actions[index]()
+    }
 }
 
 #[cfg(windows)]
@@ -57,19 +766,19 @@ pub trait HotkeyManagerImpl<T> {
     /// # Arguments
     ///
     /// * `key` - The main hotkey. For example `VKey::Return` for the CTRL + ALT + ENTER
-    /// combination.
+    ///   combination.
     ///
     /// * `key_modifiers` - The modifier keys that need to be combined with the main key. The
-    /// modifier keys are the keys that need to be pressed in addition to the main hotkey in order
-    /// for the hotkey event to fire. For example `&[ModKey::Ctrl, ModKey::Alt]` for the
-    /// CTRL + ALT + ENTER combination.
+    ///   modifier keys are the keys that need to be pressed in addition to the main hotkey in order
+    ///   for the hotkey event to fire. For example `&[ModKey::Ctrl, ModKey::Alt]` for the
+    ///   CTRL + ALT + ENTER combination.
     ///
     /// * `extra_keys` - A list of additional VKeys that also need to be pressed for the hotkey
-    /// callback to be executed. This is enforced after the windows hotkey event is fired, but
-    /// before executing the callback. So these keys need to be pressed before the main hotkey.
+    ///   callback to be executed. This is enforced after the windows hotkey event is fired, but
+    ///   before executing the callback. So these keys need to be pressed before the main hotkey.
     ///
     /// * `callback` - A callback function or closure that will be executed when the hotkey is
-    /// triggered. The return type for all callbacks in the same HotkeyManager must be the same.
+    ///   triggered. The return type for all callbacks in the same HotkeyManager must be the same.
     ///
     /// # Windows API Functions used
     /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
@@ -82,7 +791,9 @@ pub trait HotkeyManagerImpl<T> {
         callback: impl Fn() -> T + Send + 'static,
     ) -> Result<HotkeyId, HkError>;
 
-    /// Same as `register_extrakeys` but without extra keys.
+    /// Same as `register_extrakeys` but without extra keys. The modifiers can be given as
+    /// anything that implements `IntoModifiers`, like `ModKey::Alt`, `&[ModKey::Ctrl,
+    /// ModKey::Alt]`, `ModKey::Ctrl | ModKey::Alt` or `"ctrl+alt"`.
     ///
     /// # Windows API Functions used
     /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
@@ -90,9 +801,61 @@ pub trait HotkeyManagerImpl<T> {
     fn register(
         &mut self,
         key: VKey,
-        key_modifiers: &[ModKey],
+        key_modifiers: impl IntoModifiers,
         callback: impl Fn() -> T + Send + 'static,
-    ) -> Result<HotkeyId, HkError>;
+    ) -> Result<HotkeyId, HkError> {
+        let key_modifiers = key_modifiers.into_modifiers()?.to_vec();
+        self.register_extrakeys(key, &key_modifiers, &[], callback)
+    }
+
+    /// Same as `register`, but the callback can mutate its captured state without wrapping it in
+    /// a `RefCell` or `Mutex`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use windows_hotkeys::keys::{ModKey, VKey};
+    /// use windows_hotkeys::{HotkeyManager, HotkeyManagerImpl};
+    ///
+    /// let mut hkm = HotkeyManager::new();
+    /// let mut presses = 0;
+    /// hkm.register_mut(VKey::P, ModKey::Alt, move || {
+    ///     presses += 1;
+    ///     println!("Pressed {presses} times");
+    /// })
+    /// .unwrap();
+    /// ```
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    fn register_mut(
+        &mut self,
+        key: VKey,
+        key_modifiers: impl IntoModifiers,
+        callback: impl FnMut() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        // Calls of the same callback are already serialized, so this lock is never contended
+        let callback = Mutex::new(callback);
+        self.register(key, key_modifiers, move || {
+            let mut callback = callback.lock().unwrap_or_else(|e| e.into_inner());
+            callback()
+        })
+    }
+
+    /// Same as `register_extrakeys`, but the main key, modifiers and extra keys are taken from a
+    /// `HotkeyCombo`. This allows registering combos that were parsed from a string like
+    /// `"CTRL + ALT + M"` or stored in a configuration.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    fn register_combo(
+        &mut self,
+        combo: &HotkeyCombo,
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        self.register_extrakeys(combo.key, &combo.modifiers, &combo.extra_keys, callback)
+    }
 
     /// Unregister a hotkey. This will prevent the hotkey from being triggered in the future.
     ///
@@ -122,14 +885,81 @@ pub trait HotkeyManagerImpl<T> {
     fn handle_hotkey(&self) -> Option<T>;
 
     /// Run the event loop, listening for hotkeys. This will run indefinitely until interrupted and
-    /// execute any hotkeys registered before.
+    /// execute any hotkeys registered before. Returns why the loop stopped.
     ///
-    fn event_loop(&self);
+    fn event_loop(&self) -> ExitReason;
 
     /// Get an `InterruptHandle` for this `HotkeyManager` that can be used to interrupt the event
     /// loop.
     ///
     fn interrupt_handle(&self) -> InterruptHandle;
+
+    /// Create a `HotkeyScope` for this `HotkeyManager`. All hotkeys that are registered through
+    /// the scope are automatically unregistered when the scope is dropped.
+    ///
+    fn scope(&mut self) -> HotkeyScope<'_, Self, T>
+    where
+        Self: Sized,
+    {
+        HotkeyScope::new(self)
+    }
+
+    /// Register all hotkey definitions of a config, for example a bindings file. Instead of
+    /// stopping at the first problem, every definition is attempted and the returned `LoadReport`
+    /// lists which definitions were registered and why the others failed. This gives config
+    /// authors the full picture at once.
+    ///
+    /// Definitions whose key combination was already registered by an earlier definition are
+    /// reported as duplicates and not registered again.
+    ///
+    /// # Arguments
+    ///
+    /// * `defs` - The hotkey definitions to register
+    ///
+    /// * `callback_for` - Creates the callback for the definition with the given index. This is
+    ///   only called for definitions that are actually registered.
+    ///
+    fn register_defs<F>(
+        &mut self,
+        defs: &[HotkeyDef],
+        callback_for: impl FnMut(usize, &HotkeyDef) -> F,
+    ) -> LoadReport
+    where
+        Self: Sized,
+        F: Fn() -> T + Send + 'static,
+    {
+        validate::register_defs(self, defs, callback_for)
+    }
+
+    /// Register the hotkey definitions of a config as bindings of named actions. All definitions
+    /// with the same `HotkeyDef::action` are alternate bindings of one action, like the primary
+    /// and secondary bindings in editors and games. In the order of the definitions, the first
+    /// binding of an action is the primary binding with the alternate index 0, the next one has
+    /// the index 1 and so on.
+    ///
+    /// Same as for `register_defs`, every definition is attempted and the returned `LoadReport`
+    /// lists the outcome for every definition. Definitions without an action are reported as
+    /// invalid. If some bindings of an action fail, the other bindings are still registered.
+    ///
+    /// # Arguments
+    ///
+    /// * `defs` - The hotkey definitions to register
+    ///
+    /// * `callback_for` - Creates the callback for the action name and the alternate index of the
+    ///   binding, so the callback can report which binding was used. This is only called for
+    ///   definitions that are actually registered.
+    ///
+    fn register_actions<F>(
+        &mut self,
+        defs: &[HotkeyDef],
+        callback_for: impl FnMut(&str, usize) -> F,
+    ) -> LoadReport
+    where
+        Self: Sized,
+        F: Fn() -> T + Send + 'static,
+    {
+        validate::register_actions(self, defs, callback_for)
+    }
 }
 
 /// The `InterruptHandle` can be used to interrupt the event loop of the originating `HotkeyManager`.
@@ -140,7 +970,7 @@ pub trait HotkeyManagerImpl<T> {
 /// simply not do anything.
 ///
 #[cfg(windows)]
-pub struct InterruptHandle(HWND);
+pub struct InterruptHandle(Vec<HWND>);
 
 #[cfg(windows)]
 unsafe impl Sync for InterruptHandle {}
@@ -153,10 +983,29 @@ impl InterruptHandle {
     /// Interrupt the evet loop of the associated `HotkeyManager`.
     ///
     pub fn interrupt(&self) {
-        unsafe {
-            PostMessageW(self.0, WM_NULL, 0, 0);
+        for &hwnd in &self.0 {
+            unsafe { PostMessageW(hwnd, WM_NULL, 0, 0) };
         }
     }
+
+    /// Interrupt the event loop of the associated `HotkeyManager` after all hotkey messages that
+    /// are already queued have been processed. Key presses are delivered asynchronously by
+    /// windows, so hotkeys that were pressed right before the interrupt may still be queued
+    /// behind it. This makes sure that these triggers are not dropped on shutdown.
+    ///
+    pub fn interrupt_after_drain(&self) {
+        for &hwnd in &self.0 {
+            unsafe { PostMessageW(hwnd, WM_NULL, DRAIN_INTERRUPT, 0) };
+        }
+    }
+
+    /// Combine the handles of multiple `HotkeyManager`s into one handle that interrupts all of
+    /// their event loops
+    ///
+    #[cfg(feature = "threadsafe")]
+    pub(crate) fn combine(handles: impl IntoIterator<Item = InterruptHandle>) -> Self {
+        InterruptHandle(handles.into_iter().flat_map(|handle| handle.0).collect())
+    }
 }
 
 /// Get the global keystate for a given Virtual Key.
@@ -175,3 +1024,23 @@ pub fn get_global_keystate(vk: VKey) -> bool {
 
     key_state == 1
 }
+
+/// Stop the event loop that executes the current callback once the callback has returned. The
+/// event loop then returns `ExitReason::CallbackRequested`, and `handle_hotkey` returns `None` the
+/// next time it is called.
+///
+/// This only affects an event loop on the current thread, so it has to be called from within a
+/// hotkey callback. The callbacks of the threadsafe `HotkeyManager` run on its backend thread, so
+/// this works for them as well.
+///
+#[cfg(windows)]
+pub fn stop_event_loop() {
+    STOP_REQUESTED.with(|stop| stop.set(true));
+}
+
+/// Check and reset the stop request of the current thread
+///
+#[cfg(windows)]
+pub(crate) fn take_stop_request() -> bool {
+    STOP_REQUESTED.with(|stop| stop.replace(false))
+}
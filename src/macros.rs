@@ -0,0 +1,58 @@
+/// Register multiple hotkeys at once, given as key combination strings and the expressions that
+/// are executed when they are triggered. This expands into a `HotkeyManagerImpl::register_combo`
+/// call for every binding, with the combo parsed by `HotkeyCombo::parse` and the expression
+/// wrapped into a `move` closure.
+///
+/// Evaluates to `Result<Vec<HotkeyId>, HkError>` with the ids in the order of the bindings. If a
+/// combo can't be parsed or registered, the hotkeys of the previous bindings are unregistered
+/// again and the error is returned, so the bindings are either all registered or none.
+///
+/// # Example
+/// ```no_run
+/// use windows_hotkeys::{hotkeys, HotkeyManager, HotkeyManagerImpl};
+///
+/// fn do_a() {
+///     println!("A");
+/// }
+///
+/// let mut hkm = HotkeyManager::new();
+/// let ids = hotkeys!(hkm => {
+///     "ctrl + alt + a" => do_a(),
+///     "win + space" => println!("B"),
+/// })
+/// .unwrap();
+///
+/// hkm.event_loop();
+/// ```
+///
+#[macro_export]
+macro_rules! hotkeys {
+    ($hkm:expr => { $($combo:expr => $action:expr),* $(,)? }) => {{
+        let hkm = &mut $hkm;
+        let mut ids = ::std::vec::Vec::new();
+
+        let result = 'bindings: {
+            $(
+                let combo = match $crate::combo::HotkeyCombo::parse($combo) {
+                    ::std::result::Result::Ok(combo) => combo,
+                    ::std::result::Result::Err(err) => break 'bindings ::std::result::Result::Err(err),
+                };
+                match $crate::HotkeyManagerImpl::register_combo(&mut *hkm, &combo, move || $action) {
+                    ::std::result::Result::Ok(id) => ids.push(id),
+                    ::std::result::Result::Err(err) => break 'bindings ::std::result::Result::Err(err),
+                }
+            )*
+            ::std::result::Result::Ok(())
+        };
+
+        match result {
+            ::std::result::Result::Ok(()) => ::std::result::Result::Ok(ids),
+            ::std::result::Result::Err(err) => {
+                for id in ids {
+                    let _ = $crate::HotkeyManagerImpl::unregister(&mut *hkm, id);
+                }
+                ::std::result::Result::<::std::vec::Vec<$crate::HotkeyId>, $crate::error::HkError>::Err(err)
+            }
+        }
+    }};
+}
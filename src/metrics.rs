@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use crate::{diagnostics::HookDiagnostics, error::HkError, HotkeyId};
+
+/// A snapshot of the self-metrics of a `HotkeyManager`, taken with `metrics`. The metrics are
+/// collected for the whole lifetime of the manager and are cheap enough to be always on, so a
+/// long-running daemon can publish them as health info, for example in a tray tooltip or through
+/// a Prometheus exporter.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metrics {
+    /// The number of messages that the event loop received, including all hotkey messages
+    pub messages_processed: u64,
+    /// The number of callbacks that were executed or handed to the dispatcher
+    pub callbacks_run: u64,
+    /// The number of callbacks that panicked while a `PanicPolicy` was set
+    pub callbacks_panicked: u64,
+    /// The average time that it took to execute a callback or to hand it to the dispatcher
+    pub average_dispatch_time: Duration,
+    /// The description of the latest error, like a failed registration or a panicking callback
+    pub last_error: Option<String>,
+    /// The findings of the hook diagnostics, if they are enabled with `enable_hook_diagnostics`
+    pub hook_chain: Option<HookDiagnostics>,
+}
+
+/// Collects the metrics of a `HotkeyManager`
+///
+#[derive(Debug, Default)]
+pub(crate) struct MetricsRecorder {
+    metrics: Metrics,
+    /// The sum of all dispatch times, used to calculate the average
+    total_dispatch_time: Duration,
+}
+
+impl MetricsRecorder {
+    /// Count a message that was received by the event loop
+    ///
+    pub(crate) fn message(&mut self) {
+        self.metrics.messages_processed += 1;
+    }
+
+    /// Count a callback that was executed or dispatched in the given time
+    ///
+    pub(crate) fn dispatched(&mut self, time: Duration) {
+        self.metrics.callbacks_run += 1;
+        self.total_dispatch_time += time;
+    }
+
+    /// Count a callback that panicked
+    ///
+    pub(crate) fn panicked(&mut self, id: HotkeyId) {
+        self.metrics.callbacks_panicked += 1;
+        self.metrics.last_error = Some(format!("the callback of hotkey {} panicked", id.0));
+    }
+
+    /// Remember the error as the latest error
+    ///
+    pub(crate) fn error(&mut self, err: &HkError) {
+        self.metrics.last_error = Some(err.to_string());
+    }
+
+    /// Get a snapshot of the current metrics
+    ///
+    pub(crate) fn snapshot(&self) -> Metrics {
+        let average_dispatch_time = match self.metrics.callbacks_run {
+            0 => Duration::ZERO,
+            runs => {
+                Duration::from_nanos((self.total_dispatch_time.as_nanos() / runs as u128) as u64)
+            }
+        };
+
+        Metrics {
+            average_dispatch_time,
+            ..self.metrics.clone()
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::{collections::HashMap, time::Duration};
+
+use crate::{
+    keys::{ModKey, VKey},
+    HotkeyId,
+};
+
+/// Function that is called with the new mode whenever the mode of a `HotkeyManager` changes
+///
+pub(crate) type ModeObserver = Box<dyn Fn(Option<&str>) + Send + 'static>;
+
+/// A key combination that switches the `HotkeyManager` into a different mode
+///
+pub(crate) struct ModeSwitch {
+    pub(crate) key: VKey,
+    pub(crate) modifiers: Vec<ModKey>,
+    /// The mode in which the switch is active, `None` if it is active in all modes
+    pub(crate) from: Option<String>,
+    /// The mode that the switch enters, `None` for the default mode
+    pub(crate) to: Option<String>,
+}
+
+/// The modes of a `HotkeyManager`, similar to the modes of i3.
+///
+/// Hotkeys that belong to a mode are only registered with windows while their mode is active.
+/// Hotkeys without a mode are active in all modes. The default mode is `None`.
+///
+#[derive(Default)]
+pub(crate) struct Modes {
+    /// The currently active mode
+    pub(crate) current: Option<String>,
+    /// The registered mode switches by their hotkey id
+    pub(crate) switches: HashMap<HotkeyId, ModeSwitch>,
+    /// The time after which a mode is left automatically if no hotkey is pressed
+    pub(crate) timeouts: HashMap<String, Duration>,
+    /// The timer that leaves the current mode when it times out
+    pub(crate) timer: Option<usize>,
+    /// Called whenever the mode changes
+    pub(crate) observer: Option<ModeObserver>,
+}
+
+impl Modes {
+    /// Check if the hotkeys of the mode are active. Hotkeys without a mode are always active.
+    ///
+    pub(crate) fn is_active(&self, mode: Option<&str>) -> bool {
+        mode.is_none() || mode == self.current.as_deref()
+    }
+
+    /// The timeout of the current mode, if it has one
+    ///
+    pub(crate) fn current_timeout(&self) -> Option<Duration> {
+        self.current
+            .as_ref()
+            .and_then(|mode| self.timeouts.get(mode))
+            .copied()
+    }
+}
@@ -0,0 +1,652 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+#[cfg(feature = "mouse-listener")]
+use std::{
+    sync::mpsc::{channel, Receiver},
+    time::Duration,
+};
+
+use winapi::ctypes::c_int;
+use winapi::shared::minwindef::{LPARAM, LRESULT, WPARAM};
+use winapi::shared::windef::HWND;
+use winapi::um::winuser::{
+    CallNextHookEx, PostMessageW, HC_ACTION, LLMHF_INJECTED, LLMHF_LOWER_IL_INJECTED,
+    MSLLHOOKSTRUCT, VK_LBUTTON, VK_MBUTTON, VK_RBUTTON, VK_XBUTTON1, VK_XBUTTON2, WH_MOUSE_LL,
+    WM_HOTKEY, WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MOUSEHWHEEL,
+    WM_MOUSEMOVE, WM_MOUSEWHEEL, WM_RBUTTONDOWN, WM_RBUTTONUP, WM_XBUTTONDOWN, WM_XBUTTONUP,
+    XBUTTON1, XBUTTON2,
+};
+
+use crate::{
+    condition::WindowCondition,
+    error::HkError,
+    gesture::{GestureBinding, GestureRecorder},
+    hook::{current_modifiers, HookAction, HookBinding, HookThread, MASK_KEY},
+    keys::{ModKey, VKey},
+    send::{click, tap_key, INJECTED_EXTRA_INFO},
+    HotkeyId, Predicate,
+};
+
+/// Handler function that is called by the mouse hook for every mouse event
+///
+type MouseHandler = Box<dyn FnMut(&MouseHookEvent) -> HookAction + 'static>;
+
+thread_local! {
+    /// The handler for the mouse hook installed on the current thread. Same as for the keyboard
+    /// hook, a thread local is used to route the events from the hook procedure to the handler.
+    static MOUSE_HANDLER: RefCell<Option<MouseHandler>> = RefCell::new(None);
+}
+
+/// A mouse button that can be used as the main key of a hotkey, for example in
+/// `CTRL + MiddleClick`.
+///
+/// Mouse buttons are not `VKey` variants, but they have virtual key codes. So when a mouse hotkey
+/// is reported, for example in a `HotkeyEvent`, the key is the `VKey` returned by
+/// `MouseButton::to_vkey`.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    /// Left mouse button
+    Left,
+    /// Right mouse button
+    Right,
+    /// Middle mouse button, usually the mouse wheel
+    Middle,
+    /// First side button, usually the back button
+    XButton1,
+    /// Second side button, usually the forward button
+    XButton2,
+}
+
+impl MouseButton {
+    /// Get the windows virtual keycode of the mouse button
+    ///
+    pub const fn to_vk_code(&self) -> i32 {
+        match self {
+            MouseButton::Left => VK_LBUTTON,
+            MouseButton::Right => VK_RBUTTON,
+            MouseButton::Middle => VK_MBUTTON,
+            MouseButton::XButton1 => VK_XBUTTON1,
+            MouseButton::XButton2 => VK_XBUTTON2,
+        }
+    }
+
+    /// Get the mouse button as a `VKey`, using its virtual keycode
+    ///
+    pub const fn to_vkey(&self) -> VKey {
+        VKey::CustomKeyCode(self.to_vk_code())
+    }
+
+    /// Get the mouse button from a `VKey` created with `MouseButton::to_vkey`
+    ///
+    pub fn from_vkey(vkey: VKey) -> Option<Self> {
+        [
+            MouseButton::Left,
+            MouseButton::Right,
+            MouseButton::Middle,
+            MouseButton::XButton1,
+            MouseButton::XButton2,
+        ]
+        .into_iter()
+        .find(|button| button.to_vkey() == vkey)
+    }
+
+    /// Get the mouse button and if it was pressed from a mouse message. For the side buttons, the
+    /// button is stored in the high word of the mouse data
+    ///
+    fn from_message(message: u32, mouse_data: u32) -> Option<(Self, bool)> {
+        let xbutton = match (mouse_data >> 16) as u16 {
+            XBUTTON1 => Some(MouseButton::XButton1),
+            XBUTTON2 => Some(MouseButton::XButton2),
+            _ => None,
+        };
+
+        Some(match message {
+            WM_LBUTTONDOWN => (MouseButton::Left, true),
+            WM_LBUTTONUP => (MouseButton::Left, false),
+            WM_RBUTTONDOWN => (MouseButton::Right, true),
+            WM_RBUTTONUP => (MouseButton::Right, false),
+            WM_MBUTTONDOWN => (MouseButton::Middle, true),
+            WM_MBUTTONUP => (MouseButton::Middle, false),
+            WM_XBUTTONDOWN => (xbutton?, true),
+            WM_XBUTTONUP => (xbutton?, false),
+            _ => return None,
+        })
+    }
+}
+
+impl From<MouseButton> for VKey {
+    fn from(button: MouseButton) -> Self {
+        button.to_vkey()
+    }
+}
+
+/// A scroll direction of the mouse wheel that can be used as the main key of a hotkey, for
+/// example in `CTRL + WheelUp`.
+///
+/// The wheel has no virtual key codes, so the `VKey` returned by `MouseWheel::to_vkey` uses codes
+/// outside of the range of virtual key codes, which can't collide with actual keys.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MouseWheel {
+    /// Scrolling the vertical wheel away from the user
+    Up,
+    /// Scrolling the vertical wheel towards the user
+    Down,
+    /// Tilting the wheel or scrolling the horizontal wheel to the left
+    Left,
+    /// Tilting the wheel or scrolling the horizontal wheel to the right
+    Right,
+}
+
+/// The first key code that is used for the scroll directions. Virtual key codes are in the range
+/// `0x01` to `0xFE`
+///
+const WHEEL_KEY_CODE: i32 = 0x100;
+
+impl MouseWheel {
+    /// Get the scroll direction as a `VKey`, using a key code outside of the virtual key codes
+    ///
+    pub const fn to_vkey(&self) -> VKey {
+        VKey::CustomKeyCode(WHEEL_KEY_CODE + *self as i32)
+    }
+
+    /// Get the scroll direction from a `VKey` created with `MouseWheel::to_vkey`
+    ///
+    pub fn from_vkey(vkey: VKey) -> Option<Self> {
+        [
+            MouseWheel::Up,
+            MouseWheel::Down,
+            MouseWheel::Left,
+            MouseWheel::Right,
+        ]
+        .into_iter()
+        .find(|direction| direction.to_vkey() == vkey)
+    }
+
+    /// Get the scroll direction and the wheel delta from a wheel message
+    ///
+    fn from_message(message: u32, mouse_data: u32) -> Option<(Self, i16)> {
+        let delta = (mouse_data >> 16) as i16;
+
+        Some(match (message, delta > 0) {
+            (WM_MOUSEWHEEL, true) => (MouseWheel::Up, delta),
+            (WM_MOUSEWHEEL, false) => (MouseWheel::Down, delta),
+            (WM_MOUSEHWHEEL, true) => (MouseWheel::Right, delta),
+            (WM_MOUSEHWHEEL, false) => (MouseWheel::Left, delta),
+            _ => return None,
+        })
+    }
+}
+
+impl From<MouseWheel> for VKey {
+    fn from(direction: MouseWheel) -> Self {
+        direction.to_vkey()
+    }
+}
+
+/// A mouse event as received by the low-level mouse hook. This contains the complete data of the
+/// `MSLLHOOKSTRUCT`.
+///
+/// See: <https://learn.microsoft.com/en-us/windows/win32/api/winuser/ns-winuser-msllhookstruct>
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MouseHookEvent {
+    /// The mouse message identifier, for example `WM_LBUTTONDOWN` or `WM_MOUSEMOVE`
+    pub message: u32,
+    /// The x coordinate of the cursor in per-monitor aware screen coordinates
+    pub x: i32,
+    /// The y coordinate of the cursor in per-monitor aware screen coordinates
+    pub y: i32,
+    /// The raw mouse data, which contains the wheel delta or the pressed X button
+    pub mouse_data: u32,
+    /// The raw `LLMHF_*` flags of the event
+    pub flags: u32,
+    /// The message timestamp in milliseconds
+    pub time: u32,
+    /// Additional information associated with the message
+    pub extra_info: usize,
+}
+
+impl MouseHookEvent {
+    fn from_raw(message: u32, raw: &MSLLHOOKSTRUCT) -> Self {
+        Self {
+            message,
+            x: raw.pt.x,
+            y: raw.pt.y,
+            mouse_data: raw.mouseData,
+            flags: raw.flags,
+            time: raw.time,
+            extra_info: raw.dwExtraInfo,
+        }
+    }
+
+    /// Get the mouse button of a button press or release event
+    ///
+    pub fn button(&self) -> Option<MouseButton> {
+        MouseButton::from_message(self.message, self.mouse_data).map(|(button, _)| button)
+    }
+
+    /// Get the scroll direction and the wheel delta of a mouse wheel event
+    ///
+    pub fn wheel(&self) -> Option<(MouseWheel, i16)> {
+        MouseWheel::from_message(self.message, self.mouse_data)
+    }
+
+    /// Check if the event is the press of a mouse button
+    ///
+    pub fn is_button_down(&self) -> bool {
+        matches!(
+            MouseButton::from_message(self.message, self.mouse_data),
+            Some((_, true))
+        )
+    }
+
+    /// Check if the event is the release of a mouse button
+    ///
+    pub fn is_button_up(&self) -> bool {
+        matches!(
+            MouseButton::from_message(self.message, self.mouse_data),
+            Some((_, false))
+        )
+    }
+
+    /// Check if the event is a movement of the mouse
+    ///
+    pub fn is_move(&self) -> bool {
+        self.message == WM_MOUSEMOVE
+    }
+
+    /// Check if the event was injected, for example by `SendInput`
+    ///
+    pub fn is_injected(&self) -> bool {
+        self.flags & LLMHF_INJECTED != 0
+    }
+
+    /// Check if the event was injected from a process running at a lower integrity level
+    ///
+    pub fn is_lower_il_injected(&self) -> bool {
+        self.flags & LLMHF_LOWER_IL_INJECTED != 0
+    }
+}
+
+/// A low-level mouse hook (`WH_MOUSE_LL`) that calls the handler for every mouse event in the
+/// system. The handler decides if the event is passed on or blocked.
+///
+/// Same as the `KeyboardHook`, the hook is installed on a dedicated background thread and removed
+/// when the `MouseHook` is dropped.
+///
+/// # Note
+/// Mouse movements are also passed to the handler, so it is called very often and should return
+/// as quickly as possible.
+///
+/// # Windows API Functions used
+/// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setwindowshookexw>
+///
+pub struct MouseHook {
+    thread: HookThread,
+}
+
+impl MouseHook {
+    /// Install a new low-level mouse hook that calls the `handler` for every mouse event.
+    ///
+    pub fn new(
+        handler: impl FnMut(&MouseHookEvent) -> HookAction + Send + 'static,
+    ) -> Result<Self, HkError> {
+        let thread = HookThread::spawn(
+            WH_MOUSE_LL,
+            Some(mouse_hook_proc),
+            move || MOUSE_HANDLER.with(|h| *h.borrow_mut() = Some(Box::new(handler))),
+            || MOUSE_HANDLER.with(|h| *h.borrow_mut() = None),
+        )?;
+
+        Ok(Self { thread })
+    }
+
+    /// Get the id of the background thread that the hook is running on
+    ///
+    pub fn thread_id(&self) -> u32 {
+        self.thread.thread_id
+    }
+}
+
+/// A stream of all mouse events in the system, received through a low-level mouse hook. The
+/// events are never blocked, so the listener doesn't interfere with the input of other
+/// applications.
+///
+/// Mouse movements produce a large number of events, so they are throttled: after a movement
+/// event, further movements are dropped until the throttle interval has passed. Button and wheel
+/// events are always delivered.
+///
+#[cfg(feature = "mouse-listener")]
+pub struct MouseListener {
+    _hook: MouseHook,
+    rec: Receiver<MouseHookEvent>,
+}
+
+#[cfg(feature = "mouse-listener")]
+impl MouseListener {
+    /// The default minimum time between two delivered movement events
+    ///
+    pub const DEFAULT_MOVE_THROTTLE: Duration = Duration::from_millis(15);
+
+    /// Install a low-level mouse hook and start listening for mouse events, with the movements
+    /// throttled to `DEFAULT_MOVE_THROTTLE`.
+    ///
+    pub fn new() -> Result<Self, HkError> {
+        Self::with_move_throttle(Self::DEFAULT_MOVE_THROTTLE)
+    }
+
+    /// Install a low-level mouse hook and start listening for mouse events. Movements are
+    /// delivered at most once per `throttle`. A throttle of zero delivers all movements.
+    ///
+    pub fn with_move_throttle(throttle: Duration) -> Result<Self, HkError> {
+        let throttle = throttle.as_millis() as u32;
+        let mut last_move: Option<u32> = None;
+
+        let (snd, rec) = channel();
+        let hook = MouseHook::new(move |event| {
+            if event.message == WM_MOUSEMOVE {
+                // The timestamps are in milliseconds and wrap around, so the difference is used
+                if last_move.is_some_and(|last| event.time.wrapping_sub(last) < throttle) {
+                    return HookAction::Pass;
+                }
+                last_move = Some(event.time);
+            }
+
+            let _ = snd.send(*event);
+            HookAction::Pass
+        })?;
+
+        Ok(Self { _hook: hook, rec })
+    }
+
+    /// Block until the next mouse event is received.
+    ///
+    pub fn recv(&self) -> Option<MouseHookEvent> {
+        self.rec.recv().ok()
+    }
+
+    /// Get the next mouse event if one is available, without blocking.
+    ///
+    pub fn try_recv(&self) -> Option<MouseHookEvent> {
+        self.rec.try_recv().ok()
+    }
+
+    /// Get a blocking iterator over the received mouse events.
+    ///
+    pub fn iter(&self) -> impl Iterator<Item = MouseHookEvent> + '_ {
+        self.rec.iter()
+    }
+}
+
+/// The hook procedure for `WH_MOUSE_LL` that forwards the events to the thread local handler
+///
+unsafe extern "system" fn mouse_hook_proc(code: c_int, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code == HC_ACTION {
+        let raw = &*(lparam as *const MSLLHOOKSTRUCT);
+        let event = MouseHookEvent::from_raw(wparam as u32, raw);
+
+        let action = MOUSE_HANDLER.with(|h| match h.try_borrow_mut() {
+            Ok(mut handler) => handler.as_mut().map(|handler| handler(&event)),
+            Err(_) => None,
+        });
+
+        if action == Some(HookAction::Block) {
+            return 1;
+        }
+    }
+
+    CallNextHookEx(std::ptr::null_mut(), code, wparam, lparam)
+}
+
+/// Hotkeys with a mouse button as the main key, which are detected using a low-level mouse hook.
+/// Same as for `HookHotkeys`, a `WM_HOTKEY` message is posted to the target window when a
+/// combination is detected.
+///
+pub(crate) struct MouseHotkeys {
+    bindings: Arc<Mutex<HashMap<HotkeyId, HookBinding>>>,
+    gestures: Arc<Mutex<HashMap<HotkeyId, GestureBinding>>>,
+    _hook: MouseHook,
+}
+
+impl MouseHotkeys {
+    /// Install the mouse hook and post the detected hotkeys to the given window
+    ///
+    pub(crate) fn new(hwnd: HWND) -> Result<Self, HkError> {
+        let bindings = Arc::new(Mutex::new(HashMap::new()));
+        let gestures = Arc::new(Mutex::new(HashMap::new()));
+
+        let mut matcher = MouseMatcher {
+            // The window handle is not Send, but it is only used to post messages
+            hwnd: hwnd as usize,
+            bindings: bindings.clone(),
+            gestures: gestures.clone(),
+            swallowed: HashSet::new(),
+            recording: None,
+        };
+        let hook = MouseHook::new(move |event| matcher.handle(event))?;
+
+        Ok(Self {
+            bindings,
+            gestures,
+            _hook: hook,
+        })
+    }
+
+    pub(crate) fn insert(&self, id: HotkeyId, binding: HookBinding) {
+        self.bindings
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(id, binding);
+    }
+
+    /// Replace the conditions of a binding, if a binding is registered for the id
+    ///
+    pub(crate) fn set_conditions(
+        &self,
+        id: HotkeyId,
+        condition: Option<Predicate>,
+        window: Option<WindowCondition>,
+    ) {
+        if let Some(binding) = self
+            .bindings
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_mut(&id)
+        {
+            binding.condition = condition;
+            binding.window = window;
+        }
+    }
+
+    pub(crate) fn insert_gesture(&self, id: HotkeyId, gesture: GestureBinding) {
+        self.gestures
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(id, gesture);
+    }
+
+    /// Remove a binding or gesture. Returns `false` if nothing was registered for the id
+    ///
+    pub(crate) fn remove(&self, id: HotkeyId) -> bool {
+        let binding = self
+            .bindings
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&id);
+        let gesture = self
+            .gestures
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&id);
+        binding.is_some() || gesture.is_some()
+    }
+}
+
+/// The mouse hotkey detection running inside of the mouse hook
+///
+struct MouseMatcher {
+    hwnd: usize,
+    bindings: Arc<Mutex<HashMap<HotkeyId, HookBinding>>>,
+    gestures: Arc<Mutex<HashMap<HotkeyId, GestureBinding>>>,
+    /// Buttons where the press was blocked, so the matching release must be blocked as well
+    swallowed: HashSet<MouseButton>,
+    /// The gesture that is drawn while the gesture button is held
+    recording: Option<GestureRecorder>,
+}
+
+impl MouseMatcher {
+    fn handle(&mut self, event: &MouseHookEvent) -> HookAction {
+        if let Some((direction, delta)) = event.wheel() {
+            return self.scrolled(event, direction, delta);
+        }
+
+        if event.is_move() {
+            if let Some(recording) = &mut self.recording {
+                recording.moved(event.x, event.y);
+            }
+            return HookAction::Pass;
+        }
+
+        let Some((button, down)) = MouseButton::from_message(event.message, event.mouse_data)
+        else {
+            return HookAction::Pass;
+        };
+
+        if !down {
+            if self
+                .recording
+                .as_ref()
+                .is_some_and(|rec| rec.button == button)
+            {
+                self.gesture_finished();
+                return HookAction::Block;
+            }
+
+            return match self.swallowed.remove(&button) {
+                true => HookAction::Block,
+                false => HookAction::Pass,
+            };
+        }
+
+        // Ignore the input sent by this crate
+        if event.extra_info == INJECTED_EXTRA_INFO {
+            return HookAction::Pass;
+        }
+
+        let vk = button.to_vkey();
+        let modifiers = current_modifiers();
+
+        // The press of a gesture button is blocked until it is known if a gesture was drawn
+        let is_gesture_button = self
+            .gestures
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .any(|gesture| gesture.button == button && gesture.modifiers == modifiers);
+        if is_gesture_button && self.recording.is_none() {
+            self.recording = Some(GestureRecorder::new(button, modifiers, event.x, event.y));
+            return HookAction::Block;
+        }
+
+        let bindings = self.bindings.lock().unwrap_or_else(|e| e.into_inner());
+        let mut action = HookAction::Pass;
+
+        for (id, binding) in bindings.iter() {
+            if binding.key != vk || binding.modifiers != modifiers || !binding.is_allowed() {
+                continue;
+            }
+
+            let lparam = (modifiers | (vk.to_vk_code() as u32) << 16) as LPARAM;
+            unsafe { PostMessageW(self.hwnd as HWND, WM_HOTKEY, id.0 as WPARAM, lparam) };
+
+            if binding.block {
+                action = HookAction::Block;
+            }
+        }
+
+        if action == HookAction::Block {
+            self.swallowed.insert(button);
+            mask_modifiers(modifiers);
+        }
+
+        action
+    }
+
+    /// Post the hotkey message for the gesture that was drawn. If the cursor was not moved, the
+    /// blocked click is sent again so that the button keeps working as usual
+    ///
+    fn gesture_finished(&mut self) {
+        let Some(recording) = self.recording.take() else {
+            return;
+        };
+
+        if recording.strokes.is_empty() {
+            let _ = click(recording.button);
+            return;
+        }
+
+        let gestures = self.gestures.lock().unwrap_or_else(|e| e.into_inner());
+        let vk = recording.button.to_vkey();
+
+        for (id, _) in gestures.iter().filter(|(_, g)| recording.matches(g)) {
+            let lparam = (recording.modifiers | (vk.to_vk_code() as u32) << 16) as LPARAM;
+            unsafe { PostMessageW(self.hwnd as HWND, WM_HOTKEY, id.0 as WPARAM, lparam) };
+        }
+
+        mask_modifiers(recording.modifiers);
+    }
+
+    /// Post the hotkey messages for all bindings of the scroll direction. The wheel delta is
+    /// passed in the high word of the `lParam` instead of the key
+    ///
+    fn scrolled(
+        &mut self,
+        event: &MouseHookEvent,
+        direction: MouseWheel,
+        delta: i16,
+    ) -> HookAction {
+        if event.extra_info == INJECTED_EXTRA_INFO {
+            return HookAction::Pass;
+        }
+
+        let vk = direction.to_vkey();
+        let modifiers = current_modifiers();
+
+        let bindings = self.bindings.lock().unwrap_or_else(|e| e.into_inner());
+        let mut action = HookAction::Pass;
+
+        for (id, binding) in bindings.iter() {
+            if binding.key != vk || binding.modifiers != modifiers || !binding.is_allowed() {
+                continue;
+            }
+
+            let lparam = (modifiers | (delta as u16 as u32) << 16) as LPARAM;
+            unsafe { PostMessageW(self.hwnd as HWND, WM_HOTKEY, id.0 as WPARAM, lparam) };
+
+            if binding.block {
+                action = HookAction::Block;
+            }
+        }
+
+        if action == HookAction::Block {
+            mask_modifiers(modifiers);
+        }
+
+        action
+    }
+}
+
+/// Without a key in between, releasing the Win or ALT key after a blocked mouse event would open
+/// the start menu or the menu bar, so an unassigned key is tapped right away if they are held.
+///
+fn mask_modifiers(modifiers: u32) {
+    let win_or_alt = ModKey::Win.to_mod_code() | ModKey::Alt.to_mod_code();
+    if modifiers & win_or_alt != 0 {
+        let _ = tap_key(MASK_KEY);
+    }
+}
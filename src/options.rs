@@ -0,0 +1,294 @@
+use crate::{
+    combo::HotkeyCombo,
+    condition::{AccessibilityCondition, CursorRegion, SessionKind, WindowCondition},
+    keys::{ModKey, VKey},
+};
+
+/// Per-hotkey configuration for `register_with_options`. This bundles the optional settings of a
+/// hotkey, so they can be set at registration time without a separate `register_*` function for
+/// every combination.
+///
+/// The default options register an enabled hotkey without any extra keys or restrictions, the
+/// same as `register`.
+///
+/// # Example
+/// ```no_run
+/// use windows_hotkeys::{keys::VKey, options::RegisterOptions};
+///
+/// let options = RegisterOptions {
+///     no_repeat: Some(false),
+///     extra_keys: vec![VKey::Left],
+///     label: Some("Move left".to_string()),
+///     description: Some("Move the window to the left half of the screen".to_string()),
+///     ..Default::default()
+/// };
+/// ```
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisterOptions {
+    /// If `ModKey::NoRepeat` is applied to the hotkey. `None` uses the manager wide option that
+    /// is set with `set_no_repeat`
+    pub no_repeat: Option<bool>,
+    /// Additional keys that need to be pressed for the callback to be executed
+    pub extra_keys: Vec<VKey>,
+    /// A name for the hotkey, which is reported in the `HotkeyInfo` and the `HotkeyEvent`s of the
+    /// hotkey. If the registration fails, the error is wrapped in `HkError::Labeled`
+    pub label: Option<String>,
+    /// A longer explanation of what the hotkey does, which is reported in the `HotkeyInfo`. This
+    /// can be shown next to the label in a list of the bindings
+    pub description: Option<String>,
+    /// If the hotkey starts enabled. Disabled hotkeys stay registered, but don't execute the
+    /// callback until they are enabled with `set_enabled`
+    pub enabled: bool,
+    /// Only trigger while the cursor is inside of the region, see `set_cursor_region`
+    pub cursor_region: Option<CursorRegion>,
+    /// Only trigger in the given kind of session, see `set_allowed_session`
+    pub session: Option<SessionKind>,
+    /// Only trigger if the accessibility condition is met, see `set_accessibility_condition`
+    pub accessibility: Option<AccessibilityCondition>,
+    /// Only trigger if the foreground window meets the condition, see `set_window_condition`
+    pub window: Option<WindowCondition>,
+    /// Add the hotkey to a named group, which can be enabled and disabled as a whole with
+    /// `set_group_enabled`
+    pub group: Option<String>,
+    /// Add the hotkey to a mode, so it is only registered while the mode is active. See
+    /// `register_mode_switch`
+    pub mode: Option<String>,
+    /// Unregister the hotkey while a fullscreen application like a game is in the foreground,
+    /// see `enable_game_mode`
+    pub suppress_in_fullscreen: bool,
+    /// Unregister or disable the hotkey after a number of triggers, see `set_trigger_limit`
+    pub trigger_limit: Option<TriggerLimit>,
+}
+
+/// What happens to a hotkey after it was triggered a number of times, see `set_trigger_limit`.
+/// This keeps the lifetime of temporary hotkeys in the manager, instead of callbacks that race
+/// with `unregister` calls.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TriggerLimit {
+    /// Unregister the hotkey from windows after this many triggers. The id stays valid, but the
+    /// hotkey is never registered again
+    Unregister(u64),
+    /// Disable the hotkey after this many triggers, like `set_enabled`. Enabling it again restarts
+    /// the count
+    Disable(u64),
+}
+
+impl Default for RegisterOptions {
+    fn default() -> Self {
+        Self {
+            no_repeat: None,
+            extra_keys: Vec::new(),
+            label: None,
+            description: None,
+            enabled: true,
+            cursor_region: None,
+            session: None,
+            accessibility: None,
+            window: None,
+            group: None,
+            mode: None,
+            suppress_in_fullscreen: false,
+            trigger_limit: None,
+        }
+    }
+}
+
+/// Fluent builder for a hotkey, as an alternative to the positional arguments of the `register_*`
+/// functions. The finished `Hotkey` is registered with `add`.
+///
+/// # Example
+/// ```no_run
+/// use windows_hotkeys::{
+///     keys::VKey, options::HotkeyBuilder, singlethreaded::HotkeyManager, HotkeyManagerImpl,
+/// };
+///
+/// let mut hkm = HotkeyManager::new();
+/// let hotkey = HotkeyBuilder::new(VKey::K)
+///     .ctrl()
+///     .alt()
+///     .no_repeat(false)
+///     .extra_key(VKey::Left)
+///     .on_trigger(|| println!("CTRL + ALT + K + Left"));
+/// hkm.add(hotkey).unwrap();
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct HotkeyBuilder {
+    key: VKey,
+    modifiers: Vec<ModKey>,
+    options: RegisterOptions,
+}
+
+impl HotkeyBuilder {
+    /// Start building a hotkey with the given main key
+    ///
+    pub fn new(key: VKey) -> Self {
+        Self {
+            key,
+            modifiers: Vec::new(),
+            options: RegisterOptions::default(),
+        }
+    }
+
+    /// Add a modifier key
+    ///
+    pub fn modifier(mut self, modifier: ModKey) -> Self {
+        if !self.modifiers.contains(&modifier) {
+            self.modifiers.push(modifier);
+        }
+        self
+    }
+
+    /// Add the ALT modifier
+    ///
+    pub fn alt(self) -> Self {
+        self.modifier(ModKey::Alt)
+    }
+
+    /// Add the CTRL modifier
+    ///
+    pub fn ctrl(self) -> Self {
+        self.modifier(ModKey::Ctrl)
+    }
+
+    /// Add the SHIFT modifier
+    ///
+    pub fn shift(self) -> Self {
+        self.modifier(ModKey::Shift)
+    }
+
+    /// Add the WIN modifier
+    ///
+    pub fn win(self) -> Self {
+        self.modifier(ModKey::Win)
+    }
+
+    /// Set if `ModKey::NoRepeat` is applied, see `RegisterOptions::no_repeat`
+    ///
+    pub fn no_repeat(mut self, no_repeat: bool) -> Self {
+        self.options.no_repeat = Some(no_repeat);
+        self
+    }
+
+    /// Add an extra key that needs to be pressed for the callback to be executed
+    ///
+    pub fn extra_key(mut self, key: VKey) -> Self {
+        self.options.extra_keys.push(key);
+        self
+    }
+
+    /// Set the label of the hotkey, see `RegisterOptions::label`
+    ///
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.options.label = Some(label.into());
+        self
+    }
+
+    /// Set the description of the hotkey, see `RegisterOptions::description`
+    ///
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.options.description = Some(description.into());
+        self
+    }
+
+    /// Add the hotkey to a named group, see `RegisterOptions::group`
+    ///
+    pub fn group(mut self, group: impl Into<String>) -> Self {
+        self.options.group = Some(group.into());
+        self
+    }
+
+    /// Add the hotkey to a mode, see `RegisterOptions::mode`
+    ///
+    pub fn mode(mut self, mode: impl Into<String>) -> Self {
+        self.options.mode = Some(mode.into());
+        self
+    }
+
+    /// Unregister the hotkey while a fullscreen application is in the foreground, see
+    /// `RegisterOptions::suppress_in_fullscreen`
+    ///
+    pub fn suppress_in_fullscreen(mut self) -> Self {
+        self.options.suppress_in_fullscreen = true;
+        self
+    }
+
+    /// Unregister or disable the hotkey after a number of triggers, see
+    /// `RegisterOptions::trigger_limit`
+    ///
+    pub fn trigger_limit(mut self, limit: TriggerLimit) -> Self {
+        self.options.trigger_limit = Some(limit);
+        self
+    }
+
+    /// Set if the hotkey starts enabled, see `RegisterOptions::enabled`
+    ///
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.options.enabled = enabled;
+        self
+    }
+
+    /// Only trigger while the cursor is inside of the region, see `RegisterOptions::cursor_region`
+    ///
+    pub fn cursor_region(mut self, region: CursorRegion) -> Self {
+        self.options.cursor_region = Some(region);
+        self
+    }
+
+    /// Only trigger in the given kind of session, see `RegisterOptions::session`
+    ///
+    pub fn session(mut self, session: SessionKind) -> Self {
+        self.options.session = Some(session);
+        self
+    }
+
+    /// Only trigger if the accessibility condition is met, see `RegisterOptions::accessibility`
+    ///
+    pub fn accessibility(mut self, condition: AccessibilityCondition) -> Self {
+        self.options.accessibility = Some(condition);
+        self
+    }
+
+    /// Only trigger if the foreground window meets the condition, see `RegisterOptions::window`
+    ///
+    pub fn window(mut self, condition: WindowCondition) -> Self {
+        self.options.window = Some(condition);
+        self
+    }
+
+    /// Finish the hotkey with the callback that is executed when it is triggered
+    ///
+    pub fn on_trigger<T>(self, callback: impl Fn() -> T + Send + 'static) -> Hotkey<T> {
+        Hotkey {
+            key: self.key,
+            modifiers: self.modifiers,
+            options: self.options,
+            callback: Box::new(callback),
+        }
+    }
+}
+
+/// A hotkey that was built with the `HotkeyBuilder` and can be registered with `add`.
+///
+pub struct Hotkey<T> {
+    pub(crate) key: VKey,
+    pub(crate) modifiers: Vec<ModKey>,
+    pub(crate) options: RegisterOptions,
+    pub(crate) callback: Box<dyn Fn() -> T + Send + 'static>,
+}
+
+impl<T> Hotkey<T> {
+    /// Get the key combination of the hotkey
+    ///
+    pub fn combo(&self) -> HotkeyCombo {
+        HotkeyCombo::new(self.key, &self.modifiers).with_extra_keys(&self.options.extra_keys)
+    }
+
+    /// Get the options that the hotkey is registered with
+    ///
+    pub fn options(&self) -> &RegisterOptions {
+        &self.options
+    }
+}
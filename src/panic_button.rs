@@ -0,0 +1,136 @@
+use std::{
+    sync::mpsc::channel,
+    thread::{spawn, JoinHandle},
+};
+
+use winapi::um::processthreadsapi::{GetCurrentThread, SetThreadPriority};
+use winapi::um::winbase::THREAD_PRIORITY_HIGHEST;
+
+use crate::{
+    combo::HotkeyCombo,
+    error::HkError,
+    keys::{IntoModifiers, VKey},
+    singlethreaded::HotkeyManager,
+    HotkeyManagerImpl, InterruptHandle, PanicPolicy,
+};
+
+/// An emergency-stop hotkey, for example for automation tools that drive the keyboard and mouse.
+/// The panic button is registered on its own high priority thread with its own hidden window, so
+/// it is independent of all `HotkeyManager`s:
+///
+/// - It is triggered even while a callback of another manager is running and blocks its event
+///   loop, or while no event loop is running at all
+/// - Disabled hotkeys and groups, scopes and other conditions of the other managers don't apply
+///   to it
+/// - Its callback is executed right away on the thread of the panic button. A panic in the
+///   callback is ignored, so the panic button keeps working
+///
+/// The panic button stays registered until it is dropped.
+///
+/// # Example
+/// ```no_run
+/// use std::sync::{
+///     atomic::{AtomicBool, Ordering},
+///     Arc,
+/// };
+/// use windows_hotkeys::{
+///     keys::{ModKey, VKey},
+///     panic_button::PanicButton,
+/// };
+///
+/// let stop = Arc::new(AtomicBool::new(false));
+/// let stop_flag = stop.clone();
+/// let _panic_button = PanicButton::register(VKey::Escape, ModKey::Ctrl | ModKey::Shift, move || {
+///     stop_flag.store(true, Ordering::SeqCst);
+/// })
+/// .unwrap();
+///
+/// while !stop.load(Ordering::SeqCst) {
+///     // Drive the input of the automation
+/// }
+/// ```
+///
+pub struct PanicButton {
+    combo: HotkeyCombo,
+    interrupt: InterruptHandle,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl PanicButton {
+    /// Register the panic button for the key combination. This returns once the hotkey was
+    /// registered on the thread of the panic button.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-setthreadpriority>
+    ///
+    pub fn register(
+        key: VKey,
+        key_modifiers: impl IntoModifiers,
+        callback: impl Fn() + Send + 'static,
+    ) -> Result<Self, HkError> {
+        let key_modifiers = key_modifiers.into_modifiers()?.to_vec();
+        Self::register_combo(&HotkeyCombo::new(key, &key_modifiers), callback)
+    }
+
+    /// Same as `register`, but the key combination is taken from a `HotkeyCombo`, for example one
+    /// that was parsed from a configuration.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-setthreadpriority>
+    ///
+    pub fn register_combo(
+        combo: &HotkeyCombo,
+        callback: impl Fn() + Send + 'static,
+    ) -> Result<Self, HkError> {
+        let (ret_snd, ret_rec) = channel();
+        let thread_combo = combo.clone();
+
+        let thread = spawn(move || {
+            // Keep the panic button responsive while other threads keep the CPU busy
+            unsafe { SetThreadPriority(GetCurrentThread(), THREAD_PRIORITY_HIGHEST as i32) };
+
+            let mut hkm = HotkeyManager::<()>::new();
+            hkm.set_panic_policy(Some(PanicPolicy::Ignore));
+            let registered = hkm
+                .register_combo(&thread_combo, callback)
+                .map(|_| hkm.interrupt_handle());
+
+            let is_registered = registered.is_ok();
+            let _ = ret_snd.send(registered);
+            if is_registered {
+                hkm.event_loop();
+            }
+        });
+
+        match ret_rec.recv() {
+            Ok(Ok(interrupt)) => Ok(Self {
+                combo: combo.clone(),
+                interrupt,
+                thread: Some(thread),
+            }),
+            Ok(Err(err)) => {
+                let _ = thread.join();
+                Err(err)
+            }
+            // The thread died before it could report the registration
+            Err(_) => Err(HkError::RegistrationFailed(None)),
+        }
+    }
+
+    /// The key combination of the panic button
+    ///
+    pub fn combo(&self) -> &HotkeyCombo {
+        &self.combo
+    }
+}
+
+impl Drop for PanicButton {
+    fn drop(&mut self) {
+        self.interrupt.interrupt();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use std::ops::Deref;
+
+use crate::{combo::HotkeyCombo, error::HkError, HotkeyId, HotkeyManagerImpl};
+
+/// A layer on top of a `HotkeyManager` that binds key combinations to the values of an action
+/// type, usually an enum, instead of closures. Triggering a hotkey returns a clone of its action
+/// from `handle_hotkey`, so all actions can be handled in one `match`.
+///
+/// An action can be bound to multiple key combinations. The registry dereferences to the
+/// underlying `HotkeyManager`, so the event loop and `handle_hotkey` are used as usual. Hotkeys
+/// should only be registered through the registry, so that it knows about all bindings.
+///
+/// # Example
+/// ```no_run
+/// use windows_hotkeys::{
+///     registry::HotkeyRegistry, singlethreaded::HotkeyManager, HotkeyManagerImpl,
+/// };
+///
+/// #[derive(Debug, Clone, PartialEq)]
+/// enum Action {
+///     Copy,
+///     Paste,
+///     Quit,
+/// }
+///
+/// let mut registry = HotkeyRegistry::new(HotkeyManager::new());
+/// registry
+///     .bind_all([
+///         (Action::Copy, "ctrl + alt + c".parse().unwrap()),
+///         (Action::Paste, "ctrl + alt + v".parse().unwrap()),
+///         (Action::Quit, "ctrl + alt + q".parse().unwrap()),
+///     ])
+///     .unwrap();
+///
+/// while let Some(action) = registry.handle_hotkey() {
+///     match action {
+///         Action::Copy => println!("Copy"),
+///         Action::Paste => println!("Paste"),
+///         Action::Quit => break,
+///     }
+/// }
+/// ```
+///
+pub struct HotkeyRegistry<A, M: HotkeyManagerImpl<A>> {
+    manager: M,
+    /// The registered hotkeys in the order they were bound
+    bindings: Vec<(HotkeyId, A, HotkeyCombo)>,
+}
+
+impl<A, M> HotkeyRegistry<A, M>
+where
+    A: Clone + PartialEq + Send + 'static,
+    M: HotkeyManagerImpl<A>,
+{
+    /// Create a new registry on top of the manager
+    ///
+    pub fn new(manager: M) -> Self {
+        Self {
+            manager,
+            bindings: Vec::new(),
+        }
+    }
+
+    /// Bind the action to the key combination. When the hotkey is triggered, `handle_hotkey`
+    /// returns a clone of the action.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn bind(&mut self, action: A, combo: &HotkeyCombo) -> Result<HotkeyId, HkError> {
+        let returned = action.clone();
+        let id = self
+            .manager
+            .register_combo(combo, move || returned.clone())?;
+        self.bindings.push((id, action, combo.clone()));
+        Ok(id)
+    }
+
+    /// Bind all actions to their key combinations, for example from a `HashMap` or an array of
+    /// pairs. Stops at the first binding that fails and returns its error, the bindings before
+    /// it stay registered.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn bind_all(
+        &mut self,
+        bindings: impl IntoIterator<Item = (A, HotkeyCombo)>,
+    ) -> Result<Vec<HotkeyId>, HkError> {
+        bindings
+            .into_iter()
+            .map(|(action, combo)| self.bind(action, &combo))
+            .collect()
+    }
+
+    /// Unregister all key combinations that are bound to the action
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-unregisterhotkey>
+    ///
+    pub fn unbind(&mut self, action: &A) -> Result<(), HkError> {
+        let ids: Vec<_> = self
+            .bindings
+            .iter()
+            .filter(|(_, bound, _)| bound == action)
+            .map(|&(id, _, _)| id)
+            .collect();
+
+        for id in ids {
+            self.manager.unregister(id)?;
+            self.bindings.retain(|&(bound_id, _, _)| bound_id != id);
+        }
+        Ok(())
+    }
+
+    /// Unregister all bindings of the registry
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-unregisterhotkey>
+    ///
+    pub fn unbind_all(&mut self) -> Result<(), HkError> {
+        while let Some(&(id, _, _)) = self.bindings.first() {
+            self.manager.unregister(id)?;
+            self.bindings.remove(0);
+        }
+        Ok(())
+    }
+
+    /// Get the action that the hotkey is bound to
+    ///
+    pub fn action(&self, id: HotkeyId) -> Option<&A> {
+        self.bindings
+            .iter()
+            .find(|&&(bound_id, _, _)| bound_id == id)
+            .map(|(_, action, _)| action)
+    }
+
+    /// Get all key combinations that are bound to the action, in the order they were bound
+    ///
+    pub fn combos(&self, action: &A) -> Vec<&HotkeyCombo> {
+        self.bindings
+            .iter()
+            .filter(|(_, bound, _)| bound == action)
+            .map(|(_, _, combo)| combo)
+            .collect()
+    }
+
+    /// Get all bindings as pairs of the action and the key combination, in the order they were
+    /// bound
+    ///
+    pub fn bindings(&self) -> impl Iterator<Item = (&A, &HotkeyCombo)> {
+        self.bindings
+            .iter()
+            .map(|(_, action, combo)| (action, combo))
+    }
+
+    /// Get the underlying manager back. The hotkeys stay registered.
+    ///
+    pub fn into_inner(self) -> M {
+        self.manager
+    }
+}
+
+impl<A, M: HotkeyManagerImpl<A>> Deref for HotkeyRegistry<A, M> {
+    type Target = M;
+
+    fn deref(&self) -> &Self::Target {
+        &self.manager
+    }
+}
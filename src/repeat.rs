@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+/// Policy for repeating a hotkey callback while the hotkey is held down. In contrast to the
+/// automatic key repeat of the OS, the repeat interval can shrink the longer the hotkey is held,
+/// which is useful for hotkeys like volume control or resizing windows.
+///
+/// The repeats are timed by the `HotkeyManager`, so the OS key repeat should be disabled for the
+/// hotkey using `ModKey::NoRepeat` (which is the default).
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RepeatPolicy {
+    /// Delay between the initial trigger and the first repeat
+    pub initial_delay: Duration,
+    /// Interval between the first and the second repeat
+    pub interval: Duration,
+    /// The shortest interval that can be reached by accelerating
+    pub min_interval: Duration,
+    /// Factor that the interval is multiplied with after each repeat. Values below `1.0` shorten
+    /// the interval, `1.0` disables the acceleration
+    pub acceleration: f32,
+}
+
+impl RepeatPolicy {
+    /// Create a `RepeatPolicy` that repeats with a constant interval after the initial delay.
+    ///
+    pub fn constant(initial_delay: Duration, interval: Duration) -> Self {
+        Self {
+            initial_delay,
+            interval,
+            min_interval: interval,
+            acceleration: 1.0,
+        }
+    }
+
+    /// Calculate the interval that follows the given interval
+    ///
+    pub(crate) fn next_interval(&self, interval: Duration) -> Duration {
+        interval
+            .mul_f32(self.acceleration.max(0.0))
+            .max(self.min_interval)
+    }
+}
+
+impl Default for RepeatPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_millis(400),
+            interval: Duration::from_millis(150),
+            min_interval: Duration::from_millis(30),
+            acceleration: 0.85,
+        }
+    }
+}
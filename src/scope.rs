@@ -0,0 +1,102 @@
+use std::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{
+    error::HkError,
+    keys::{IntoModifiers, ModKey, VKey},
+    HotkeyId, HotkeyManagerImpl,
+};
+
+/// A guard that keeps track of all hotkeys registered through it and unregisters them when it is
+/// dropped. This makes temporary sets of hotkeys, for example while a dialog is open or for the
+/// lifetime of a plugin, leak-proof.
+///
+/// A `HotkeyScope` is created with `HotkeyManagerImpl::scope`. The scope dereferences to the
+/// underlying `HotkeyManager`, so the event loop can be run through it. Hotkeys that are
+/// registered directly on the `HotkeyManager` are not part of the scope.
+///
+pub struct HotkeyScope<'a, M: HotkeyManagerImpl<T>, T> {
+    manager: &'a mut M,
+    ids: Vec<HotkeyId>,
+    _phantom: PhantomData<T>,
+}
+
+impl<'a, M: HotkeyManagerImpl<T>, T> HotkeyScope<'a, M, T> {
+    pub(crate) fn new(manager: &'a mut M) -> Self {
+        Self {
+            manager,
+            ids: Vec::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Register a new hotkey with additional required extra keys as part of this scope. See
+    /// `HotkeyManagerImpl::register_extrakeys`.
+    ///
+    pub fn register_extrakeys(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        extra_keys: &[VKey],
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let id = self
+            .manager
+            .register_extrakeys(key, key_modifiers, extra_keys, callback)?;
+        self.ids.push(id);
+        Ok(id)
+    }
+
+    /// Register a new hotkey as part of this scope. See `HotkeyManagerImpl::register`.
+    ///
+    pub fn register(
+        &mut self,
+        key: VKey,
+        key_modifiers: impl IntoModifiers,
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let key_modifiers = key_modifiers.into_modifiers()?.to_vec();
+        self.register_extrakeys(key, &key_modifiers, &[], callback)
+    }
+
+    /// Unregister a hotkey of this scope before the scope is dropped.
+    ///
+    pub fn unregister(&mut self, id: HotkeyId) -> Result<(), HkError> {
+        if !self.ids.contains(&id) {
+            return Err(HkError::InvalidHotkeyId(id));
+        }
+        self.manager.unregister(id)?;
+        self.ids.retain(|&scope_id| scope_id != id);
+        Ok(())
+    }
+
+    /// Get the ids of all hotkeys that are currently registered through this scope.
+    ///
+    pub fn ids(&self) -> &[HotkeyId] {
+        &self.ids
+    }
+}
+
+impl<M: HotkeyManagerImpl<T>, T> Deref for HotkeyScope<'_, M, T> {
+    type Target = M;
+
+    fn deref(&self) -> &Self::Target {
+        self.manager
+    }
+}
+
+impl<M: HotkeyManagerImpl<T>, T> DerefMut for HotkeyScope<'_, M, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.manager
+    }
+}
+
+impl<M: HotkeyManagerImpl<T>, T> Drop for HotkeyScope<'_, M, T> {
+    fn drop(&mut self) {
+        for id in self.ids.drain(..) {
+            let _ = self.manager.unregister(id);
+        }
+    }
+}
@@ -0,0 +1,261 @@
+use std::{thread::sleep, time::Duration};
+
+use winapi::um::winuser::{
+    SendInput, INPUT, INPUT_KEYBOARD, INPUT_MOUSE, KEYBDINPUT, KEYEVENTF_EXTENDEDKEY,
+    KEYEVENTF_KEYUP, KEYEVENTF_UNICODE, MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP,
+    MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP, MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP,
+    MOUSEEVENTF_XDOWN, MOUSEEVENTF_XUP, MOUSEINPUT, XBUTTON1, XBUTTON2,
+};
+
+use crate::{
+    combo::HotkeyCombo,
+    error::HkError,
+    get_global_keystate,
+    hook::MASK_KEY,
+    keys::{ModKey, VKey},
+    mouse::MouseButton,
+};
+
+/// The physical keys of the modifiers, used to find out which modifiers are currently held
+///
+const MODIFIER_KEYS: [(ModKey, VKey); 8] = [
+    (ModKey::Alt, VKey::LMenu),
+    (ModKey::Alt, VKey::RMenu),
+    (ModKey::Ctrl, VKey::LControl),
+    (ModKey::Ctrl, VKey::RControl),
+    (ModKey::Shift, VKey::LShift),
+    (ModKey::Shift, VKey::RShift),
+    (ModKey::Win, VKey::LWin),
+    (ModKey::Win, VKey::RWin),
+];
+
+/// Value of the `dwExtraInfo` field for all input that is sent by this crate. This can be used to
+/// recognize the crate's own injected input in low-level hooks.
+///
+pub const INJECTED_EXTRA_INFO: usize = 0x574b_4859;
+
+/// Press down the given key.
+///
+/// ## Windows API Functions used
+/// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-sendinput>
+///
+pub fn press_key(vk: VKey) -> Result<(), HkError> {
+    send_inputs(&[key_input(vk.to_vk_code() as u16, 0, 0)])
+}
+
+/// Release the given key.
+///
+/// ## Windows API Functions used
+/// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-sendinput>
+///
+pub fn release_key(vk: VKey) -> Result<(), HkError> {
+    send_inputs(&[key_input(vk.to_vk_code() as u16, 0, KEYEVENTF_KEYUP)])
+}
+
+/// Press and release the given key.
+///
+/// ## Windows API Functions used
+/// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-sendinput>
+///
+pub fn tap_key(vk: VKey) -> Result<(), HkError> {
+    let vk = vk.to_vk_code() as u16;
+    send_inputs(&[key_input(vk, 0, 0), key_input(vk, 0, KEYEVENTF_KEYUP)])
+}
+
+/// Press the key combination in the focused application, like the user would. This is useful when
+/// a hotkey should send a different shortcut, for example `CTRL + C`.
+///
+/// The modifiers of the combination are pressed, the main key is tapped and the modifiers are
+/// released again. Modifiers that are currently held but are not part of the combination, for
+/// example the modifiers of the hotkey that is being handled, are released for the duration of
+/// the combination and pressed again afterwards, so the previous modifier state is restored.
+///
+/// ## Windows API Functions used
+/// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-sendinput>
+/// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getasynckeystate>
+///
+pub fn press_combo(combo: &HotkeyCombo) -> Result<(), HkError> {
+    press_combo_with_delay(combo, Duration::ZERO)
+}
+
+/// Same as `press_combo`, but waits for the given delay between every key event. Some
+/// applications miss key events that are sent all at once, a delay of a few milliseconds helps
+/// with those.
+///
+/// ## Windows API Functions used
+/// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-sendinput>
+/// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getasynckeystate>
+///
+pub fn press_combo_with_delay(combo: &HotkeyCombo, delay: Duration) -> Result<(), HkError> {
+    let wanted = |mk: ModKey| combo.modifiers.contains(&mk);
+
+    let held: Vec<_> = MODIFIER_KEYS
+        .iter()
+        .filter(|(_, vk)| get_global_keystate(*vk))
+        .collect();
+    let stray: Vec<VKey> = held
+        .iter()
+        .filter(|(mk, _)| !wanted(*mk))
+        .map(|(_, vk)| *vk)
+        .collect();
+    let missing: Vec<VKey> = [ModKey::Alt, ModKey::Ctrl, ModKey::Shift, ModKey::Win]
+        .into_iter()
+        .filter(|&mk| wanted(mk) && !held.iter().any(|(held_mk, _)| *held_mk == mk))
+        .map(VKey::from)
+        .collect();
+
+    let mut inputs = Vec::new();
+
+    // Releasing the Win or ALT key on its own would open the start menu or the menu bar
+    if stray.iter().any(|&vk| is_menu_key(vk)) {
+        inputs.extend(tap_inputs(MASK_KEY));
+    }
+    inputs.extend(stray.iter().map(|&vk| vkey_input(vk, KEYEVENTF_KEYUP)));
+    inputs.extend(missing.iter().map(|&vk| vkey_input(vk, 0)));
+    inputs.extend(tap_inputs(combo.key));
+    inputs.extend(
+        missing
+            .iter()
+            .rev()
+            .map(|&vk| vkey_input(vk, KEYEVENTF_KEYUP)),
+    );
+    inputs.extend(stray.iter().map(|&vk| vkey_input(vk, 0)));
+
+    if delay.is_zero() {
+        return send_inputs(&inputs);
+    }
+
+    for input in &inputs {
+        send_inputs(std::slice::from_ref(input))?;
+        sleep(delay);
+    }
+    Ok(())
+}
+
+/// Press and release the given mouse button at the current cursor position.
+///
+/// ## Windows API Functions used
+/// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-sendinput>
+///
+pub fn click(button: MouseButton) -> Result<(), HkError> {
+    let (down, up, data) = match button {
+        MouseButton::Left => (MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP, 0),
+        MouseButton::Right => (MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP, 0),
+        MouseButton::Middle => (MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP, 0),
+        MouseButton::XButton1 => (MOUSEEVENTF_XDOWN, MOUSEEVENTF_XUP, XBUTTON1),
+        MouseButton::XButton2 => (MOUSEEVENTF_XDOWN, MOUSEEVENTF_XUP, XBUTTON2),
+    };
+
+    send_inputs(&[mouse_input(down, data as u32), mouse_input(up, data as u32)])
+}
+
+/// Type the given unicode character into the focused application. This is independent of the
+/// current keyboard layout.
+///
+/// ## Windows API Functions used
+/// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-sendinput>
+///
+pub fn send_char(ch: char) -> Result<(), HkError> {
+    let mut buf = [0; 2];
+    let inputs: Vec<_> = ch
+        .encode_utf16(&mut buf)
+        .iter()
+        .flat_map(|&unit| {
+            [
+                key_input(0, unit, KEYEVENTF_UNICODE),
+                key_input(0, unit, KEYEVENTF_UNICODE | KEYEVENTF_KEYUP),
+            ]
+        })
+        .collect();
+
+    send_inputs(&inputs)
+}
+
+/// Type the given text into the focused application. This is independent of the current
+/// keyboard layout.
+///
+/// ## Windows API Functions used
+/// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-sendinput>
+///
+pub fn send_text(text: &str) -> Result<(), HkError> {
+    text.chars().try_for_each(send_char)
+}
+
+/// Create a keyboard `INPUT` struct
+///
+fn key_input(vk: u16, scan: u16, flags: u32) -> INPUT {
+    let mut input = INPUT {
+        type_: INPUT_KEYBOARD,
+        u: unsafe { std::mem::zeroed() },
+    };
+    unsafe {
+        *input.u.ki_mut() = KEYBDINPUT {
+            wVk: vk,
+            wScan: scan,
+            dwFlags: flags,
+            time: 0,
+            dwExtraInfo: INJECTED_EXTRA_INFO,
+        };
+    }
+    input
+}
+
+/// Create a keyboard `INPUT` struct for the key, with `KEYEVENTF_EXTENDEDKEY` for extended keys
+///
+fn vkey_input(vk: VKey, flags: u32) -> INPUT {
+    let flags = match vk.is_extended() {
+        true => flags | KEYEVENTF_EXTENDEDKEY,
+        false => flags,
+    };
+    key_input(vk.to_vk_code() as u16, 0, flags)
+}
+
+/// Create the keyboard `INPUT` structs to press and release the key
+///
+fn tap_inputs(vk: VKey) -> [INPUT; 2] {
+    [vkey_input(vk, 0), vkey_input(vk, KEYEVENTF_KEYUP)]
+}
+
+/// Check if the key is one of the Win or ALT keys, which open a menu when released on their own
+///
+fn is_menu_key(vk: VKey) -> bool {
+    [VKey::LWin, VKey::RWin, VKey::LMenu, VKey::RMenu, VKey::Menu].contains(&vk)
+}
+
+/// Create a mouse `INPUT` struct that doesn't move the cursor
+///
+fn mouse_input(flags: u32, data: u32) -> INPUT {
+    let mut input = INPUT {
+        type_: INPUT_MOUSE,
+        u: unsafe { std::mem::zeroed() },
+    };
+    unsafe {
+        *input.u.mi_mut() = MOUSEINPUT {
+            dx: 0,
+            dy: 0,
+            mouseData: data,
+            dwFlags: flags,
+            time: 0,
+            dwExtraInfo: INJECTED_EXTRA_INFO,
+        };
+    }
+    input
+}
+
+/// Send all inputs at once, so they can't be interleaved with other input
+///
+fn send_inputs(inputs: &[INPUT]) -> Result<(), HkError> {
+    let sent = unsafe {
+        SendInput(
+            inputs.len() as u32,
+            inputs.as_ptr() as *mut INPUT,
+            std::mem::size_of::<INPUT>() as i32,
+        )
+    };
+
+    if sent as usize != inputs.len() {
+        Err(HkError::SendInputFailed)
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    time::Duration,
+};
+
+use crate::{
+    combo::{ComboCode, HotkeyCombo},
+    HotkeyId, SharedCallback,
+};
+
+/// One step of a hotkey sequence. For example the `CTRL + K` in the sequence `CTRL + K`,
+/// `CTRL + C`.
+///
+pub type SequenceStep = HotkeyCombo;
+
+/// A registered hotkey sequence
+///
+pub(crate) struct Sequence<T> {
+    pub(crate) steps: Vec<HotkeyCombo>,
+    pub(crate) callback: SharedCallback<T>,
+    /// Leader actions start with the leader combo and use the leader timeout
+    pub(crate) leader: bool,
+}
+
+/// The state machine for all hotkey sequences of a `HotkeyManager`.
+///
+/// The first steps of all sequences are registered permanently. When one of them is pressed, the
+/// matching sequences are armed and the combos of their next steps are registered until the
+/// sequence completes, is broken by a different step or times out.
+///
+pub(crate) struct Sequences<T> {
+    pub(crate) sequences: HashMap<HotkeyId, Sequence<T>>,
+    /// The hotkey ids that are used to register the step combos with windows
+    pub(crate) step_ids: HashMap<ComboCode, HotkeyId>,
+    /// The step combos that are currently registered with windows
+    pub(crate) active: HashSet<ComboCode>,
+    /// The armed sequences with the index of their next step
+    pub(crate) armed: Vec<(HotkeyId, usize)>,
+    /// The timer that disarms the sequences when the next step is not pressed in time
+    pub(crate) timer: Option<usize>,
+    /// The maximum time between two steps
+    pub(crate) timeout: Duration,
+    /// The leader combo and the time in which a leader action has to be pressed after it
+    pub(crate) leader: Option<(HotkeyCombo, Duration)>,
+}
+
+impl<T> Default for Sequences<T> {
+    fn default() -> Self {
+        Self {
+            sequences: HashMap::new(),
+            step_ids: HashMap::new(),
+            active: HashSet::new(),
+            armed: Vec::new(),
+            timer: None,
+            timeout: Duration::from_secs(1),
+            leader: None,
+        }
+    }
+}
+
+impl<T> Sequences<T> {
+    /// Assign hotkey ids to all step combos that don't have one yet, starting at the id offset
+    ///
+    pub(crate) fn reserve_step_ids(&mut self, steps: &[HotkeyCombo], id_offset: &mut i32) {
+        for combo in steps.iter().map(HotkeyCombo::code) {
+            if let Entry::Vacant(entry) = self.step_ids.entry(combo) {
+                entry.insert(HotkeyId(*id_offset));
+                *id_offset += 1;
+            }
+        }
+    }
+
+    /// The time in which the next step of the armed sequences has to be pressed
+    ///
+    pub(crate) fn armed_timeout(&self) -> Duration {
+        let leader_timeout = self.leader.as_ref().map(|(_, timeout)| *timeout);
+
+        self.armed
+            .iter()
+            .map(|(id, _)| match self.sequences[id].leader {
+                true => leader_timeout.unwrap_or(self.timeout),
+                false => self.timeout,
+            })
+            .max()
+            .unwrap_or(self.timeout)
+    }
+
+    /// Get the step combo that is registered under the given hotkey id
+    ///
+    pub(crate) fn combo_of(&self, id: HotkeyId) -> Option<ComboCode> {
+        self.step_ids
+            .iter()
+            .find(|(_, &step_id)| step_id == id)
+            .map(|(&combo, _)| combo)
+    }
+
+    /// The first steps of all sequences, which are always registered
+    ///
+    pub(crate) fn prefixes(&self) -> HashSet<ComboCode> {
+        self.sequences
+            .values()
+            .map(|seq| seq.steps[0].code())
+            .collect()
+    }
+
+    /// The combos of the next steps of all armed sequences
+    ///
+    pub(crate) fn next_combos(&self) -> HashSet<ComboCode> {
+        self.armed
+            .iter()
+            .map(|(id, step)| self.sequences[id].steps[*step].code())
+            .collect()
+    }
+
+    /// Advance the armed sequences with the pressed combo. If no armed sequence continues with
+    /// the combo, the sequences starting with the combo are armed instead. Returns the id of a
+    /// sequence that was completed by the combo.
+    ///
+    pub(crate) fn advance(&mut self, combo: ComboCode) -> Option<HotkeyId> {
+        let matches = |armed: &[(HotkeyId, usize)]| -> Vec<(HotkeyId, usize)> {
+            armed
+                .iter()
+                .filter(|(id, step)| self.sequences[id].steps[*step].code() == combo)
+                .map(|&(id, step)| (id, step + 1))
+                .collect()
+        };
+
+        let mut progress = matches(&self.armed);
+        if progress.is_empty() {
+            let all: Vec<_> = self.sequences.keys().map(|&id| (id, 0)).collect();
+            progress = matches(&all);
+        }
+
+        let completed = progress
+            .iter()
+            .find(|(id, step)| self.sequences[id].steps.len() == *step)
+            .map(|(id, _)| *id);
+
+        // A completed sequence ends the current run, even if longer sequences share the steps
+        self.armed = match completed {
+            Some(_) => Vec::new(),
+            None => progress,
+        };
+
+        completed
+    }
+}
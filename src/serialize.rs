@@ -0,0 +1,64 @@
+use std::fmt;
+
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{
+    combo::HotkeyCombo,
+    error::HkError,
+    keys::{IntoModifiers, ModKey, Modifiers, VKey},
+};
+
+/// Implement `Serialize` using the `Display` implementation and `Deserialize` using the given
+/// parse function, so the values are stored as the same human-readable names that are used
+/// everywhere else, like `"VK_RETURN"` or `"CONTROL + ALT + M"`, instead of raw key codes.
+///
+macro_rules! impl_serde_by_name {
+    ($ty:ty, $expecting:literal, $parse:expr) => {
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct NameVisitor;
+
+                impl Visitor<'_> for NameVisitor {
+                    type Value = $ty;
+
+                    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                        f.write_str($expecting)
+                    }
+
+                    fn visit_str<E: de::Error>(self, name: &str) -> Result<Self::Value, E> {
+                        let parse: fn(&str) -> Result<$ty, HkError> = $parse;
+                        parse(name).map_err(E::custom)
+                    }
+                }
+
+                deserializer.deserialize_str(NameVisitor)
+            }
+        }
+    };
+}
+
+impl_serde_by_name!(
+    VKey,
+    "a key name like `VK_RETURN` or `A`",
+    VKey::from_keyname
+);
+impl_serde_by_name!(ModKey, "a modifier name like `CTRL`", ModKey::from_keyname);
+impl_serde_by_name!(
+    Modifiers,
+    "modifier names separated by `+`, like `CTRL + ALT`",
+    |names| names.into_modifiers()
+);
+impl_serde_by_name!(
+    HotkeyCombo,
+    "a key combination like `CTRL + ALT + M`",
+    HotkeyCombo::parse
+);
@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+
+use crate::{
+    error::HkError,
+    keys::{IntoModifiers, ModKey, VKey},
+    threadsafe::HotkeyManager,
+    ExitReason, HotkeyId, HotkeyInfo, HotkeyManagerImpl, InterruptHandle,
+};
+
+/// Decides which shard of a `ShardedHotkeyManager` a new hotkey is registered on.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShardPolicy {
+    /// Spread the hotkeys evenly over the given number of shards
+    RoundRobin(usize),
+    /// Register up to the given number of hotkeys on a shard and add a new shard once all shards
+    /// are full
+    FillUp(usize),
+}
+
+/// A single `threadsafe::HotkeyManager` of the `ShardedHotkeyManager`, with its own backend
+/// thread and hidden window
+///
+struct Shard<T: 'static> {
+    hkm: HotkeyManager<T>,
+    /// The number of hotkeys that are registered on the shard
+    hotkeys: usize,
+    /// The name of the group, if the shard is reserved for a group
+    group: Option<String>,
+}
+
+/// A hotkey manager that spreads its hotkeys over multiple `threadsafe::HotkeyManager`s, called
+/// shards. Every shard has its own backend thread and hidden window, so the callbacks of one shard
+/// are executed independently of the others. A slow callback only delays the hotkeys of its own
+/// shard, and the number of hotkeys per thread stays low.
+///
+/// The `ShardPolicy` decides on which shard a hotkey is registered. Hotkeys that should be
+/// isolated from all others can be registered in a named group with `register_in_group`, every
+/// group gets a dedicated shard.
+///
+/// The returned `HotkeyId`s are unique across all shards. The event loop runs the event loops of
+/// all shards at the same time, so the callbacks are executed on the backend threads of their
+/// shards and the return values of the callbacks are dropped.
+///
+/// # Example
+/// ```no_run
+/// use windows_hotkeys::{
+///     keys::{ModKey, VKey},
+///     sharded::{ShardPolicy, ShardedHotkeyManager},
+/// };
+///
+/// let mut hkm = ShardedHotkeyManager::new(ShardPolicy::RoundRobin(2));
+/// hkm.register(VKey::A, ModKey::Alt, || println!("ALT + A")).unwrap();
+/// hkm.register_in_group("slow", VKey::S, ModKey::Alt, || {
+///     std::thread::sleep(std::time::Duration::from_secs(5));
+/// })
+/// .unwrap();
+///
+/// hkm.event_loop();
+/// ```
+///
+pub struct ShardedHotkeyManager<T: 'static> {
+    policy: ShardPolicy,
+    shards: Vec<Shard<T>>,
+    /// The shard and the id on that shard of every registered hotkey
+    ids: HashMap<HotkeyId, (usize, HotkeyId)>,
+    next_id: i32,
+    /// The shard that gets the next hotkey for `ShardPolicy::RoundRobin`
+    next_shard: usize,
+}
+
+impl<T: 'static + Send> ShardedHotkeyManager<T> {
+    /// Create a new manager with the given sharding policy. The shards are created once they are
+    /// needed.
+    ///
+    pub fn new(policy: ShardPolicy) -> Self {
+        Self {
+            policy,
+            shards: Vec::new(),
+            ids: HashMap::new(),
+            next_id: 0,
+            next_shard: 0,
+        }
+    }
+
+    /// Register a new hotkey on the shard that is selected by the sharding policy. See
+    /// `HotkeyManagerImpl::register`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register(
+        &mut self,
+        key: VKey,
+        key_modifiers: impl IntoModifiers,
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let key_modifiers = key_modifiers.into_modifiers()?.to_vec();
+        self.register_extrakeys(key, &key_modifiers, &[], callback)
+    }
+
+    /// Register a new hotkey with additional required extra keys on the shard that is selected by
+    /// the sharding policy. See `HotkeyManagerImpl::register_extrakeys`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_extrakeys(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        extra_keys: &[VKey],
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let shard = self.policy_shard();
+        self.register_on(shard, key, key_modifiers, extra_keys, callback)
+    }
+
+    /// Register a new hotkey on the dedicated shard of the group. The shard of a group only
+    /// contains the hotkeys of that group and is created with the first hotkey of the group.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_in_group(
+        &mut self,
+        group: &str,
+        key: VKey,
+        key_modifiers: impl IntoModifiers,
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let key_modifiers = key_modifiers.into_modifiers()?.to_vec();
+
+        let shard = match self
+            .shards
+            .iter()
+            .position(|shard| shard.group.as_deref() == Some(group))
+        {
+            Some(shard) => shard,
+            None => self.add_shard(Some(group.to_string())),
+        };
+        self.register_on(shard, key, &key_modifiers, &[], callback)
+    }
+
+    /// Unregister a hotkey from its shard. See `HotkeyManagerImpl::unregister`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-unregisterhotkey>
+    ///
+    pub fn unregister(&mut self, id: HotkeyId) -> Result<(), HkError> {
+        let &(shard, shard_id) = self.ids.get(&id).ok_or(HkError::InvalidHotkeyId(id))?;
+        self.shards[shard].hkm.unregister(shard_id)?;
+        self.shards[shard].hotkeys -= 1;
+        self.ids.remove(&id);
+        Ok(())
+    }
+
+    /// Change the key combination of a registered hotkey while keeping its id, its shard and its
+    /// callback. See `singlethreaded::HotkeyManager::rebind`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-unregisterhotkey>
+    ///
+    pub fn rebind(
+        &mut self,
+        id: HotkeyId,
+        key: VKey,
+        key_modifiers: &[ModKey],
+    ) -> Result<(), HkError> {
+        let &(shard, shard_id) = self.ids.get(&id).ok_or(HkError::InvalidHotkeyId(id))?;
+        match self.shards[shard].hkm.rebind(shard_id, key, key_modifiers) {
+            // The shard removed the hotkey
+            Err(HkError::NotRegistered(_)) => {
+                self.shards[shard].hotkeys -= 1;
+                self.ids.remove(&id);
+                Err(HkError::NotRegistered(id))
+            }
+            res => res,
+        }
+    }
+
+    /// Unregister all hotkeys of all shards. The shards stay alive and are reused for new
+    /// hotkeys.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-unregisterhotkey>
+    ///
+    pub fn unregister_all(&mut self) -> Result<(), HkError> {
+        for shard in &mut self.shards {
+            shard.hkm.unregister_all()?;
+            shard.hotkeys = 0;
+        }
+        self.ids.clear();
+        Ok(())
+    }
+
+    /// Get the information about the hotkeys of all shards, ordered by their ids. The ids are the
+    /// ones returned by this manager. See `singlethreaded::HotkeyManager::hotkeys`.
+    ///
+    pub fn hotkeys(&self) -> Vec<HotkeyInfo> {
+        let mut hotkeys = Vec::new();
+        for (index, shard) in self.shards.iter().enumerate() {
+            for mut info in shard.hkm.hotkeys() {
+                let id = self
+                    .ids
+                    .iter()
+                    .find(|(_, &shard_id)| shard_id == (index, info.id))
+                    .map(|(&id, _)| id);
+                if let Some(id) = id {
+                    info.id = id;
+                    hotkeys.push(info);
+                }
+            }
+        }
+        hotkeys.sort_by_key(|info| info.id.0);
+        hotkeys
+    }
+
+    /// The number of shards that were created so far
+    ///
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// The index of the shard that the hotkey is registered on
+    ///
+    pub fn shard_of(&self, id: HotkeyId) -> Option<usize> {
+        self.ids.get(&id).map(|&(shard, _)| shard)
+    }
+
+    /// Run the event loops of all shards at the same time. This blocks until the event loops of
+    /// all shards were interrupted, for example with the handle from `interrupt_handle`. Returns
+    /// why the event loop of every shard stopped, in the order of the shards.
+    ///
+    pub fn event_loop(&self) -> Vec<ExitReason> {
+        let running: Vec<_> = self
+            .shards
+            .iter()
+            .map(|shard| shard.hkm.start_event_loop())
+            .collect();
+
+        running
+            .into_iter()
+            .filter_map(|done| done.recv().ok())
+            .collect()
+    }
+
+    /// Get an `InterruptHandle` that interrupts the event loops of all shards that exist at the
+    /// time of the call.
+    ///
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        InterruptHandle::combine(self.shards.iter().map(|shard| shard.hkm.interrupt_handle()))
+    }
+
+    /// Select the shard for a new hotkey according to the sharding policy. Group shards are never
+    /// selected.
+    ///
+    fn policy_shard(&mut self) -> usize {
+        let shared: Vec<_> = (0..self.shards.len())
+            .filter(|&shard| self.shards[shard].group.is_none())
+            .collect();
+
+        match self.policy {
+            ShardPolicy::RoundRobin(count) => {
+                let index = self.next_shard;
+                self.next_shard = (index + 1) % count.max(1);
+                match shared.get(index) {
+                    Some(&shard) => shard,
+                    None => self.add_shard(None),
+                }
+            }
+            ShardPolicy::FillUp(capacity) => {
+                match shared
+                    .into_iter()
+                    .find(|&shard| self.shards[shard].hotkeys < capacity.max(1))
+                {
+                    Some(shard) => shard,
+                    None => self.add_shard(None),
+                }
+            }
+        }
+    }
+
+    /// Start a new shard and return its index
+    ///
+    fn add_shard(&mut self, group: Option<String>) -> usize {
+        self.shards.push(Shard {
+            hkm: HotkeyManager::new(),
+            hotkeys: 0,
+            group,
+        });
+        self.shards.len() - 1
+    }
+
+    fn register_on(
+        &mut self,
+        shard: usize,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        extra_keys: &[VKey],
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let shard_id =
+            self.shards[shard]
+                .hkm
+                .register_extrakeys(key, key_modifiers, extra_keys, callback)?;
+        self.shards[shard].hotkeys += 1;
+
+        let id = HotkeyId(self.next_id);
+        self.next_id += 1;
+        self.ids.insert(id, (shard, shard_id));
+        Ok(id)
+    }
+}
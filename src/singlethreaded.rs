@@ -1,19 +1,59 @@
 #[cfg(not(target_os = "windows"))]
 compile_error!("Only supported on windows");
 
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
+#[cfg(feature = "test-support")]
+use winapi::shared::minwindef::{LPARAM, WPARAM};
 use winapi::shared::windef::HWND;
 use winapi::um::libloaderapi::GetModuleHandleA;
+use winapi::um::processthreadsapi::SwitchToThread;
+#[cfg(feature = "test-support")]
+use winapi::um::winuser::PostMessageW;
 use winapi::um::winuser::{
-    CreateWindowExA, DestroyWindow, GetMessageW, RegisterHotKey, UnregisterHotKey, HWND_MESSAGE,
-    MSG, WM_HOTKEY, WM_NULL, WS_DISABLED, WS_EX_NOACTIVATE,
+    CreateWindowExA, DestroyWindow, GetMessageW, GetSystemMetrics, IsWindow, KillTimer,
+    MsgWaitForMultipleObjectsEx, PeekMessageW, SetTimer, SetWindowTextW, HWND_MESSAGE, MSG,
+    PM_REMOVE, QS_ALLINPUT, SM_SHUTTINGDOWN, WM_HOTKEY, WM_NULL, WM_TIMER, WS_DISABLED,
+    WS_EX_NOACTIVATE,
 };
 
+#[cfg(feature = "test-support")]
+use crate::test_support::{TestHandle, ADVANCE_TIMERS, SIMULATE_REPEAT, SIMULATE_TRIGGER};
 use crate::{
-    error::HkError, get_global_keystate, keys::*, HotkeyCallback, HotkeyId, HotkeyManagerImpl,
-    InterruptHandle,
+    cleanup,
+    combo::{ComboCode, HotkeyCombo},
+    condition::{AccessibilityCondition, CursorRegion, SessionKind, WindowCondition},
+    cycle_callback,
+    diagnostics::{HookProbe, PROBE_KEY},
+    downcast_context,
+    error::HkError,
+    feedback::KeyboardFeedback,
+    gesture::{GestureBinding, Stroke},
+    hook::{current_modifiers, HookBinding, HookHotkeys, REPEAT_FLAG},
+    instance::{to_wide, InstanceCommand, SingleInstance},
+    keys::*,
+    metrics::{Metrics, MetricsRecorder},
+    mode::{ModeObserver, ModeSwitch, Modes},
+    mouse::{MouseButton, MouseHotkeys, MouseWheel},
+    options::{Hotkey, RegisterOptions, TriggerLimit},
+    repeat::RepeatPolicy,
+    send::tap_key,
+    sequence::{Sequence, SequenceStep, Sequences},
+    take_stop_request,
+    window::{capture_trigger_window, is_fullscreen_app_active, trigger_window},
+    wrap_action_handler, wrap_dispatcher, wrap_trigger_callback, ActionHandler, CallbackError,
+    DispatchFn, DoubleTap, ErrorHandler, ErrorSink, EventSink, ExitReason, HotkeyBackend,
+    HotkeyCallback, HotkeyEvent, HotkeyId, HotkeyInfo, HotkeyManagerImpl, HotkeyTarget,
+    InstanceHandler, InterruptHandle, MessageTap, PanicPolicy, Predicate, RegistrationObserver,
+    SharedCallback, SharedContext, TappedMessage, TriggerCallback, TriggerContext, TriggerFeedback,
+    TriggerOutcome, DRAIN_INTERRUPT, INSTANCE_COMMAND,
 };
 
 /// The HotkeyManager is used to register, unregister and await hotkeys with their callback
@@ -21,7 +61,8 @@ use crate::{
 ///
 /// # Note
 /// Due to limitations with the windows event system the HotkeyManager can't be moved to other
-/// threads.
+/// threads. Frameworks that guarantee the thread affinity themselves can use
+/// `assume_same_thread` to opt out of this restriction.
 ///
 pub struct HotkeyManager<T> {
     /// Handle to the hidden window that is used to receive the hotkey events
@@ -30,6 +71,82 @@ pub struct HotkeyManager<T> {
     handlers: HashMap<HotkeyId, HotkeyCallback<T>>,
     /// Automatically set the `ModKey::NoRepeat` when registering hotkeys. Defaults to `true`
     no_repeat: bool,
+    /// Optional dispatcher that receives the matched callbacks instead of executing them directly
+    dispatcher: Option<DispatchFn<T>>,
+    /// Observers that are called after a hotkey was registered
+    registered_observers: Vec<RegistrationObserver>,
+    /// Observers that are called after a hotkey was unregistered
+    unregistered_observers: Vec<RegistrationObserver>,
+    /// Optional function that is called for every message received by the event loop
+    message_tap: Option<MessageTap>,
+    /// Function that is called with the events about triggered hotkeys
+    event_sink: Option<EventSink>,
+    /// The interval per hotkey in which the triggers are merged into one event for the event sink
+    event_throttle: Option<Duration>,
+    /// The triggers that are held back from the event sink while it is throttled, by hotkey
+    throttled: RefCell<HashMap<HotkeyId, ThrottledEvents>>,
+    trigger_feedback: Option<TriggerFeedback>,
+    /// Shows the current mode and the executed hotkeys on the keyboard, see `set_keyboard_feedback`
+    keyboard_feedback: RefCell<Option<Box<dyn KeyboardFeedback>>>,
+    /// Keyboard hook for the hotkeys registered with `register_hooked`. Created on first use
+    hook: Option<HookHotkeys>,
+    /// The low-level mouse hook for mouse hotkeys. Only installed when it is needed
+    mouse_hook: Option<MouseHotkeys>,
+    /// Active timers of the hidden window and the actions to execute when they elapse
+    timers: RefCell<HashMap<usize, TimerAction>>,
+    /// The id for the next timer that is started
+    next_timer_id: Cell<usize>,
+    /// The number of repeats since the last fresh press of each hotkey
+    repeats: RefCell<HashMap<HotkeyId, u32>>,
+    /// True while the queued hotkey messages are processed after `interrupt_after_drain`
+    draining: Cell<bool>,
+    /// How long the event loop polls for messages before it blocks, for lower trigger latency
+    spin_wait: Option<Duration>,
+    /// What happens when a callback panics
+    panic_policy: Option<PanicPolicy>,
+    /// Receives the errors of all hotkeys without an error handler. Shared with the callbacks of
+    /// `register_fallible`, which may be executed by a dispatcher on another thread
+    error_sink: Arc<Mutex<Option<ErrorSink>>>,
+    /// Set when a callback panicked with the `StopLoop` policy
+    panic_stop: Cell<bool>,
+    /// The registered hotkey sequences and their progress
+    sequences: RefCell<Sequences<T>>,
+    /// Key combinations that are registered without a callback to block other applications
+    reserved: HashMap<ComboCode, HotkeyId>,
+    /// The hotkey ids of the aliases registered with `register_aliases`, by the id of the group
+    alias_groups: HashMap<HotkeyId, Vec<HotkeyId>>,
+    /// The current state of the hotkeys registered with `register_toggle`
+    toggles: HashMap<HotkeyId, Arc<AtomicBool>>,
+    /// The named groups that are currently disabled with `set_group_enabled`
+    disabled_groups: HashSet<String>,
+    /// The context that is passed to the callbacks registered with `register_with_context`
+    context: Option<SharedContext>,
+    /// True while all hotkeys are unregistered with `suspend`
+    suspended: bool,
+    /// Function that is called with the commands from other instances
+    instance_handler: Option<InstanceHandler>,
+    /// The current mode and the mode switches
+    modes: RefCell<Modes>,
+    /// The self-metrics of the event loop, see `metrics`
+    metrics: RefCell<MetricsRecorder>,
+    /// Function that executes the hotkeys registered with `register_action`
+    action_handler: Option<ActionHandler<T>>,
+    /// The event of the latest executed trigger of an action hotkey, which is passed on to the
+    /// action handler
+    action_event: RefCell<Option<HotkeyEvent>>,
+    /// The timer that checks for fullscreen applications while the game mode is enabled
+    game_mode_timer: Option<usize>,
+    /// True while the game mode detected a fullscreen application and suppresses the hotkeys
+    fullscreen: Cell<bool>,
+    /// The probe hotkey and the findings of the hook diagnostics, while they are enabled
+    hook_probe: RefCell<Option<HookProbe>>,
+    /// Set while the threadsafe backend is paused. Triggers are discarded while it is set
+    paused: Arc<AtomicBool>,
+    /// The time and cursor position of the latest hotkey message, for the `TriggerContext`
+    trigger_message: Cell<(u32, (i32, i32))>,
+    /// Only let the timers elapse through `TestHandle::advance_timers`
+    #[cfg(feature = "test-support")]
+    manual_timers: bool,
 
     /// Make sure that `HotkeyManager` is not Send / Sync. This prevents it from being moved
     /// between threads, which would prevent hotkey-events from being received.
@@ -38,24 +155,3520 @@ pub struct HotkeyManager<T> {
     _unimpl_send_sync: PhantomData<*const u8>,
 }
 
+/// The result of waiting for a hotkey event
+///
+enum Wait<T> {
+    /// A hotkey callback was executed
+    Executed(T),
+    /// The event loop stopped
+    Stopped(ExitReason),
+    /// No callback was executed within the timeout
+    Idle,
+}
+
+/// Action that is executed when a timer of the `HotkeyManager` elapses
+///
+#[derive(Debug, Clone, Copy)]
+enum TimerAction {
+    /// Execute the regular callback of the hotkey, because it was not pressed a second time
+    /// within the double-tap window. The `lparam` is the one of the hotkey message
+    SingleTap { id: HotkeyId, lparam: isize },
+    /// Execute the callback of the long-press hotkey if it is still held down. The `lparam` is the
+    /// one of the hotkey message
+    LongPress { id: HotkeyId, lparam: isize },
+    /// Repeat the callback of the hotkey if it is still held down. The interval is the time until
+    /// the next repeat
+    Repeat {
+        id: HotkeyId,
+        interval: Duration,
+        count: u32,
+    },
+    /// Disarm the hotkey sequences, because the next step was not pressed in time
+    SequenceTimeout,
+    /// Return to the default mode, because no hotkey was pressed in time
+    ModeTimeout,
+    /// Check if a fullscreen application is in the foreground for the game mode
+    GameModeCheck,
+    /// Pass the merged triggers of the hotkey to the throttled event sink
+    FlushEvents { id: HotkeyId },
+    /// Inject the next probe of the hook diagnostics
+    HookProbe,
+}
+
+/// The triggers of a hotkey that are held back from the event sink while it is throttled
+///
+struct ThrottledEvents {
+    /// When the current throttle interval started
+    since: Instant,
+    /// The latest held back trigger, with the number of held back triggers in `merged`
+    pending: Option<HotkeyEvent>,
+    /// The timer that passes the pending event on at the end of the interval
+    timer: Option<usize>,
+}
+
 impl<T> Default for HotkeyManager<T> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<T> HotkeyManager<T> {
-    /// Enable or disable the automatically applied `ModKey::NoRepeat` modifier. By default, this
-    /// option is set to `true` which causes all hotkey registration calls to add the `NoRepeat`
-    /// modifier, thereby disabling automatic retriggers of hotkeys when holding down the keys.
+impl<T> HotkeyManager<T> {
+    /// Wrap the `HotkeyManager` into a `SendHotkeyManager`, which implements `Send`. This is an
+    /// escape hatch for embedding the manager into frameworks that require `Send`, but guarantee
+    /// the thread affinity themselves.
+    ///
+    /// # Safety
+    /// The hidden window and the hotkeys are bound to the thread that created the
+    /// `HotkeyManager`. The caller must ensure that the returned `SendHotkeyManager` is only used
+    /// and dropped on that thread, even if it is moved between threads in the meantime.
+    ///
+    pub unsafe fn assume_same_thread(self) -> SendHotkeyManager<T> {
+        SendHotkeyManager(self)
+    }
+
+    /// Enable or disable the automatically applied `ModKey::NoRepeat` modifier. By default, this
+    /// option is set to `true` which causes all hotkey registration calls to add the `NoRepeat`
+    /// modifier, thereby disabling automatic retriggers of hotkeys when holding down the keys.
+    ///
+    /// When this option is disabled, the `ModKey::NoRepeat` can still be manually added while
+    /// registering hotkeys. To override the option for a single hotkey in either direction, use
+    /// `register_with_repeat`.
+    ///
+    /// Note: Setting this flag doesn't change previously registered hotkeys. It only applies to
+    /// registrations performed after calling this function.
+    pub fn set_no_repeat(&mut self, no_repeat: bool) {
+        self.no_repeat = no_repeat;
+    }
+
+    /// Restrict a registered hotkey to only trigger while the cursor is inside of the given screen
+    /// region. Passing `None` removes the restriction again.
+    ///
+    pub fn set_cursor_region(
+        &mut self,
+        id: HotkeyId,
+        region: Option<CursorRegion>,
+    ) -> Result<(), HkError> {
+        for member in self.members(id) {
+            let handler = self
+                .handlers
+                .get_mut(&member)
+                .ok_or(HkError::InvalidHotkeyId(id))?;
+            handler.cursor_region = region.clone();
+        }
+        Ok(())
+    }
+
+    /// Set what happens when a callback panics. Passing `None` removes the policy again, so the
+    /// panic unwinds out of the event loop. See `PanicPolicy` for details.
+    ///
+    pub fn set_panic_policy(&mut self, policy: Option<PanicPolicy>) {
+        self.panic_policy = policy;
+    }
+
+    /// Enable or disable a registered hotkey. Disabled hotkeys stay registered, so the key
+    /// combination stays reserved, but their callback is not executed until they are enabled
+    /// again. Triggers of disabled hotkeys are reported to the event sink as rejected.
+    ///
+    pub fn set_enabled(&mut self, id: HotkeyId, enabled: bool) -> Result<(), HkError> {
+        for member in self.members(id) {
+            let handler = self
+                .handlers
+                .get_mut(&member)
+                .ok_or(HkError::InvalidHotkeyId(id))?;
+            // The trigger count restarts when a disabled hotkey is enabled again
+            if enabled && !handler.enabled.get() {
+                handler.triggers.store(0, Ordering::Relaxed);
+            }
+            handler.enabled.set(enabled);
+        }
+        Ok(())
+    }
+
+    /// Register a key combination that switches the manager into a different mode, similar to the
+    /// modes of i3. Hotkeys are added to a mode with `RegisterOptions::mode` and are only
+    /// registered with windows while their mode is active, so a mode can use short combinations
+    /// or even single keys that are passed on to other applications in all other modes. Hotkeys
+    /// without a mode are active in every mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` and `key_modifiers` - The key combination of the switch.
+    ///
+    /// * `from` - The mode in which the switch is active, or `None` if it is active in all modes.
+    ///
+    /// * `to` - The mode that is entered, or `None` to return to the default mode.
+    ///
+    /// The returned id can be used to unregister the switch with `unregister`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use windows_hotkeys::{
+    ///     keys::{ModKey, VKey},
+    ///     options::RegisterOptions,
+    ///     HotkeyManager, HotkeyManagerImpl,
+    /// };
+    ///
+    /// let mut hkm = HotkeyManager::new();
+    /// hkm.register_mode_switch(VKey::R, &[ModKey::Win], None, Some("resize"))
+    ///     .unwrap();
+    /// hkm.register_mode_switch(VKey::Escape, &[], Some("resize"), None)
+    ///     .unwrap();
+    ///
+    /// let resize = RegisterOptions {
+    ///     mode: Some("resize".to_string()),
+    ///     ..Default::default()
+    /// };
+    /// hkm.register_with_options(VKey::H, &[], resize.clone(), || println!("Shrink"))
+    ///     .unwrap();
+    /// hkm.register_with_options(VKey::L, &[], resize, || println!("Grow"))
+    ///     .unwrap();
+    ///
+    /// hkm.event_loop();
+    /// ```
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_mode_switch(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> Result<HotkeyId, HkError> {
+        let id = HotkeyId(self.id_offset);
+        self.id_offset += 1;
+
+        let switch = ModeSwitch {
+            key,
+            modifiers: key_modifiers.to_vec(),
+            from: from.map(str::to_string),
+            to: to.map(str::to_string),
+        };
+        if !self.suspended && self.modes.get_mut().is_active(from) {
+            self.register_switch(id, &switch)?;
+        }
+
+        self.modes.get_mut().switches.insert(id, switch);
+        Ok(id)
+    }
+
+    /// Switch to a mode, or to the default mode with `None`. The hotkeys of the previous mode are
+    /// unregistered and the ones of the new mode are registered.
+    ///
+    /// Hotkeys of the new mode that can't be registered, for example because another application
+    /// registered the key combination, are returned with the reason. They stay part of the mode
+    /// and are registered again the next time the mode is entered.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-unregisterhotkey>
+    ///
+    pub fn set_mode(&mut self, mode: Option<&str>) -> Vec<(HotkeyId, HkError)> {
+        self.switch_mode(mode.map(str::to_string))
+    }
+
+    /// Get the currently active mode, `None` for the default mode
+    ///
+    pub fn mode(&self) -> Option<String> {
+        self.modes.borrow().current.clone()
+    }
+
+    /// Leave the mode automatically and return to the default mode if no hotkey is pressed for the
+    /// given time after the mode was entered or the last hotkey was pressed. Passing `None`
+    /// removes the timeout, so the mode is only left with a mode switch.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-settimer>
+    ///
+    pub fn set_mode_timeout(&mut self, mode: &str, timeout: Option<Duration>) {
+        let modes = self.modes.get_mut();
+        match timeout {
+            Some(timeout) => modes.timeouts.insert(mode.to_string(), timeout),
+            None => modes.timeouts.remove(mode),
+        };
+        self.restart_mode_timeout();
+    }
+
+    /// Set a function that is called with the new mode whenever the mode changes, for example to
+    /// show the current mode in a status bar. The function is called on the thread of the event
+    /// loop.
+    ///
+    pub fn on_mode_change(&mut self, observer: impl Fn(Option<&str>) + Send + 'static) {
+        self.set_mode_observer(Some(Box::new(observer)));
+    }
+
+    /// Set or remove the mode change observer. Used by the threadsafe backend.
+    ///
+    pub(crate) fn set_mode_observer(&mut self, observer: Option<ModeObserver>) {
+        self.modes.get_mut().observer = observer;
+    }
+
+    /// Accept the commands that new instances of the program send with `RunningInstance::send`.
+    /// The handler is called with every command on the thread of the event loop, while the event
+    /// loop is running. See `SingleInstance`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setwindowtextw>
+    ///
+    pub fn accept_instance_commands(
+        &mut self,
+        instance: &SingleInstance,
+        handler: impl Fn(InstanceCommand) + Send + 'static,
+    ) {
+        self.set_instance_handler(&instance.window_title(), Box::new(handler));
+    }
+
+    /// Make the hidden window findable under the title and set the handler for the commands from
+    /// other instances. Used by the threadsafe backend.
+    ///
+    pub(crate) fn set_instance_handler(&mut self, title: &str, handler: InstanceHandler) {
+        let title = to_wide(title);
+        unsafe { SetWindowTextW(self.hwnd.0, title.as_ptr()) };
+        self.instance_handler = Some(handler);
+    }
+
+    /// Suspend all hotkeys, for example for a "pause hotkeys" toggle. All hotkeys and sequences
+    /// are unregistered from windows and the hooks, so the key combinations reach other
+    /// applications again, but they are kept by the manager and can be restored with `resume`.
+    /// Combinations that were reserved with `reserve` stay reserved.
+    ///
+    /// While the manager is suspended, new hotkeys can be registered and existing ones changed
+    /// as usual, but they are only registered with windows once the manager is resumed.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-unregisterhotkey>
+    ///
+    pub fn suspend(&mut self) {
+        if self.suspended {
+            return;
+        }
+
+        for (&id, handler) in &self.handlers {
+            let _ = self.deactivate(id, handler);
+        }
+        self.set_switches_active(false);
+        self.suspended = true;
+
+        // Pending double-taps, long-presses and repeats are dropped
+        let timer_ids: Vec<_> = self.timers.get_mut().keys().copied().collect();
+        for timer_id in timer_ids {
+            self.stop_timer(timer_id);
+        }
+
+        let mut seqs = self.sequences.borrow_mut();
+        seqs.armed.clear();
+        self.sync_sequence_steps(&mut seqs);
+    }
+
+    /// Register all hotkeys and sequences again after they were suspended with `suspend`.
+    ///
+    /// Hotkeys that can't be registered anymore, for example because another application
+    /// registered the key combination in the meantime, are removed from the manager. They are
+    /// returned with the reason, so they can be reported or registered again with a different
+    /// key combination.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn resume(&mut self) -> Vec<(HotkeyId, HkError)> {
+        if !self.suspended {
+            return Vec::new();
+        }
+        self.suspended = false;
+
+        let mut ids: Vec<_> = self.handlers.keys().copied().collect();
+        ids.sort_by_key(|id| id.0);
+
+        let mut failed = Vec::new();
+        for id in ids {
+            let handler = self.handlers.remove(&id).unwrap();
+            let res = self.activate(id, &handler);
+            self.handlers.insert(id, handler);
+            if let Err(err) = res {
+                self.remove_handler(id);
+                failed.push((id, err));
+            }
+        }
+
+        failed.extend(self.set_switches_active(true));
+
+        let mut seqs = self.sequences.borrow_mut();
+        self.sync_sequence_steps(&mut seqs);
+        failed
+    }
+
+    /// Check if the hotkeys are currently suspended with `suspend`
+    ///
+    pub fn is_suspended(&self) -> bool {
+        self.suspended
+    }
+
+    /// Enable the game mode: while a fullscreen application like a game is in the foreground,
+    /// all hotkeys that are marked with `set_suppress_in_fullscreen` are unregistered, so their
+    /// key combinations reach the application. They are registered again as soon as the
+    /// fullscreen application is left.
+    ///
+    /// The foreground window is checked in the given interval while the event loop is running,
+    /// see `window::is_fullscreen_app_active` for what counts as fullscreen. Hotkeys that fail to
+    /// register again are reported as the last error in the `metrics`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use windows_hotkeys::{keys::{ModKey, VKey}, HotkeyManager, HotkeyManagerImpl};
+    ///
+    /// let mut hkm = HotkeyManager::new();
+    /// let id = hkm.register(VKey::Tab, &[ModKey::Alt, ModKey::Shift], || ()).unwrap();
+    /// hkm.set_suppress_in_fullscreen(id, true).unwrap();
+    /// hkm.enable_game_mode(Duration::from_millis(500));
+    /// hkm.event_loop();
+    /// ```
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-settimer>
+    ///
+    pub fn enable_game_mode(&mut self, interval: Duration) {
+        if let Some(timer_id) = self.game_mode_timer.take() {
+            self.stop_timer(timer_id);
+        }
+        self.game_mode_timer = self.start_timer(interval, TimerAction::GameModeCheck);
+        self.check_fullscreen();
+    }
+
+    /// Disable the game mode and register the suppressed hotkeys again
+    ///
+    pub fn disable_game_mode(&mut self) {
+        if let Some(timer_id) = self.game_mode_timer.take() {
+            self.stop_timer(timer_id);
+        }
+        self.set_fullscreen(false);
+    }
+
+    /// Enable the hook diagnostics, to find out why hotkeys stop working or lag while another
+    /// application is running. In the given interval, an unassigned key is injected as a probe
+    /// and the time until it arrives in the event loop as a hotkey is measured. On the way, the
+    /// probe passes the low-level keyboard hooks of all applications, like AutoHotkey, anti-cheat
+    /// software or accessibility tools, the same way that real key presses do.
+    ///
+    /// The findings are reported as errors with the id of the probe hotkey, so they reach the
+    /// error sink (see `set_error_sink`) and the last error of the `metrics`:
+    /// - `HkError::HookChainBlocked` if a probe didn't arrive before the next one was due
+    /// - `HkError::HookChainSlow` if a probe took longer than `threshold` to arrive
+    ///
+    /// The statistics of all probes are included in the `metrics`. Probes are skipped while
+    /// modifier keys are held down, and the measured time includes callbacks that block the event
+    /// loop. Software that only blocks injected input also swallows the probes, even if real key
+    /// presses still pass.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use windows_hotkeys::singlethreaded::HotkeyManager;
+    ///
+    /// let mut hkm = HotkeyManager::<()>::new();
+    /// hkm.set_error_sink(|_, err| eprintln!("{}", err));
+    /// hkm.enable_hook_diagnostics(Duration::from_secs(5), Duration::from_millis(50))
+    ///     .unwrap();
+    /// hkm.event_loop();
+    /// ```
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-sendinput>
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-settimer>
+    ///
+    pub fn enable_hook_diagnostics(
+        &mut self,
+        interval: Duration,
+        threshold: Duration,
+    ) -> Result<(), HkError> {
+        self.disable_hook_diagnostics();
+
+        let id = HotkeyId(self.id_offset);
+        self.id_offset += 1;
+
+        cleanup::register_hotkey(
+            self.hwnd.0,
+            id.0,
+            ModKey::NoRepeat.to_mod_code(),
+            PROBE_KEY.to_vk_code() as u32,
+        )?;
+
+        let mut probe = HookProbe::new(id, threshold);
+        probe.timer = self.start_timer(interval, TimerAction::HookProbe);
+        *self.hook_probe.get_mut() = Some(probe);
+        Ok(())
+    }
+
+    /// Disable the hook diagnostics. Their findings are removed from the `metrics`
+    ///
+    pub fn disable_hook_diagnostics(&mut self) {
+        if let Some(probe) = self.hook_probe.get_mut().take() {
+            if let Some(timer_id) = probe.timer {
+                self.stop_timer(timer_id);
+            }
+            cleanup::unregister_hotkey(self.hwnd.0, probe.id.0);
+        }
+    }
+
+    /// Share the flag that pauses the execution of triggered hotkeys. Used by the threadsafe
+    /// backend, so it can be paused without a round trip to the backend thread.
+    ///
+    #[cfg(feature = "threadsafe")]
+    pub(crate) fn set_pause_flag(&mut self, paused: Arc<AtomicBool>) {
+        self.paused = paused;
+    }
+
+    /// Check if the game mode currently suppresses the hotkeys, because a fullscreen application
+    /// is in the foreground
+    ///
+    pub fn is_game_mode_active(&self) -> bool {
+        self.fullscreen.get()
+    }
+
+    /// Mark a registered hotkey to be unregistered while the game mode detects a fullscreen
+    /// application, see `enable_game_mode`. If a fullscreen application is in the foreground
+    /// right now, the hotkey is unregistered or registered again right away.
+    ///
+    pub fn set_suppress_in_fullscreen(
+        &mut self,
+        id: HotkeyId,
+        suppress: bool,
+    ) -> Result<(), HkError> {
+        for member in self.members(id) {
+            let handler = self
+                .handlers
+                .get(&member)
+                .ok_or(HkError::InvalidHotkeyId(id))?;
+            if handler.suppress_in_fullscreen.get() == suppress {
+                continue;
+            }
+
+            let was_parked = self.is_parked(handler);
+            handler.suppress_in_fullscreen.set(suppress);
+            match (was_parked, self.is_parked(handler)) {
+                (false, true) => {
+                    let _ = self.uninstall(member, handler);
+                }
+                (true, false) => self.install(member, handler)?,
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    /// Add a registered hotkey to a named group or, with `None`, remove it from its group. A hotkey
+    /// belongs to at most one group. If the group is disabled, the hotkey is disabled with it.
+    ///
+    pub fn set_group(&mut self, id: HotkeyId, group: Option<&str>) -> Result<(), HkError> {
+        let group_enabled = group.is_none_or(|group| self.is_group_enabled(group));
+        for member in self.members(id) {
+            let handler = self
+                .handlers
+                .get_mut(&member)
+                .ok_or(HkError::InvalidHotkeyId(id))?;
+            handler.group = group.map(str::to_string);
+            handler.group_enabled.set(group_enabled);
+        }
+        Ok(())
+    }
+
+    /// Enable or disable all hotkeys of a named group at once, for example to disable all editing
+    /// hotkeys while a game is running. Like with `set_enabled`, the hotkeys stay registered and
+    /// only their callbacks are not executed, triggers are reported to the event sink as rejected
+    /// with `RejectReason::GroupDisabled`.
+    ///
+    /// The state is kept per group name, so hotkeys that are added to a disabled group later are
+    /// disabled as well. Enabling a group doesn't enable hotkeys that were disabled on their own
+    /// with `set_enabled`.
+    ///
+    pub fn set_group_enabled(&mut self, group: &str, enabled: bool) {
+        match enabled {
+            true => self.disabled_groups.remove(group),
+            false => self.disabled_groups.insert(group.to_string()),
+        };
+
+        for handler in self.handlers.values() {
+            if handler.group.as_deref() != Some(group) {
+                continue;
+            }
+            // The trigger count restarts when a disabled hotkey is enabled again
+            if enabled && !handler.group_enabled.get() {
+                handler.triggers.store(0, Ordering::Relaxed);
+            }
+            handler.group_enabled.set(enabled);
+        }
+    }
+
+    /// Check if a named group is enabled. Groups are enabled until they are disabled with
+    /// `set_group_enabled`, even if no hotkey belongs to them.
+    ///
+    pub fn is_group_enabled(&self, group: &str) -> bool {
+        !self.disabled_groups.contains(group)
+    }
+
+    /// Replace the callback of a registered hotkey, without unregistering it. This is useful when
+    /// the action of a fixed key combination depends on the state of the application. The new
+    /// callback is used for all aliases of the hotkey.
+    ///
+    /// The callback replaces everything that was executed before, so hotkeys registered with
+    /// `register_toggle` or `register_counted` lose their toggling or counting, and hotkeys
+    /// registered with `register_action`, `register_with_trigger` or `register_fallible` become
+    /// plain hotkeys. The double-tap and long-press settings of the hotkey are kept.
+    ///
+    pub fn set_callback(
+        &mut self,
+        id: HotkeyId,
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<(), HkError> {
+        let callback: SharedCallback<T> = Arc::new(Mutex::new(Box::new(callback)));
+        for member in self.members(id) {
+            let handler = self
+                .handlers
+                .get_mut(&member)
+                .ok_or(HkError::InvalidHotkeyId(id))?;
+            handler.target = HotkeyTarget::Callback(callback.clone());
+            handler.once = false;
+        }
+        Ok(())
+    }
+
+    /// Set the context that is passed to the callbacks registered with `register_with_context`.
+    /// The context is stored once in the manager and shared by all of these callbacks, so they
+    /// don't need to capture their own clones of the application state.
+    ///
+    /// Callbacks keep the context that was set when they were registered, so setting a new
+    /// context only affects the hotkeys that are registered afterwards.
+    ///
+    pub fn set_context<C: Send + Sync + 'static>(&mut self, context: C) {
+        self.context = Some(Arc::new(context));
+    }
+
+    /// Get the context that was set with `set_context`. Returns `None` if no context is set or if
+    /// it is not of the type `C`.
+    ///
+    pub fn context<C: Send + Sync + 'static>(&self) -> Option<Arc<C>> {
+        downcast_context(&self.context).ok()
+    }
+
+    /// Register a new hotkey with a callback that receives a reference to the context of the
+    /// manager, which is set with `set_context`. Otherwise the same as `register`.
+    ///
+    /// Returns `HkError::NoContext` if no context of the type `C` is set.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::sync::atomic::{AtomicU32, Ordering};
+    /// use windows_hotkeys::{keys::{ModKey, VKey}, HotkeyManager, HotkeyManagerImpl};
+    ///
+    /// struct Counter(AtomicU32);
+    ///
+    /// let mut hkm = HotkeyManager::new();
+    /// hkm.set_context(Counter(AtomicU32::new(0)));
+    /// hkm.register_with_context(VKey::Up, ModKey::Alt, |counter: &Counter| {
+    ///     counter.0.fetch_add(1, Ordering::Relaxed);
+    /// })
+    /// .unwrap();
+    /// hkm.register_with_context(VKey::P, ModKey::Alt, |counter: &Counter| {
+    ///     println!("{}", counter.0.load(Ordering::Relaxed));
+    /// })
+    /// .unwrap();
+    ///
+    /// hkm.event_loop();
+    /// ```
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_with_context<C: Send + Sync + 'static>(
+        &mut self,
+        key: VKey,
+        key_modifiers: impl IntoModifiers,
+        callback: impl Fn(&C) -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let context = downcast_context::<C>(&self.context)?;
+        self.register(key, key_modifiers, move || callback(&context))
+    }
+
+    /// Get the information about all registered hotkeys, like their key combinations and labels,
+    /// ordered by their ids. This can be used to display a list of the current bindings.
+    ///
+    /// Hotkey sequences are not included, since they don't have a single key combination.
+    ///
+    pub fn hotkeys(&self) -> Vec<HotkeyInfo> {
+        let mut hotkeys: Vec<_> = self
+            .handlers
+            .iter()
+            .map(|(&id, handler)| handler.info(id))
+            .collect();
+        hotkeys.sort_by_key(|info| info.id.0);
+        hotkeys
+    }
+
+    /// Get the number of times that the callback of a hotkey was executed since it was registered
+    /// or, if it was disabled in between, since it was enabled again. This is the same value as
+    /// `HotkeyEvent::trigger_count` of the latest trigger.
+    ///
+    pub fn trigger_count(&self, id: HotkeyId) -> Result<u64, HkError> {
+        let member = self.members(id).into_iter().next().unwrap_or(id);
+        self.handlers
+            .get(&member)
+            .map(|handler| handler.triggers.load(Ordering::Relaxed))
+            .ok_or(HkError::InvalidHotkeyId(id))
+    }
+
+    /// Get a snapshot of the self-metrics of the manager, like the number of processed messages
+    /// and the average time that the callbacks take. See `Metrics`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use windows_hotkeys::{HotkeyManager, HotkeyManagerImpl};
+    ///
+    /// let hkm = HotkeyManager::<()>::new();
+    /// let metrics = hkm.metrics();
+    /// println!(
+    ///     "{} callbacks, {:?} on average",
+    ///     metrics.callbacks_run, metrics.average_dispatch_time
+    /// );
+    /// ```
+    ///
+    pub fn metrics(&self) -> Metrics {
+        let mut metrics = self.metrics.borrow().snapshot();
+        metrics.hook_chain = self.hook_probe.borrow().as_ref().map(HookProbe::snapshot);
+        metrics
+    }
+
+    /// Change the key combination of a registered hotkey while keeping its id, callback and all
+    /// other settings. If the hotkey was registered with `ModKey::NoRepeat`, it is kept for the
+    /// new combination.
+    ///
+    /// If the new combination can't be registered, the old one stays registered and the error is
+    /// returned. If the old combination can't be registered again either, because another
+    /// application took it in the meantime, the hotkey is removed and `HkError::NotRegistered` is
+    /// returned. The registration observers are notified as if the old hotkey was unregistered
+    /// and the new one registered.
+    ///
+    /// Aliases can't be rebound, since the id of an alias group stands for multiple key
+    /// combinations. Rebinding it returns `HkError::InvalidHotkeyId`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-unregisterhotkey>
+    ///
+    pub fn rebind(
+        &mut self,
+        id: HotkeyId,
+        key: VKey,
+        key_modifiers: &[ModKey],
+    ) -> Result<(), HkError> {
+        let mut handler = self
+            .handlers
+            .remove(&id)
+            .ok_or(HkError::InvalidHotkeyId(id))?;
+        let old = handler.info(id);
+
+        if let Err(err) = self.deactivate(id, &handler) {
+            self.handlers.insert(id, handler);
+            return Err(err);
+        }
+
+        let mut modifiers = key_modifiers.to_vec();
+        if old.modifiers.contains(&ModKey::NoRepeat) && !modifiers.contains(&ModKey::NoRepeat) {
+            modifiers.push(ModKey::NoRepeat);
+        }
+        handler.key = key;
+        handler.modifiers = modifiers;
+
+        if let Err(err) = self.activate(id, &handler) {
+            // Restore the old combination, which was registered just before
+            handler.key = old.key;
+            handler.modifiers = old.modifiers;
+            let restored = self.activate(id, &handler);
+            self.handlers.insert(id, handler);
+            if restored.is_err() {
+                self.remove_handler(id);
+                return Err(HkError::NotRegistered(id));
+            }
+            return Err(err);
+        }
+
+        let info = handler.info(id);
+        self.handlers.insert(id, handler);
+        self.unregistered_observers.iter().for_each(|obs| obs(&old));
+        self.registered_observers.iter().for_each(|obs| obs(&info));
+        Ok(())
+    }
+
+    /// Get the key combination that a registered hotkey is currently bound to. This reflects
+    /// changes made with `rebind`.
+    ///
+    /// Alias groups don't have a single key combination, so their ids return
+    /// `HkError::InvalidHotkeyId`. Use `hotkeys` to get the combinations of all aliases.
+    ///
+    pub fn combo(&self, id: HotkeyId) -> Result<HotkeyCombo, HkError> {
+        self.handlers
+            .get(&id)
+            .map(|handler| handler.combo())
+            .ok_or(HkError::InvalidHotkeyId(id))
+    }
+
+    /// Same as `register`, but returns a `HotkeyHandle` instead of the id. The handle borrows the
+    /// manager, so it is meant for configuring the hotkey right after the registration. Use
+    /// `handle` to get a handle for an existing id later on.
+    ///
+    pub fn register_handle(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyHandle<'_, T>, HkError> {
+        let id = self.register(key, key_modifiers, callback)?;
+        Ok(HotkeyHandle { id, hkm: self })
+    }
+
+    /// Get a `HotkeyHandle` for a registered hotkey
+    ///
+    pub fn handle(&mut self, id: HotkeyId) -> Result<HotkeyHandle<'_, T>, HkError> {
+        if !self.handlers.contains_key(&id) && !self.alias_groups.contains_key(&id) {
+            return Err(HkError::InvalidHotkeyId(id));
+        }
+        Ok(HotkeyHandle { id, hkm: self })
+    }
+
+    /// Restrict a registered hotkey to only trigger in the given kind of session, for example to
+    /// ignore it inside of remote desktop sessions. Passing `None` removes the restriction again.
+    ///
+    pub fn set_allowed_session(
+        &mut self,
+        id: HotkeyId,
+        session: Option<SessionKind>,
+    ) -> Result<(), HkError> {
+        for member in self.members(id) {
+            let handler = self
+                .handlers
+                .get_mut(&member)
+                .ok_or(HkError::InvalidHotkeyId(id))?;
+            handler.session = session;
+        }
+        Ok(())
+    }
+
+    /// Restrict a registered hotkey to only trigger if the accessibility condition is met, for
+    /// example only while a screen reader is running. Passing `None` removes the restriction
+    /// again.
+    ///
+    pub fn set_accessibility_condition(
+        &mut self,
+        id: HotkeyId,
+        condition: Option<AccessibilityCondition>,
+    ) -> Result<(), HkError> {
+        for member in self.members(id) {
+            let handler = self
+                .handlers
+                .get_mut(&member)
+                .ok_or(HkError::InvalidHotkeyId(id))?;
+            handler.accessibility = condition;
+        }
+        Ok(())
+    }
+
+    /// Restrict a registered hotkey to only trigger while the foreground window meets the
+    /// condition, for example only while Firefox is focused. The foreground window is captured
+    /// when the hotkey is triggered, before the callback is executed. Passing `None` removes the
+    /// restriction again.
+    ///
+    /// For hotkeys that are detected by a hook, the key is passed on to the focused application
+    /// while the condition is not met, the same as for `set_condition`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use windows_hotkeys::{
+    ///     condition::{TextPattern, WindowCondition},
+    ///     keys::{ModKey, VKey},
+    ///     HotkeyManager, HotkeyManagerImpl,
+    /// };
+    ///
+    /// let mut hkm = HotkeyManager::new();
+    /// let id = hkm.register(VKey::R, &[ModKey::Alt], || println!("Reader view")).unwrap();
+    ///
+    /// let firefox = WindowCondition::class(TextPattern::Exact("MozillaWindowClass".to_string()));
+    /// hkm.set_window_condition(id, Some(firefox)).unwrap();
+    /// ```
+    ///
+    pub fn set_window_condition(
+        &mut self,
+        id: HotkeyId,
+        condition: Option<WindowCondition>,
+    ) -> Result<(), HkError> {
+        for member in self.members(id) {
+            let handler = self
+                .handlers
+                .get_mut(&member)
+                .ok_or(HkError::InvalidHotkeyId(id))?;
+            handler.window = condition.clone();
+        }
+        self.sync_hook_conditions(id);
+        Ok(())
+    }
+
+    /// Restrict a registered hotkey to only trigger while the condition function returns true,
+    /// for example depending on the state of the application. The function is called every time
+    /// the hotkey is pressed, after all other conditions are met.
+    ///
+    /// For hotkeys that are detected by a hook, the function is also called from the hook thread
+    /// when the key is pressed. While it returns false, the key is passed on to the focused
+    /// application as if the hotkey wasn't registered, even if the hotkey blocks the key
+    /// otherwise. The function should return quickly in this case, since the keyboard input is
+    /// stalled until it returns.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::sync::{
+    ///     atomic::{AtomicBool, Ordering},
+    ///     Arc,
+    /// };
+    /// use windows_hotkeys::{keys::{ModKey, VKey}, HotkeyManager, HotkeyManagerImpl};
+    ///
+    /// let armed = Arc::new(AtomicBool::new(false));
+    /// let mut hkm = HotkeyManager::new();
+    /// let id = hkm.register(VKey::F9, &[ModKey::Ctrl], || println!("Armed")).unwrap();
+    ///
+    /// let flag = armed.clone();
+    /// hkm.set_condition(id, move || flag.load(Ordering::SeqCst)).unwrap();
+    /// ```
+    ///
+    pub fn set_condition(
+        &mut self,
+        id: HotkeyId,
+        condition: impl Fn() -> bool + Send + Sync + 'static,
+    ) -> Result<(), HkError> {
+        self.update_condition(id, Some(Arc::new(condition)))
+    }
+
+    /// Remove the condition function of a registered hotkey, see `set_condition`
+    ///
+    pub fn clear_condition(&mut self, id: HotkeyId) -> Result<(), HkError> {
+        self.update_condition(id, None)
+    }
+
+    /// Unregister or disable a registered hotkey after the given number of executed triggers, or
+    /// remove the limit with `None`. Triggers that were executed before the limit was set count
+    /// towards it. See `TriggerLimit`.
+    ///
+    /// The limit of hotkeys registered with `register_once` can't be changed, since their
+    /// callback can only be executed once. For them, `HkError::OneShotHotkey` is returned.
+    ///
+    pub fn set_trigger_limit(
+        &mut self,
+        id: HotkeyId,
+        limit: Option<TriggerLimit>,
+    ) -> Result<(), HkError> {
+        let members = self.members(id);
+        if members
+            .iter()
+            .any(|member| self.handlers.get(member).is_some_and(|h| h.once))
+        {
+            return Err(HkError::OneShotHotkey(id));
+        }
+
+        for member in members {
+            let handler = self
+                .handlers
+                .get_mut(&member)
+                .ok_or(HkError::InvalidHotkeyId(id))?;
+            handler.limit = limit;
+        }
+        Ok(())
+    }
+
+    /// Set a function that receives the errors that only concern this hotkey, instead of the whole
+    /// manager. This allows plugin hosts to route the problems of a binding back to the plugin
+    /// that owns it. The errors are also recorded in the `metrics`.
+    ///
+    /// The reported errors are:
+    /// - `HkError::CallbackPanicked` if the callback panicked while a `PanicPolicy` was set
+    /// - `HkError::NoActionHandler` if the action hotkey was triggered without an action handler
+    /// - `HkError::RegistrationFailed` if the hotkey couldn't be registered again when its mode
+    ///   was activated or the game mode stopped suppressing it
+    ///
+    /// Errors of hotkeys without an error handler are passed to the error sink, see
+    /// `set_error_sink`.
+    ///
+    pub fn set_error_handler(
+        &mut self,
+        id: HotkeyId,
+        on_error: impl Fn(&HkError) + Send + Sync + 'static,
+    ) -> Result<(), HkError> {
+        self.update_error_handler(id, Some(Arc::new(on_error)))
+    }
+
+    /// Remove the error handler of a registered hotkey, see `set_error_handler`
+    ///
+    pub fn clear_error_handler(&mut self, id: HotkeyId) -> Result<(), HkError> {
+        self.update_error_handler(id, None)
+    }
+
+    /// Set a function that receives the errors of all hotkeys that have no error handler of their
+    /// own, together with the id of the hotkey. This includes the errors that are returned by the
+    /// callbacks of `register_fallible`, so failures end up in one place instead of being handled
+    /// or swallowed inside every callback.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use windows_hotkeys::keys::{ModKey, VKey};
+    /// use windows_hotkeys::singlethreaded::HotkeyManager;
+    ///
+    /// let mut hkm = HotkeyManager::<()>::new();
+    /// hkm.set_error_sink(|id, err| eprintln!("hotkey {:?} failed: {}", id, err));
+    /// hkm.register_fallible(VKey::S, &[ModKey::Ctrl, ModKey::Alt], || {
+    ///     std::fs::write("notes.txt", "saved")
+    /// })
+    /// .unwrap();
+    /// ```
+    ///
+    pub fn set_error_sink(&mut self, sink: impl Fn(HotkeyId, &HkError) + Send + Sync + 'static) {
+        self.set_error_sink_fn(Some(Arc::new(sink)));
+    }
+
+    /// Remove a previously set error sink. The errors of hotkeys without an error handler are
+    /// dropped again
+    ///
+    pub fn remove_error_sink(&mut self) {
+        self.set_error_sink_fn(None);
+    }
+
+    pub(crate) fn set_error_sink_fn(&mut self, sink: Option<ErrorSink>) {
+        *self.error_sink.lock().unwrap_or_else(|e| e.into_inner()) = sink;
+    }
+
+    /// Replace the error handler of all hotkeys of the alias group
+    ///
+    pub(crate) fn update_error_handler(
+        &mut self,
+        id: HotkeyId,
+        on_error: Option<ErrorHandler>,
+    ) -> Result<(), HkError> {
+        for member in self.members(id) {
+            let handler = self
+                .handlers
+                .get_mut(&member)
+                .ok_or(HkError::InvalidHotkeyId(id))?;
+            handler.on_error = on_error.clone();
+        }
+        Ok(())
+    }
+
+    /// Replace the condition function of all hotkeys of the alias group, including the bindings of
+    /// the hooks
+    ///
+    pub(crate) fn update_condition(
+        &mut self,
+        id: HotkeyId,
+        condition: Option<Predicate>,
+    ) -> Result<(), HkError> {
+        for member in self.members(id) {
+            let handler = self
+                .handlers
+                .get_mut(&member)
+                .ok_or(HkError::InvalidHotkeyId(id))?;
+            handler.condition = condition.clone();
+        }
+        self.sync_hook_conditions(id);
+        Ok(())
+    }
+
+    /// Copy the conditions of all hotkeys of the alias group to their bindings in the hooks, so
+    /// the hooks pass the keys on while the conditions are not met
+    ///
+    fn sync_hook_conditions(&self, id: HotkeyId) {
+        for member in self.members(id) {
+            let Some(handler) = self.handlers.get(&member) else {
+                continue;
+            };
+
+            if let Some(hook) = &self.hook {
+                hook.set_conditions(member, handler.condition.clone(), handler.window.clone());
+            }
+            if let Some(mouse_hook) = &self.mouse_hook {
+                mouse_hook.set_conditions(
+                    member,
+                    handler.condition.clone(),
+                    handler.window.clone(),
+                );
+            }
+        }
+    }
+
+    /// Register a new hotkey and choose if it is retriggered while the keys are held down,
+    /// independent of the manager wide `set_no_repeat` option. This allows a single hotkey to
+    /// repeat while all other hotkeys use `ModKey::NoRepeat`, or the other way around.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`, `key_modifiers` and `callback` - Same as for `register`. A `ModKey::NoRepeat` in
+    ///   `key_modifiers` is ignored, since `repeat` decides about it.
+    ///
+    /// * `repeat` - If true, the hotkey is retriggered by the automatic key repeat when holding
+    ///   down the keys. If false, `ModKey::NoRepeat` is added.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_with_repeat(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        repeat: bool,
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let options = RegisterOptions {
+            no_repeat: Some(!repeat),
+            ..Default::default()
+        };
+        self.register_with_options(key, key_modifiers, options, callback)
+    }
+
+    /// Register a hotkey that was built with the `HotkeyBuilder`. This is the same as calling
+    /// `register_with_options` with the key, modifiers and options of the hotkey.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn add(&mut self, hotkey: Hotkey<T>) -> Result<HotkeyId, HkError>
+    where
+        T: 'static,
+    {
+        self.register_with_options(
+            hotkey.key,
+            &hotkey.modifiers,
+            hotkey.options,
+            hotkey.callback,
+        )
+    }
+
+    /// Register a new hotkey whose callback receives the trigger count, which is `1` for the first
+    /// trigger since the registration, `2` for the second and so on. This allows implementing
+    /// first-time behavior, cycling actions or toggles without keeping external state. If the
+    /// hotkey is disabled with `set_enabled`, the count restarts when it is enabled again.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_counted(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        callback: impl Fn(u64) -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let triggers = Arc::new(AtomicU64::new(0));
+        let count = triggers.clone();
+
+        let id = self.register_with_options(
+            key,
+            key_modifiers,
+            RegisterOptions::default(),
+            move || callback(count.load(Ordering::Relaxed)),
+        )?;
+        if let Some(handler) = self.handlers.get_mut(&id) {
+            handler.triggers = triggers;
+        }
+        Ok(id)
+    }
+
+    /// Register a new hotkey that is unregistered from windows after its first trigger, so its
+    /// callback can be a `FnOnce`. This is handy for "press any hotkey to continue" or temporary
+    /// capture flows. Triggers that were already queued behind the first one are dropped.
+    ///
+    /// The id stays valid after the trigger, so unregistering it is possible but not required.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-unregisterhotkey>
+    ///
+    pub fn register_once(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        callback: impl FnOnce() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let options = RegisterOptions {
+            trigger_limit: Some(TriggerLimit::Unregister(1)),
+            ..Default::default()
+        };
+
+        // The trigger limit makes sure that the callback is only executed once, since it can't be
+        // changed for one-shot hotkeys
+        let callback = Mutex::new(Some(callback));
+        let mut handler = HotkeyCallback::new(
+            Arc::new(Mutex::new(Box::new(move || {
+                let callback = callback.lock().unwrap_or_else(|e| e.into_inner()).take();
+                callback.expect("one-shot hotkeys are only executed once")()
+            }))),
+            key,
+        );
+        handler.once = true;
+        self.register_handler_with_options(handler, key_modifiers, options, None)
+    }
+
+    /// Register a new hotkey whose callback receives the `TriggerContext` of each trigger, with
+    /// the id and key combination of the hotkey, the time of the trigger and the cursor position
+    /// at that time.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use windows_hotkeys::keys::{ModKey, VKey};
+    /// use windows_hotkeys::singlethreaded::HotkeyManager;
+    ///
+    /// let mut hkm = HotkeyManager::new();
+    /// hkm.register_with_trigger(VKey::M, &[ModKey::Win], |trigger| {
+    ///     println!("{} pressed at {:?}", trigger.combo, trigger.cursor);
+    /// })
+    /// .unwrap();
+    /// ```
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_with_trigger(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        callback: impl Fn(&TriggerContext) -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError>
+    where
+        T: 'static,
+    {
+        let target = HotkeyTarget::Trigger(wrap_trigger_callback(callback));
+        let handler = HotkeyCallback::for_target(target, key);
+        self.register_handler_with_options(handler, key_modifiers, RegisterOptions::default(), None)
+    }
+
+    /// Register a new hotkey whose callback returns a `Result`. When the callback fails, the error
+    /// is passed to the error sink as `HkError::CallbackFailed` together with the id of the
+    /// hotkey, and `T::default()` is used as the return value of the callback.
+    ///
+    /// The errors always go to the error sink, since the callback can also be executed by a
+    /// dispatcher on another thread. See `set_error_sink` for an example.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_fallible<E>(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        callback: impl Fn() -> Result<T, E> + Send + 'static,
+    ) -> Result<HotkeyId, HkError>
+    where
+        T: Default + 'static,
+        E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+    {
+        self.register_fallible_fn(
+            key,
+            key_modifiers,
+            move || callback().map_err(Into::into),
+            T::default,
+        )
+    }
+
+    /// Same as `register_fallible`, but the return value for failed callbacks is created by
+    /// `fallback`, so `T` doesn't need to implement `Default`
+    ///
+    pub(crate) fn register_fallible_fn(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        callback: impl Fn() -> Result<T, CallbackError> + Send + 'static,
+        fallback: fn() -> T,
+    ) -> Result<HotkeyId, HkError>
+    where
+        T: 'static,
+    {
+        let error_sink = self.error_sink.clone();
+        self.register_with_trigger(key, key_modifiers, move |trigger| {
+            callback().unwrap_or_else(|err| {
+                let err = HkError::CallbackFailed(trigger.id, err);
+                let sink = error_sink.lock().unwrap_or_else(|e| e.into_inner()).clone();
+                if let Some(sink) = sink {
+                    sink(trigger.id, &err);
+                }
+                fallback()
+            })
+        })
+    }
+
+    /// Register a new hotkey that switches between an on and an off state with every press, like
+    /// mute / unmute or start / stop recording. The hotkey starts in the off state, so the first
+    /// press executes `on_enable`, the second press `on_disable` and so on. The current state can
+    /// be queried with `is_on`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_toggle(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        on_enable: impl Fn() -> T + Send + 'static,
+        on_disable: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let state = Arc::new(AtomicBool::new(false));
+        let toggle = state.clone();
+
+        let id = self.register_with_options(
+            key,
+            key_modifiers,
+            RegisterOptions::default(),
+            move || match toggle.fetch_xor(true, Ordering::Relaxed) {
+                false => on_enable(),
+                true => on_disable(),
+            },
+        )?;
+        self.toggles.insert(id, state);
+        Ok(id)
+    }
+
+    /// Register a new hotkey that only carries an action name instead of a callback. When it is
+    /// triggered, the action handler that is set with `set_action_handler` is executed with the
+    /// action name and the event of the trigger.
+    ///
+    /// This decouples loading the key bindings, for example combos and action names from a
+    /// config file, from the code that implements the actions. The bindings can be registered
+    /// before the action handler is set. Triggers of action hotkeys are ignored while no action
+    /// handler is set.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use windows_hotkeys::{keys::{ModKey, VKey}, HotkeyManager, HotkeyManagerImpl};
+    ///
+    /// let mut hkm = HotkeyManager::new();
+    /// hkm.register_action(VKey::C, &[ModKey::Ctrl, ModKey::Alt], "copy").unwrap();
+    /// hkm.register_action(VKey::V, &[ModKey::Ctrl, ModKey::Alt], "paste").unwrap();
+    ///
+    /// hkm.set_action_handler(|action, event| match action {
+    ///     "copy" => println!("Copy with {}", event.combo),
+    ///     "paste" => println!("Paste with {}", event.combo),
+    ///     _ => (),
+    /// });
+    /// hkm.event_loop();
+    /// ```
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_action(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        action: &str,
+    ) -> Result<HotkeyId, HkError> {
+        self.register_action_combo(&HotkeyCombo::new(key, key_modifiers), action)
+    }
+
+    /// Same as `register_action`, but the key combination is taken from a `HotkeyCombo`, for
+    /// example one that was parsed from a configuration.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_action_combo(
+        &mut self,
+        combo: &HotkeyCombo,
+        action: &str,
+    ) -> Result<HotkeyId, HkError> {
+        let options = RegisterOptions {
+            extra_keys: combo.extra_keys.clone(),
+            ..Default::default()
+        };
+
+        let handler =
+            HotkeyCallback::for_target(HotkeyTarget::Action(action.to_string()), combo.key);
+        self.register_handler_with_options(handler, &combo.modifiers, options, None)
+    }
+
+    /// Set the function that executes the hotkeys registered with `register_action`. It is called
+    /// with the action name and the event of the trigger, and its return value is returned from
+    /// `handle_hotkey` the same as the return value of a regular callback. Replaces the previous
+    /// action handler.
+    ///
+    pub fn set_action_handler(&mut self, handler: impl Fn(&str, &HotkeyEvent) -> T + Send + 'static)
+    where
+        T: 'static,
+    {
+        self.set_action_handler_fn(Some(wrap_action_handler(handler)));
+    }
+
+    /// Remove the action handler, so triggers of action hotkeys are ignored again
+    ///
+    pub fn remove_action_handler(&mut self) {
+        self.set_action_handler_fn(None);
+    }
+
+    pub(crate) fn set_action_handler_fn(&mut self, handler: Option<ActionHandler<T>>) {
+        self.action_handler = handler;
+    }
+
+    /// Check if a hotkey that was registered with `register_toggle` is currently in the on state.
+    /// Returns `HkError::InvalidHotkeyId` if the id doesn't belong to a toggle hotkey.
+    ///
+    pub fn is_on(&self, id: HotkeyId) -> Result<bool, HkError> {
+        self.toggles
+            .get(&id)
+            .map(|state| state.load(Ordering::Relaxed))
+            .ok_or(HkError::InvalidHotkeyId(id))
+    }
+
+    /// Register a new hotkey with additional per-hotkey options. The options combine settings
+    /// that otherwise require separate calls after the registration, like `set_enabled` or
+    /// `set_cursor_region`, and allow labeling the hotkey for the `HotkeyInfo`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`, `key_modifiers` and `callback` - Same as for `register`. If
+    ///   `RegisterOptions::no_repeat` is set, a `ModKey::NoRepeat` in `key_modifiers` is ignored.
+    ///
+    /// * `options` - The options for the hotkey. `RegisterOptions::default()` registers the
+    ///   hotkey the same way as `register`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_with_options(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        options: RegisterOptions,
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let handler = HotkeyCallback::new(Arc::new(Mutex::new(Box::new(callback))), key);
+        self.register_handler_with_options(handler, key_modifiers, options, None)
+    }
+
+    /// Apply the modifiers and options to the handler and register it. All registration methods
+    /// end up here, so the options behave the same for every kind of hotkey.
+    ///
+    fn register_handler_with_options(
+        &mut self,
+        mut handler: HotkeyCallback<T>,
+        key_modifiers: &[ModKey],
+        options: RegisterOptions,
+        fallback: Option<HotkeyBackend>,
+    ) -> Result<HotkeyId, HkError> {
+        handler.modifiers = match options.no_repeat {
+            Some(no_repeat) => ModKey::with_repeat(key_modifiers, !no_repeat),
+            None => self.apply_no_repeat(key_modifiers),
+        };
+        self.register_handler(handler.with_options(options), fallback)
+    }
+
+    /// Register a new hotkey that is detected by a low-level keyboard hook instead of registering
+    /// it with windows. This allows using key combinations that are reserved by the OS, for example
+    /// using the Win key as the only modifier for arbitrary keys like Win + E.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`, `key_modifiers` and `callback` - Same as for `register`.
+    ///
+    /// * `block` - If true, the key event is blocked, so the default action of the OS (like
+    ///   Explorer opening for Win + E) or the focused application is not executed. The release of
+    ///   the Win or ALT key is masked so that it doesn't open the start menu or the menu bar. If
+    ///   false, the callback is executed in addition to the default action.
+    ///
+    /// # Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setwindowshookexw>
+    ///
+    pub fn register_hooked(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        block: bool,
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        self.register_backend(
+            key,
+            key_modifiers,
+            &[],
+            HotkeyBackend::Hook { block },
+            None,
+            callback,
+        )
+    }
+
+    /// Register a new hotkey with a mouse button as the main key, for example `CTRL + MiddleClick`
+    /// or `WIN + RightClick`. Mouse buttons can't be registered with windows, so the hotkey is
+    /// detected by a low-level mouse hook. Apart from that, it behaves like any other hotkey.
+    ///
+    /// # Arguments
+    ///
+    /// * `button` - The mouse button that triggers the hotkey
+    ///
+    /// * `key_modifiers` and `callback` - Same as for `register`.
+    ///
+    /// * `block` - If true, the click is blocked, so it doesn't reach the application under the
+    ///   cursor. If false, the callback is executed in addition to the regular click.
+    ///
+    /// # Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setwindowshookexw>
+    ///
+    pub fn register_mouse(
+        &mut self,
+        button: MouseButton,
+        key_modifiers: &[ModKey],
+        block: bool,
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        self.register_backend(
+            button.to_vkey(),
+            key_modifiers,
+            &[],
+            HotkeyBackend::Mouse { block },
+            None,
+            callback,
+        )
+    }
+
+    /// Register a new hotkey that is triggered by scrolling the mouse wheel in the given direction,
+    /// for example `CTRL + WheelUp`. Same as for `register_mouse`, it is detected by a low-level
+    /// mouse hook. Every scroll step triggers the hotkey once and the wheel delta of the step is
+    /// reported in the `HotkeyEvent`.
+    ///
+    /// # Arguments
+    ///
+    /// * `direction` - The scroll direction that triggers the hotkey
+    ///
+    /// * `key_modifiers` and `callback` - Same as for `register`.
+    ///
+    /// * `block` - If true, the scrolling is blocked, so it doesn't reach the application under
+    ///   the cursor.
+    ///
+    /// # Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setwindowshookexw>
+    ///
+    pub fn register_wheel(
+        &mut self,
+        direction: MouseWheel,
+        key_modifiers: &[ModKey],
+        block: bool,
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        self.register_backend(
+            direction.to_vkey(),
+            key_modifiers,
+            &[],
+            HotkeyBackend::Mouse { block },
+            None,
+            callback,
+        )
+    }
+
+    /// Register a mouse gesture that is drawn while the given mouse button is held, for example
+    /// holding the right button and moving the cursor down and then to the right. The cursor
+    /// movement is split into strokes of at least `gesture::STROKE_DISTANCE` pixels in one of the
+    /// four directions. When the button is released and the drawn strokes match, the callback is
+    /// executed.
+    ///
+    /// While the button is held, the press is blocked. If the button is released without moving
+    /// the cursor, the click is sent again so the button keeps its regular function.
+    ///
+    /// # Arguments
+    ///
+    /// * `button` - The mouse button that is held while drawing the gesture
+    ///
+    /// * `key_modifiers` and `callback` - Same as for `register`.
+    ///
+    /// * `strokes` - The strokes of the gesture. At least one stroke is required.
+    ///
+    /// # Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setwindowshookexw>
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-sendinput>
+    ///
+    pub fn register_gesture(
+        &mut self,
+        button: MouseButton,
+        key_modifiers: &[ModKey],
+        strokes: &[Stroke],
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        if strokes.is_empty() {
+            return Err(HkError::EmptyGesture);
+        }
+
+        let mut handler =
+            HotkeyCallback::new(Arc::new(Mutex::new(Box::new(callback))), button.to_vkey());
+        handler.backend = HotkeyBackend::Gesture;
+        handler.gesture = Some(strokes.to_vec());
+        self.register_handler_with_options(handler, key_modifiers, RegisterOptions::default(), None)
+    }
+
+    /// Register a new hotkey that executes the actions in turn on successive presses, for example
+    /// to toggle between keyboard layouts or cycle through audio devices. The first press
+    /// executes the first action, the second press the second action and so on. After the last
+    /// action, the cycle starts again with the first one.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` and `key_modifiers` - Same as for `register`.
+    ///
+    /// * `actions` - The callbacks to cycle through. At least one action is required.
+    ///
+    /// * `reset_after` - If set, the cycle starts again with the first action when the hotkey
+    ///   wasn't pressed for this duration.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_cycle(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        actions: Vec<Box<dyn Fn() -> T + Send + 'static>>,
+        reset_after: Option<Duration>,
+    ) -> Result<HotkeyId, HkError>
+    where
+        T: 'static,
+    {
+        if actions.is_empty() {
+            return Err(HkError::NoActions);
+        }
+
+        self.register(key, key_modifiers, cycle_callback(actions, reset_after))
+    }
+
+    /// Register a new hotkey that only executes the callback after the key combination was held
+    /// down for the given duration, for example holding `WIN + SPACE` for 800ms. If the keys are
+    /// released early, the trigger is cancelled.
+    ///
+    /// `ModKey::NoRepeat` is always added for long-press hotkeys.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-settimer>
+    ///
+    pub fn register_long_press(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        hold: Duration,
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let mut key_modifiers = key_modifiers.to_vec();
+        if !key_modifiers.contains(&ModKey::NoRepeat) {
+            key_modifiers.push(ModKey::NoRepeat);
+        }
+
+        let id = self.register_backend(
+            key,
+            &key_modifiers,
+            &[],
+            HotkeyBackend::RegisterHotKey,
+            None,
+            callback,
+        )?;
+        if let Some(handler) = self.handlers.get_mut(&id) {
+            handler.long_press = Some(hold);
+        }
+        Ok(id)
+    }
+
+    /// Set a separate callback that is executed when the hotkey is pressed twice within the given
+    /// time window. For example double-tapping `CTRL + C` can execute a different action than
+    /// pressing it once.
+    ///
+    /// While a double-tap callback is set, the regular callback is only executed after the time
+    /// window expired without a second press. `ModKey::NoRepeat` should be used, so that holding
+    /// the hotkey down doesn't count as a double-tap.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-settimer>
+    ///
+    pub fn set_double_tap(
+        &mut self,
+        id: HotkeyId,
+        window: Duration,
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<(), HkError> {
+        self.set_double_tap_fn(id, Some((window, Box::new(callback))))
+    }
+
+    /// Remove the double-tap callback of a hotkey, so that the regular callback is executed
+    /// immediately again.
+    ///
+    pub fn remove_double_tap(&mut self, id: HotkeyId) -> Result<(), HkError> {
+        self.set_double_tap_fn(id, None)
+    }
+
+    /// Set or remove the double-tap callback. Used by the threadsafe backend.
+    ///
+    pub(crate) fn set_double_tap_fn(
+        &mut self,
+        id: HotkeyId,
+        double_tap: Option<(Duration, Box<dyn Fn() -> T + Send + 'static>)>,
+    ) -> Result<(), HkError> {
+        let double_tap = double_tap.map(|(window, callback)| {
+            let callback: SharedCallback<T> = Arc::new(Mutex::new(callback));
+            (window, callback)
+        });
+
+        for member in self.members(id) {
+            let handler = self
+                .handlers
+                .get_mut(&member)
+                .ok_or(HkError::InvalidHotkeyId(id))?;
+            handler.double_tap = double_tap
+                .clone()
+                .map(|(window, callback)| DoubleTap { window, callback });
+        }
+        Ok(())
+    }
+
+    /// Register a new hotkey that is triggered by tapping a modifier key on its own, meaning that
+    /// the modifier is pressed and released without any other key in between. For example
+    /// tapping `ModKey::Win` can be used to open a launcher instead of the start menu.
+    ///
+    /// The modifier tap is detected by a low-level keyboard hook and the default action of the
+    /// tap, like opening the start menu or activating the menu bar, is suppressed.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setwindowshookexw>
+    ///
+    pub fn register_modifier_tap(
+        &mut self,
+        modifier: ModKey,
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        if modifier == ModKey::NoRepeat {
+            return Err(HkError::NotTappable(modifier));
+        }
+
+        let mut handler =
+            HotkeyCallback::new(Arc::new(Mutex::new(Box::new(callback))), modifier.into());
+        handler.backend = HotkeyBackend::ModifierTap;
+
+        // The modifier tap is detected by the hook, which never repeats it
+        let options = RegisterOptions {
+            no_repeat: Some(false),
+            ..Default::default()
+        };
+        self.register_handler_with_options(handler, &[modifier], options, None)
+    }
+
+    /// Register multiple alternative key combinations that all execute the same callback. The
+    /// returned id controls all of the aliases together, for example when unregistering the
+    /// hotkey or setting a cursor region. The alias that was pressed is reported in the
+    /// `HotkeyEvent`.
+    ///
+    /// If one of the aliases can't be registered, none of them are registered.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_aliases(
+        &mut self,
+        combos: &[HotkeyCombo],
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        if combos.is_empty() {
+            return Err(HkError::NoCombos);
+        }
+
+        let group_id = HotkeyId(self.id_offset);
+        self.id_offset += 1;
+
+        let callback: SharedCallback<T> = Arc::new(Mutex::new(Box::new(callback)));
+        let triggers = Arc::new(AtomicU64::new(0));
+        let mut members = Vec::new();
+
+        for combo in combos {
+            let mut handler = HotkeyCallback::new(callback.clone(), combo.key);
+            handler.triggers = triggers.clone();
+
+            let options = RegisterOptions {
+                extra_keys: combo.extra_keys.clone(),
+                ..Default::default()
+            };
+            match self.register_handler_with_options(handler, &combo.modifiers, options, None) {
+                Ok(id) => members.push(id),
+                Err(err) => {
+                    for id in members {
+                        let _ = self.unregister(id);
+                    }
+                    return Err(err);
+                }
+            }
+        }
+
+        self.alias_groups.insert(group_id, members);
+        Ok(group_id)
+    }
+
+    /// Get the ids of the hotkeys that are controlled by the given id. This is the id itself or
+    /// all aliases of an alias group.
+    ///
+    fn members(&self, id: HotkeyId) -> Vec<HotkeyId> {
+        match self.alias_groups.get(&id) {
+            Some(members) => members.clone(),
+            None => vec![id],
+        }
+    }
+
+    /// Get the id that is known to the user for a registered hotkey, which is the id of the alias
+    /// group for aliases
+    ///
+    fn group_of(&self, id: HotkeyId) -> HotkeyId {
+        self.alias_groups
+            .iter()
+            .find(|(_, members)| members.contains(&id))
+            .map_or(id, |(&group_id, _)| group_id)
+    }
+
+    /// Add `ModKey::NoRepeat` to the modifiers if `no_repeat` is set
+    ///
+    fn apply_no_repeat(&self, key_modifiers: &[ModKey]) -> Vec<ModKey> {
+        let mut key_modifiers = key_modifiers.to_vec();
+        if self.no_repeat && !key_modifiers.contains(&ModKey::NoRepeat) {
+            key_modifiers.push(ModKey::NoRepeat);
+        }
+        key_modifiers
+    }
+
+    /// Reserve a key combination without a callback. The combination is registered with windows,
+    /// so other applications can't register it and the key presses are swallowed, but nothing is
+    /// executed when it is pressed. This is useful to block combinations in kiosk or exam
+    /// software.
+    ///
+    /// Reserving an already reserved combination does nothing.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn reserve(&mut self, key: VKey, key_modifiers: &[ModKey]) -> Result<(), HkError> {
+        let combo = HotkeyCombo::new(key, key_modifiers).code();
+        if self.reserved.contains_key(&combo) {
+            return Ok(());
+        }
+
+        let id = HotkeyId(self.id_offset);
+        self.id_offset += 1;
+
+        cleanup::register_hotkey(
+            self.hwnd.0,
+            id.0,
+            combo.1 | ModKey::NoRepeat.to_mod_code(),
+            key.to_vk_code() as u32,
+        )?;
+
+        self.reserved.insert(combo, id);
+        Ok(())
+    }
+
+    /// Release a key combination that was reserved with `reserve`, so it can be used by other
+    /// applications again.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-unregisterhotkey>
+    ///
+    pub fn release(&mut self, key: VKey, key_modifiers: &[ModKey]) -> Result<(), HkError> {
+        let combo = HotkeyCombo::new(key, key_modifiers).code();
+        let id = self
+            .reserved
+            .remove(&combo)
+            .ok_or(HkError::UnregistrationFailed)?;
+
+        match cleanup::unregister_hotkey(self.hwnd.0, id.0) {
+            false => Err(HkError::UnregistrationFailed),
+            true => Ok(()),
+        }
+    }
+
+    /// Register a multi-step hotkey sequence, like `CTRL + K` followed by `CTRL + C`. The callback
+    /// is executed when all steps are pressed in order, with at most the sequence timeout between
+    /// two steps (see `set_sequence_timeout`).
+    ///
+    /// Only the first step is registered with windows permanently. Pressing it arms the sequence
+    /// and registers the next step until the sequence is completed or times out, so the other
+    /// steps can still be used normally. Multiple sequences can start with the same steps.
+    ///
+    /// The returned id can be used to unregister the sequence with `unregister`.
+    ///
+    /// # Note
+    /// Steps that are also registered as regular hotkeys of this `HotkeyManager` can't be armed,
+    /// since windows only allows registering every key combination once.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-settimer>
+    ///
+    pub fn register_sequence(
+        &mut self,
+        steps: &[SequenceStep],
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        if steps.is_empty() {
+            return Err(HkError::NoCombos);
+        }
+
+        self.insert_sequence(steps.to_vec(), false, Box::new(callback))
+    }
+
+    /// Set the leader key combination and the time in which a leader action has to be pressed
+    /// after it. Pressing the leader enters the leader mode, in which the keys registered with
+    /// `register_leader_action` execute their actions, similar to the leader key in vim.
+    ///
+    /// Changing the leader also changes it for all leader actions that are already registered. If
+    /// the new leader can't be registered, the error is returned and the old leader is kept.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn set_leader(&mut self, leader: HotkeyCombo, timeout: Duration) -> Result<(), HkError> {
+        self.sequences
+            .get_mut()
+            .reserve_step_ids(std::slice::from_ref(&leader), &mut self.id_offset);
+        let mut seqs = self.sequences.borrow_mut();
+
+        // The leader is only registered once there are leader actions. It is registered before
+        // anything is changed, so the old leader stays in place if the registration fails
+        let has_actions = seqs.sequences.values().any(|seq| seq.leader);
+        if has_actions && !self.suspended && !seqs.active.contains(&leader.code()) {
+            self.activate_step(&mut seqs, leader.code())?;
+        }
+
+        seqs.sequences
+            .values_mut()
+            .filter(|seq| seq.leader)
+            .for_each(|seq| seq.steps[0] = leader.clone());
+        seqs.leader = Some((leader, timeout));
+        seqs.armed.clear();
+        self.sync_sequence_steps(&mut seqs);
+        Ok(())
+    }
+
+    /// Register an action for the leader mode. The callback is executed when the key combination
+    /// is pressed after the leader, within the leader timeout. The leader mode ends after one
+    /// action. The leader has to be set with `set_leader` first.
+    ///
+    /// The returned id can be used to unregister the action with `unregister`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_leader_action(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let (leader, _) = self
+            .sequences
+            .get_mut()
+            .leader
+            .clone()
+            .ok_or(HkError::NoLeader)?;
+
+        let steps = vec![leader, HotkeyCombo::new(key, key_modifiers)];
+        self.insert_sequence(steps, true, Box::new(callback))
+    }
+
+    /// Add a hotkey sequence and register its first step
+    ///
+    fn insert_sequence(
+        &mut self,
+        steps: Vec<HotkeyCombo>,
+        leader: bool,
+        callback: Box<dyn Fn() -> T + Send + 'static>,
+    ) -> Result<HotkeyId, HkError> {
+        // Reserve the hotkey ids for all step combos that are not known yet
+        let seqs = self.sequences.get_mut();
+        seqs.reserve_step_ids(&steps, &mut self.id_offset);
+
+        let first = steps[0].code();
+        let mut seqs = self.sequences.borrow_mut();
+        if !seqs.active.contains(&first) {
+            self.activate_step(&mut seqs, first)?;
+        }
+
+        let id = HotkeyId(self.id_offset);
+        self.id_offset += 1;
+
+        let callback: SharedCallback<T> = Arc::new(Mutex::new(callback));
+        let sequence = Sequence {
+            steps,
+            callback,
+            leader,
+        };
+        seqs.sequences.insert(id, sequence);
+
+        Ok(id)
+    }
+
+    /// Set the maximum time between two steps of a hotkey sequence. If the next step is not
+    /// pressed in time, the sequence has to be started from the first step again. The default is
+    /// one second.
+    ///
+    pub fn set_sequence_timeout(&mut self, timeout: Duration) {
+        self.sequences.get_mut().timeout = timeout;
+    }
+
+    /// Repeat the callback of a registered hotkey while the hotkey is held down, according to the
+    /// given `RepeatPolicy`. Passing `None` disables the repeating again.
+    ///
+    /// # Note
+    /// The repeating relies on the global key state, which is not updated for keys blocked by a
+    /// keyboard hook. So this doesn't work for hotkeys registered with `register_hooked` and
+    /// `block = true`.
+    ///
+    pub fn set_repeat_policy(
+        &mut self,
+        id: HotkeyId,
+        policy: Option<RepeatPolicy>,
+    ) -> Result<(), HkError> {
+        for member in self.members(id) {
+            let handler = self
+                .handlers
+                .get_mut(&member)
+                .ok_or(HkError::InvalidHotkeyId(id))?;
+            handler.repeat = policy;
+        }
+        Ok(())
+    }
+
+    /// Add an observer that is called with the information about every hotkey after it was
+    /// successfully registered. This can be used to keep UI elements in sync with the active
+    /// hotkeys, regardless of where they were registered.
+    ///
+    pub fn on_registered(&mut self, observer: impl Fn(&HotkeyInfo) + Send + 'static) {
+        self.registered_observers.push(Box::new(observer));
+    }
+
+    /// Add an observer that is called with the information about every hotkey after it was
+    /// successfully unregistered. This includes hotkeys unregistered by `unregister_all`.
+    ///
+    pub fn on_unregistered(&mut self, observer: impl Fn(&HotkeyInfo) + Send + 'static) {
+        self.unregistered_observers.push(Box::new(observer));
+    }
+
+    /// Set a message tap that is called for every message received by the event loop, including
+    /// hotkey messages for unknown ids and interrupts. This is intended for troubleshooting.
+    ///
+    pub fn set_message_tap(&mut self, tap: impl Fn(&TappedMessage) + Send + 'static) {
+        self.set_message_tap_fn(Some(Box::new(tap)));
+    }
+
+    /// Remove a previously set message tap.
+    ///
+    pub fn remove_message_tap(&mut self) {
+        self.set_message_tap_fn(None);
+    }
+
+    /// Set or remove the message tap. Used by the threadsafe backend.
+    ///
+    pub(crate) fn set_message_tap_fn(&mut self, tap: Option<MessageTap>) {
+        self.message_tap = tap;
+    }
+
+    /// Set an event sink that is called for every triggered hotkey, both when the callback is
+    /// executed and when the trigger is rejected because the extra keys or other conditions are
+    /// not met. This helps debugging hotkeys with extra keys, which would otherwise fail silently.
+    ///
+    pub fn set_event_sink(&mut self, sink: impl Fn(&HotkeyEvent) + Send + 'static) {
+        self.set_event_sink_fn(Some(Box::new(sink)));
+    }
+
+    /// Remove a previously set event sink.
+    ///
+    pub fn remove_event_sink(&mut self) {
+        self.set_event_sink_fn(None);
+    }
+
+    /// Set or remove the event sink. Used by the threadsafe backend.
+    ///
+    pub(crate) fn set_event_sink_fn(&mut self, sink: Option<EventSink>) {
+        self.event_sink = sink;
+    }
+
+    /// Throttle the events that are passed to the event sink, so holding down a repeating hotkey
+    /// doesn't flood it. Only the first trigger of a hotkey within the interval is passed on
+    /// directly. The later ones are merged into a single event, which is passed on at the end of
+    /// the interval with the number of merged triggers in `HotkeyEvent::merged`, for example to
+    /// log "fired 87 times in the last second". This continues for every interval in which the
+    /// hotkey is triggered.
+    ///
+    /// `None` passes every trigger on directly again, which is the default. Triggers that are
+    /// held back when the throttling is changed are dropped.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-settimer>
+    ///
+    pub fn set_event_throttle(&mut self, interval: Option<Duration>) {
+        self.event_throttle = interval;
+
+        let states: Vec<_> = self.throttled.get_mut().drain().collect();
+        for (_, state) in states {
+            if let Some(timer_id) = state.timer {
+                self.stop_timer(timer_id);
+            }
+        }
+    }
+
+    /// Enable a low latency mode for the event loop, for example for push-to-talk hotkeys. Before
+    /// the event loop blocks to wait for the next message, it polls the message queue for the
+    /// given duration, yielding to other threads between the polls. Messages that arrive within
+    /// that time, like the release of a held hotkey or the next trigger of a repeating one, are
+    /// handled without the delay of waking up the blocked thread.
+    ///
+    /// This trades CPU time for lower and more consistent latency, since the thread is kept busy
+    /// for the duration after every message. A few milliseconds are usually enough. `None`
+    /// disables the polling again, which is the default.
+    ///
+    /// ## Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-peekmessagew>
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-switchtothread>
+    ///
+    pub fn set_spin_wait(&mut self, spin: Option<Duration>) {
+        self.spin_wait = spin;
+    }
+
+    /// Set a feedback function that is called whenever a hotkey callback is executed, with the
+    /// formatted key combination (like `CONTROL + ALT + M`) and the label of the hotkey, if it was
+    /// registered with one. This is intended to drive an on-screen display or notifications, for
+    /// example showing "CONTROL + ALT + M: Mute". The crate itself doesn't display anything.
+    ///
+    /// Rejected triggers are not reported, see `set_event_sink` for those.
+    ///
+    pub fn set_trigger_feedback(&mut self, feedback: impl Fn(&str, Option<&str>) + Send + 'static) {
+        self.set_trigger_feedback_fn(Some(Box::new(feedback)));
+    }
+
+    /// Set a `KeyboardFeedback` that shows the current mode and the executed hotkeys on the
+    /// keyboard itself, for example through the SDK of an RGB keyboard or with the lock LEDs using
+    /// `LockLedFeedback`. It is told the current mode right away. Replaces the previous keyboard
+    /// feedback.
+    ///
+    pub fn set_keyboard_feedback(&mut self, feedback: impl KeyboardFeedback + 'static) {
+        self.set_keyboard_feedback_box(Some(Box::new(feedback)));
+    }
+
+    /// Remove a previously set keyboard feedback
+    ///
+    pub fn remove_keyboard_feedback(&mut self) {
+        self.set_keyboard_feedback_box(None);
+    }
+
+    /// Set or remove the keyboard feedback. Used by the threadsafe backend.
+    ///
+    pub(crate) fn set_keyboard_feedback_box(
+        &mut self,
+        feedback: Option<Box<dyn KeyboardFeedback>>,
+    ) {
+        let mut feedback = feedback;
+        if let Some(feedback) = feedback.as_mut() {
+            feedback.mode_changed(self.modes.get_mut().current.as_deref());
+        }
+        *self.keyboard_feedback.get_mut() = feedback;
+    }
+
+    /// Remove a previously set trigger feedback function.
+    ///
+    pub fn remove_trigger_feedback(&mut self) {
+        self.set_trigger_feedback_fn(None);
+    }
+
+    /// Set or remove the trigger feedback function. Used by the threadsafe backend.
+    ///
+    pub(crate) fn set_trigger_feedback_fn(&mut self, feedback: Option<TriggerFeedback>) {
+        self.trigger_feedback = feedback;
+    }
+
+    /// Move all hotkeys from another `HotkeyManager` into this one. The hotkeys are unregistered
+    /// from the other manager and registered again with this manager, keeping their callbacks
+    /// and all other settings. This allows composing hotkeys that were registered independently,
+    /// for example by plugins, into a single event loop.
+    ///
+    /// Since the hotkeys receive new ids, a map from the old ids to the results of the new
+    /// registrations is returned. Hotkeys that fail to register again are dropped.
+    ///
+    pub fn absorb(
+        &mut self,
+        mut other: HotkeyManager<T>,
+    ) -> HashMap<HotkeyId, Result<HotkeyId, HkError>> {
+        let handlers = other.take_handlers();
+        self.absorb_handlers(handlers)
+    }
+
+    /// Unregister all hotkeys and return the handlers without notifying any observers. This is
+    /// used to move the hotkeys into a different manager.
+    ///
+    pub(crate) fn take_handlers(&mut self) -> Vec<(HotkeyId, HotkeyCallback<T>)> {
+        let handlers: Vec<_> = self.handlers.drain().collect();
+        for (id, handler) in &handlers {
+            let _ = self.deactivate(*id, handler);
+        }
+        handlers
+    }
+
+    /// Register the handlers taken from a different manager with this manager. Returns the map
+    /// from the old ids to the registration results.
+    ///
+    pub(crate) fn absorb_handlers(
+        &mut self,
+        handlers: Vec<(HotkeyId, HotkeyCallback<T>)>,
+    ) -> HashMap<HotkeyId, Result<HotkeyId, HkError>> {
+        handlers
+            .into_iter()
+            .map(|(old_id, handler)| (old_id, self.register_handler(handler, None)))
+            .collect()
+    }
+
+    /// Register a new hotkey using the given backend. If windows refuses the registration and a
+    /// fallback backend is given, the fallback is used instead. Adds `ModKey::NoRepeat` if
+    /// `no_repeat` is set.
+    ///
+    pub(crate) fn register_backend(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        extra_keys: &[VKey],
+        backend: HotkeyBackend,
+        fallback: Option<HotkeyBackend>,
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let mut handler = HotkeyCallback::new(Arc::new(Mutex::new(Box::new(callback))), key);
+        handler.backend = backend;
+
+        let options = RegisterOptions {
+            extra_keys: extra_keys.to_owned(),
+            ..Default::default()
+        };
+        self.register_handler_with_options(handler, key_modifiers, options, fallback)
+    }
+
+    /// Register the hotkey of the handler with windows under a new id and add the handler to the
+    /// active handlers if successful. If the registration fails, the fallback backend is tried.
+    ///
+    fn register_handler(
+        &mut self,
+        mut handler: HotkeyCallback<T>,
+        fallback: Option<HotkeyBackend>,
+    ) -> Result<HotkeyId, HkError> {
+        let register_id = HotkeyId(self.id_offset);
+        self.id_offset += 1;
+
+        let mut res = self.activate(register_id, &handler);
+        if let (Err(HkError::RegistrationFailed(_)), Some(fallback)) = (&res, fallback) {
+            handler.backend = fallback;
+            res = self.activate(register_id, &handler);
+        }
+        res.map_err(|err| {
+            let err = match &handler.label {
+                Some(label) => err.with_label(label),
+                None => err,
+            };
+            self.metrics.borrow_mut().error(&err);
+            err
+        })?;
+
+        // Hotkeys that are added to a disabled group start out disabled as well
+        let group_disabled = handler
+            .group
+            .as_ref()
+            .is_some_and(|group| self.disabled_groups.contains(group));
+        handler.group_enabled.set(!group_disabled);
+
+        // Add the HotkeyCallback to the handlers when the hotkey was registered
+        let info = handler.info(register_id);
+        self.handlers.insert(register_id, handler);
+        self.registered_observers.iter().for_each(|obs| obs(&info));
+
+        Ok(register_id)
+    }
+
+    /// Start detecting the hotkey of the handler under the given id, either by registering it
+    /// with windows or by adding it to the keyboard hook.
+    ///
+    fn activate(&mut self, id: HotkeyId, handler: &HotkeyCallback<T>) -> Result<(), HkError> {
+        if self.is_parked(handler) {
+            return Ok(());
+        }
+
+        // The hooks post the hotkey messages to the hidden window, so it is required for hooked
+        // hotkeys
+        match handler.backend {
+            HotkeyBackend::RegisterHotKey => (),
+            HotkeyBackend::Hook { .. } | HotkeyBackend::ModifierTap if self.hook.is_none() => {
+                if self.hwnd.0.is_null() {
+                    return Err(HkError::HookFailed);
+                }
+                self.hook = Some(HookHotkeys::new(self.hwnd.0)?);
+            }
+            HotkeyBackend::Mouse { .. } | HotkeyBackend::Gesture if self.mouse_hook.is_none() => {
+                if self.hwnd.0.is_null() {
+                    return Err(HkError::HookFailed);
+                }
+                self.mouse_hook = Some(MouseHotkeys::new(self.hwnd.0)?);
+            }
+            _ => (),
+        }
+
+        self.install(id, handler)
+    }
+
+    /// Check if the hotkey is deliberately not detected right now: while all hotkeys are
+    /// suspended, while its mode is inactive or while it is suppressed by the game mode. Parked
+    /// hotkeys are only activated by `resume`, when their mode is entered or when the fullscreen
+    /// application is left.
+    ///
+    fn is_parked(&self, handler: &HotkeyCallback<T>) -> bool {
+        self.suspended
+            || handler.spent.get()
+            || !self.modes.borrow().is_active(handler.mode.as_deref())
+            || handler.suppress_in_fullscreen.get() && self.fullscreen.get()
+    }
+
+    /// Register the hotkey with windows or add it to the hook that detects it. The hooks must
+    /// already be installed.
+    ///
+    fn install(&self, id: HotkeyId, handler: &HotkeyCallback<T>) -> Result<(), HkError> {
+        match handler.backend {
+            HotkeyBackend::RegisterHotKey => {
+                // Try to register the hotkey combination with windows
+                cleanup::register_hotkey(
+                    self.hwnd.0,
+                    id.0,
+                    ModKey::combine(&handler.modifiers),
+                    handler.key.to_vk_code() as u32,
+                )
+            }
+            HotkeyBackend::Hook { .. } | HotkeyBackend::ModifierTap => {
+                let hook = self.hook.as_ref().ok_or(HkError::HookFailed)?;
+
+                let binding = match (handler.backend, handler.modifiers.first()) {
+                    (HotkeyBackend::ModifierTap, Some(&modifier)) => {
+                        HookBinding::modifier_tap(modifier, true)
+                    }
+                    (HotkeyBackend::Hook { block }, _) => {
+                        HookBinding::new(handler.key, &handler.modifiers, block)
+                    }
+                    _ => return Err(HkError::RegistrationFailed(None)),
+                };
+                let binding =
+                    binding.with_conditions(handler.condition.clone(), handler.window.clone());
+                hook.insert(id, binding);
+                Ok(())
+            }
+            HotkeyBackend::Mouse { block } => {
+                let hook = self.mouse_hook.as_ref().ok_or(HkError::HookFailed)?;
+
+                let binding = HookBinding::new(handler.key, &handler.modifiers, block)
+                    .with_conditions(handler.condition.clone(), handler.window.clone());
+                hook.insert(id, binding);
+                Ok(())
+            }
+            HotkeyBackend::Gesture => {
+                let (Some(button), Some(strokes)) =
+                    (MouseButton::from_vkey(handler.key), &handler.gesture)
+                else {
+                    return Err(HkError::RegistrationFailed(None));
+                };
+
+                let hook = self.mouse_hook.as_ref().ok_or(HkError::HookFailed)?;
+                let gesture = GestureBinding::new(button, &handler.modifiers, strokes);
+                hook.insert_gesture(id, gesture);
+                Ok(())
+            }
+        }
+    }
+
+    /// Stop detecting the hotkey with the given id
+    ///
+    fn deactivate(&self, id: HotkeyId, handler: &HotkeyCallback<T>) -> Result<(), HkError> {
+        // Parked hotkeys were already deactivated
+        if self.is_parked(handler) {
+            return Ok(());
+        }
+
+        self.uninstall(id, handler)
+    }
+
+    /// Unregister the hotkey with windows or remove it from the hook that detects it
+    ///
+    fn uninstall(&self, id: HotkeyId, handler: &HotkeyCallback<T>) -> Result<(), HkError> {
+        let ok = match handler.backend {
+            HotkeyBackend::RegisterHotKey => cleanup::unregister_hotkey(self.hwnd.0, id.0),
+            HotkeyBackend::Hook { .. } | HotkeyBackend::ModifierTap => {
+                self.hook.as_ref().is_some_and(|hook| hook.remove(id))
+            }
+            HotkeyBackend::Mouse { .. } | HotkeyBackend::Gesture => {
+                self.mouse_hook.as_ref().is_some_and(|hook| hook.remove(id))
+            }
+        };
+
+        if ok {
+            Ok(())
+        } else {
+            Err(HkError::UnregistrationFailed)
+        }
+    }
+
+    /// Check if a fullscreen application is in the foreground and suppress or restore the hotkeys
+    /// if that changed
+    ///
+    fn check_fullscreen(&self) {
+        self.set_fullscreen(is_fullscreen_app_active());
+    }
+
+    /// Unregister the hotkeys that are suppressed in fullscreen when a fullscreen application
+    /// enters the foreground, and register them again when it is left
+    ///
+    fn set_fullscreen(&self, fullscreen: bool) {
+        if self.fullscreen.get() == fullscreen {
+            return;
+        }
+
+        let suppressed = self
+            .handlers
+            .iter()
+            .filter(|(_, handler)| handler.suppress_in_fullscreen.get());
+
+        if fullscreen {
+            for (&id, handler) in suppressed {
+                let _ = self.deactivate(id, handler);
+            }
+            self.fullscreen.set(true);
+        } else {
+            self.fullscreen.set(false);
+            for (&id, handler) in suppressed {
+                if self.is_parked(handler) {
+                    continue;
+                }
+                if let Err(err) = self.install(id, handler) {
+                    self.metrics.borrow_mut().error(&err);
+                    self.report_error(id, &err);
+                }
+            }
+        }
+    }
+
+    /// Register the key combination of a mode switch with windows
+    ///
+    fn register_switch(&self, id: HotkeyId, switch: &ModeSwitch) -> Result<(), HkError> {
+        cleanup::register_hotkey(
+            self.hwnd.0,
+            id.0,
+            ModKey::combine(&switch.modifiers) | ModKey::NoRepeat.to_mod_code(),
+            switch.key.to_vk_code() as u32,
+        )
+    }
+
+    /// Register or unregister the mode switches of the current mode and the ones that are active
+    /// in all modes. Returns the switches that couldn't be registered.
+    ///
+    fn set_switches_active(&self, active: bool) -> Vec<(HotkeyId, HkError)> {
+        let modes = self.modes.borrow();
+        let mut failed = Vec::new();
+        for (&id, switch) in &modes.switches {
+            if !modes.is_active(switch.from.as_deref()) {
+                continue;
+            }
+            match active {
+                true => {
+                    if let Err(err) = self.register_switch(id, switch) {
+                        failed.push((id, err));
+                    }
+                }
+                false => {
+                    cleanup::unregister_hotkey(self.hwnd.0, id.0);
+                }
+            }
+        }
+        failed
+    }
+
+    /// Leave the current mode and enter the given one. The hotkeys and mode switches of the
+    /// previous mode are unregistered and the ones of the new mode are registered. Returns the
+    /// hotkeys and mode switches that couldn't be registered.
+    ///
+    /// Only hotkeys with the `RegisterHotKey` backend can belong to a mode, so this doesn't need
+    /// mutable access to the hooks and can be called from the event loop.
+    ///
+    fn switch_mode(&self, mode: Option<String>) -> Vec<(HotkeyId, HkError)> {
+        if self.modes.borrow().current == mode {
+            self.restart_mode_timeout();
+            return Vec::new();
+        }
+
+        let mut failed = Vec::new();
+        let in_current_mode = |m: &Option<String>| m.is_some() && self.modes.borrow().current == *m;
+
+        if !self.suspended {
+            for (&id, handler) in &self.handlers {
+                if in_current_mode(&handler.mode) && !self.is_parked(handler) {
+                    cleanup::unregister_hotkey(self.hwnd.0, id.0);
+                }
+            }
+            for (&id, switch) in &self.modes.borrow().switches {
+                if in_current_mode(&switch.from) {
+                    cleanup::unregister_hotkey(self.hwnd.0, id.0);
+                }
+            }
+        }
+
+        self.modes.borrow_mut().current = mode;
+
+        if !self.suspended {
+            for (&id, handler) in &self.handlers {
+                if !in_current_mode(&handler.mode) || self.is_parked(handler) {
+                    continue;
+                }
+                let registered = cleanup::register_hotkey(
+                    self.hwnd.0,
+                    id.0,
+                    ModKey::combine(&handler.modifiers),
+                    handler.key.to_vk_code() as u32,
+                );
+                if let Err(err) = registered {
+                    self.report_error(id, &err);
+                    failed.push((id, err));
+                }
+            }
+            for (&id, switch) in &self.modes.borrow().switches {
+                if !in_current_mode(&switch.from) {
+                    continue;
+                }
+                if let Err(err) = self.register_switch(id, switch) {
+                    failed.push((id, err));
+                }
+            }
+        }
+
+        self.restart_mode_timeout();
+
+        let modes = self.modes.borrow();
+        if let Some(observer) = &modes.observer {
+            observer(modes.current.as_deref());
+        }
+        if let Some(feedback) = self.keyboard_feedback.borrow_mut().as_mut() {
+            feedback.mode_changed(modes.current.as_deref());
+        }
+        failed
+    }
+
+    /// Restart the timeout of the current mode, or stop it if the mode has no timeout
+    ///
+    fn restart_mode_timeout(&self) {
+        let mut modes = self.modes.borrow_mut();
+        match (modes.current_timeout(), modes.timer) {
+            (Some(timeout), Some(timer_id)) => self.set_timer(timer_id, timeout),
+            (Some(timeout), None) => {
+                modes.timer = self.start_timer(timeout, TimerAction::ModeTimeout)
+            }
+            (None, Some(timer_id)) => {
+                self.stop_timer(timer_id);
+                modes.timer = None;
+            }
+            (None, None) => (),
+        }
+    }
+
+    /// Remove the handler of a hotkey that is not detected anymore, notify the observers and
+    /// remove the hotkey from its alias group
+    ///
+    fn remove_handler(&mut self, id: HotkeyId) {
+        self.toggles.remove(&id);
+        if let Some(handler) = self.handlers.remove(&id) {
+            let info = handler.info(id);
+            self.unregistered_observers
+                .iter()
+                .for_each(|obs| obs(&info));
+        }
+
+        // Remove the hotkey from its alias group, and the group if it was the last alias
+        self.alias_groups
+            .values_mut()
+            .for_each(|members| members.retain(|&member| member != id));
+        self.alias_groups.retain(|_, members| !members.is_empty());
+    }
+
+    /// Set or remove the internal dispatch function. This is the generic version of
+    /// `set_dispatcher` that is also used by the threadsafe backend.
+    ///
+    pub(crate) fn set_dispatch_fn(&mut self, dispatcher: Option<DispatchFn<T>>) {
+        self.dispatcher = dispatcher;
+    }
+
+    /// Start a new timer on the hidden window that executes the action after the delay. Returns
+    /// the id of the timer if it was started.
+    ///
+    fn start_timer(&self, delay: Duration, action: TimerAction) -> Option<usize> {
+        // Timers with ids require a window
+        if self.hwnd.0.is_null() {
+            return None;
+        }
+
+        let timer_id = self.next_timer_id.get();
+        self.next_timer_id.set(timer_id + 1);
+
+        self.set_timer(timer_id, delay);
+        self.timers.borrow_mut().insert(timer_id, action);
+        Some(timer_id)
+    }
+
+    /// Stop a timer that was started with `start_timer`
+    ///
+    fn stop_timer(&self, timer_id: usize) {
+        self.timers.borrow_mut().remove(&timer_id);
+        unsafe { KillTimer(self.hwnd.0, timer_id) };
+    }
+
+    /// Start or restart the timer with the given id
+    ///
+    fn set_timer(&self, timer_id: usize, delay: Duration) {
+        #[cfg(feature = "test-support")]
+        if self.manual_timers {
+            return;
+        }
+
+        let delay = delay.as_millis().min(u32::MAX as u128) as u32;
+        unsafe { SetTimer(self.hwnd.0, timer_id, delay, None) };
+    }
+
+    /// Handle an elapsed timer. Returns the callback result if a callback was executed.
+    ///
+    fn handle_timer(&self, timer_id: usize) -> Option<T> {
+        let action = self.timers.borrow_mut().remove(&timer_id);
+        // Triggers that are started by timers are discarded while the threadsafe backend is
+        // paused, the same as the hotkey messages. They are neither reported nor counted
+        let paused = self.paused.load(Ordering::Relaxed);
+
+        match action {
+            Some(TimerAction::Repeat {
+                id,
+                interval,
+                count,
+            }) => {
+                let handler = self.handlers.get(&id);
+                match (handler, handler.and_then(|h| h.repeat)) {
+                    (Some(handler), Some(policy))
+                        if !paused
+                            && handler.is_enabled()
+                            && !handler.spent.get()
+                            && handler.is_held() =>
+                    {
+                        self.set_timer(timer_id, interval);
+                        self.timers.borrow_mut().insert(
+                            timer_id,
+                            TimerAction::Repeat {
+                                id,
+                                interval: policy.next_interval(interval),
+                                count: count + 1,
+                            },
+                        );
+                        // Repeats are only executed while all modifiers of the hotkey are held
+                        let modifiers =
+                            ModKey::combine(&handler.modifiers) & !ModKey::NoRepeat.to_mod_code();
+                        let outcome = TriggerOutcome::Executed;
+                        self.emit(id, handler.combo(), modifiers as isize, count, outcome);
+                        return self.execute_hotkey(id, handler);
+                    }
+                    _ => (),
+                }
+            }
+            Some(TimerAction::SingleTap { id, lparam }) => {
+                unsafe { KillTimer(self.hwnd.0, timer_id) };
+
+                let handler = self.handlers.get(&id).filter(|_| !paused)?;
+                self.emit(id, handler.combo(), lparam, 0, TriggerOutcome::Executed);
+                return self.execute_hotkey(id, handler);
+            }
+            Some(TimerAction::LongPress { id, lparam }) => {
+                unsafe { KillTimer(self.hwnd.0, timer_id) };
+
+                let handler = self.handlers.get(&id).filter(|h| !paused && h.is_held())?;
+                self.emit(id, handler.combo(), lparam, 0, TriggerOutcome::Executed);
+                return self.execute_hotkey(id, handler);
+            }
+            Some(TimerAction::FlushEvents { id }) => {
+                let mut throttled = self.throttled.borrow_mut();
+                let pending = throttled
+                    .get_mut(&id)
+                    .and_then(|state| state.pending.take());
+
+                match (pending, self.event_throttle, &self.event_sink) {
+                    (Some(pending), Some(interval), Some(sink)) => {
+                        // Keep merging the triggers while the hotkey is triggered in every interval
+                        if let Some(state) = throttled.get_mut(&id) {
+                            state.since = Instant::now();
+                        }
+                        drop(throttled);
+
+                        self.set_timer(timer_id, interval);
+                        self.timers
+                            .borrow_mut()
+                            .insert(timer_id, TimerAction::FlushEvents { id });
+                        sink(&pending);
+                        return None;
+                    }
+                    _ => {
+                        throttled.remove(&id);
+                    }
+                }
+            }
+            Some(TimerAction::GameModeCheck) => {
+                // The timer keeps running until the game mode is disabled
+                self.timers
+                    .borrow_mut()
+                    .insert(timer_id, TimerAction::GameModeCheck);
+                self.check_fullscreen();
+            }
+            Some(TimerAction::HookProbe) => {
+                // The timer keeps running until the diagnostics are disabled
+                self.timers
+                    .borrow_mut()
+                    .insert(timer_id, TimerAction::HookProbe);
+                self.send_hook_probe();
+            }
+            Some(TimerAction::ModeTimeout) => {
+                self.modes.borrow_mut().timer = None;
+                self.switch_mode(None);
+            }
+            Some(TimerAction::SequenceTimeout) => {
+                let mut seqs = self.sequences.borrow_mut();
+                seqs.timer = None;
+                seqs.armed.clear();
+                self.sync_sequence_steps(&mut seqs);
+            }
+            None => (),
+        }
+
+        unsafe { KillTimer(self.hwnd.0, timer_id) };
+        None
+    }
+
+    /// Register a step combo of the hotkey sequences with windows
+    ///
+    fn activate_step(&self, seqs: &mut Sequences<T>, combo: ComboCode) -> Result<(), HkError> {
+        let (key, modifiers) = combo;
+        cleanup::register_hotkey(
+            self.hwnd.0,
+            seqs.step_ids[&combo].0,
+            modifiers | ModKey::NoRepeat.to_mod_code(),
+            key.to_vk_code() as u32,
+        )?;
+
+        seqs.active.insert(combo);
+        Ok(())
+    }
+
+    /// Register the step combos that are needed for the current progress of the hotkey sequences
+    /// and unregister the ones that are not needed anymore. Also restarts the sequence timeout
+    /// while sequences are armed.
+    ///
+    fn sync_sequence_steps(&self, seqs: &mut Sequences<T>) {
+        let mut needed = seqs.prefixes();
+        needed.extend(seqs.next_combos());
+        if self.suspended {
+            needed.clear();
+        }
+
+        let unneeded: Vec<_> = seqs.active.difference(&needed).copied().collect();
+        for combo in unneeded {
+            cleanup::unregister_hotkey(self.hwnd.0, seqs.step_ids[&combo].0);
+            seqs.active.remove(&combo);
+        }
+
+        for combo in needed {
+            if !seqs.active.contains(&combo) {
+                let _ = self.activate_step(seqs, combo);
+            }
+        }
+
+        match (seqs.armed.is_empty(), seqs.timer) {
+            (false, Some(timer_id)) => self.set_timer(timer_id, seqs.armed_timeout()),
+            (false, None) => {
+                let timeout = seqs.armed_timeout();
+                seqs.timer = self.start_timer(timeout, TimerAction::SequenceTimeout)
+            }
+            (true, Some(timer_id)) => {
+                self.stop_timer(timer_id);
+                seqs.timer = None;
+            }
+            (true, None) => (),
+        }
+    }
+
+    /// Advance the hotkey sequences with a pressed step combo and execute the callback of a
+    /// completed sequence
+    ///
+    fn advance_sequences(&self, combo: ComboCode, msg: &MSG) -> Option<T> {
+        let mut seqs = self.sequences.borrow_mut();
+        let completed = seqs.advance(combo);
+        self.sync_sequence_steps(&mut seqs);
+
+        let id = completed?;
+        let sequence = &seqs.sequences[&id];
+        let callback = sequence.callback.clone();
+        let last_step = sequence.steps[sequence.steps.len() - 1].clone();
+        drop(seqs);
+
+        self.emit(id, last_step, msg.lParam, 0, TriggerOutcome::Executed);
+        self.execute(id, &callback)
+    }
+
+    /// Remove a hotkey sequence. Returns false if there is no sequence with the given id
+    ///
+    fn unregister_sequence(&mut self, id: HotkeyId) -> bool {
+        let mut seqs = self.sequences.borrow_mut();
+        if seqs.sequences.remove(&id).is_none() {
+            return false;
+        }
+
+        seqs.armed.clear();
+        self.sync_sequence_steps(&mut seqs);
+        true
+    }
+
+    /// Wait for a single hotkey event and execute the callback if all keys match. If a timeout is
+    /// given, `Wait::Idle` is returned when no callback was executed within the timeout.
+    ///
+    fn wait_hotkey(&self, timeout: Option<Duration>) -> Wait<T> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+        loop {
+            // A callback panicked with the `StopLoop` policy
+            if self.panic_stop.replace(false) {
+                return Wait::Stopped(ExitReason::CallbackPanicked);
+            }
+            if take_stop_request() {
+                return Wait::Stopped(ExitReason::CallbackRequested);
+            }
+
+            let mut msg = std::mem::MaybeUninit::<MSG>::uninit();
+
+            let ok = if self.draining.get() {
+                // While draining, only the already queued hotkey messages are read without
+                // blocking. The interrupt is completed once the queue is empty
+                let found = unsafe {
+                    PeekMessageW(
+                        msg.as_mut_ptr(),
+                        self.hwnd.0,
+                        WM_HOTKEY,
+                        WM_HOTKEY,
+                        PM_REMOVE,
+                    )
+                };
+                if found == 0 {
+                    self.draining.set(false);
+                    return self.stopped(ExitReason::Interrupted);
+                }
+                found
+            } else if let Some(deadline) = deadline {
+                match self.wait_message(msg.as_mut_ptr(), deadline) {
+                    true => 1,
+                    false if unsafe { IsWindow(self.hwnd.0) } == 0 => {
+                        return self.stopped(ExitReason::WindowDestroyed)
+                    }
+                    false => return Wait::Idle,
+                }
+            } else if self.spin_message(msg.as_mut_ptr()) {
+                1
+            } else {
+                // Block and read a message from the message queue. Filtered to receive messages
+                // from WM_NULL to WM_HOTKEY
+                unsafe { GetMessageW(msg.as_mut_ptr(), self.hwnd.0, WM_NULL, WM_HOTKEY) }
+            };
+
+            // `GetMessageW` returns 0 for `WM_QUIT` and -1 if the window is gone
+            match ok {
+                0 => return self.stopped(ExitReason::Quit),
+                -1 => return self.stopped(ExitReason::WindowDestroyed),
+                _ => (),
+            }
+
+            let msg = unsafe { msg.assume_init() };
+            self.metrics.borrow_mut().message();
+
+            if let Some(tap) = &self.message_tap {
+                tap(&self.tapped_message(&msg));
+            }
+
+            // The probes of the hook diagnostics are measured even while paused
+            if WM_HOTKEY == msg.message && self.receive_hook_probe(HotkeyId(msg.wParam as i32)) {
+                continue;
+            }
+
+            if WM_HOTKEY == msg.message && self.paused.load(Ordering::Relaxed) {
+                // Triggers are discarded while the threadsafe backend is paused
+                continue;
+            } else if WM_HOTKEY == msg.message {
+                let hk_id = HotkeyId(msg.wParam as i32);
+                capture_trigger_window();
+                self.trigger_message.set((msg.time, (msg.pt.x, msg.pt.y)));
+
+                // Any hotkey keeps the current mode alive
+                self.restart_mode_timeout();
+
+                // Mode switches don't have a handler
+                let target = self
+                    .modes
+                    .borrow()
+                    .switches
+                    .get(&hk_id)
+                    .map(|s| s.to.clone());
+                if let Some(target) = target {
+                    self.switch_mode(target);
+                    continue;
+                }
+
+                // Step combos of the hotkey sequences don't have a handler
+                let step = self.sequences.borrow().combo_of(hk_id);
+                if let Some(combo) = step {
+                    if let Some(ret) = self.advance_sequences(combo, &msg) {
+                        return Wait::Executed(ret);
+                    }
+                }
+
+                // Get the callback for the received ID
+                // Triggers of one-shot hotkeys that were queued behind the first one are dropped
+                let handler = self.handlers.get(&hk_id).filter(|h| !h.spent.get());
+                if let Some(handler) = handler {
+                    let repeat = self.repeat_index(hk_id, msg.lParam);
+
+                    // Check if all extra conditions like extra keys are met
+                    match handler.check_conditions(&msg) {
+                        Ok(()) if handler.long_press.is_some() => {
+                            self.start_long_press(hk_id, handler, &msg)
+                        }
+                        Ok(()) if handler.double_tap.is_some() => {
+                            if let Some(ret) = self.handle_tap(hk_id, handler, &msg) {
+                                return Wait::Executed(ret);
+                            }
+                        }
+                        Ok(()) => {
+                            let outcome = TriggerOutcome::Executed;
+                            self.emit(hk_id, handler.combo(), msg.lParam, repeat, outcome);
+                            self.start_repeat(hk_id, handler);
+                            if let Some(ret) = self.execute_hotkey(hk_id, handler) {
+                                return Wait::Executed(ret);
+                            }
+                        }
+                        Err(reason) => {
+                            let outcome = TriggerOutcome::Rejected(reason);
+                            self.emit(hk_id, handler.combo(), msg.lParam, repeat, outcome)
+                        }
+                    }
+                }
+            } else if WM_TIMER == msg.message {
+                if let Some(ret) = self.handle_timer(msg.wParam) {
+                    return Wait::Executed(ret);
+                }
+            } else if WM_NULL == msg.message {
+                match msg.wParam {
+                    DRAIN_INTERRUPT => self.draining.set(true),
+                    INSTANCE_COMMAND => {
+                        let command = InstanceCommand::from_code(msg.lParam);
+                        if let (Some(handler), Some(command)) = (&self.instance_handler, command) {
+                            handler(command);
+                        }
+                    }
+                    #[cfg(feature = "test-support")]
+                    SIMULATE_TRIGGER | SIMULATE_REPEAT => {
+                        let id = HotkeyId(msg.lParam as i32);
+                        self.post_simulated_trigger(id, msg.wParam == SIMULATE_REPEAT);
+                    }
+                    #[cfg(feature = "test-support")]
+                    ADVANCE_TIMERS => self.elapse_timers(),
+                    _ => return self.stopped(ExitReason::Interrupted),
+                }
+            }
+        }
+    }
+
+    /// Poll for a message for the event loop for the duration of `spin_wait`, yielding the rest of
+    /// the time slice between the polls. Returns false if no message was received, so the event
+    /// loop has to block.
+    ///
+    /// ## Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-peekmessagew>
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-switchtothread>
+    ///
+    fn spin_message(&self, msg: *mut MSG) -> bool {
+        let Some(spin) = self.spin_wait else {
+            return false;
+        };
+
+        let deadline = Instant::now() + spin;
+        loop {
+            let found = unsafe { PeekMessageW(msg, self.hwnd.0, WM_NULL, WM_HOTKEY, PM_REMOVE) };
+            if found != 0 {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+            unsafe { SwitchToThread() };
+        }
+    }
+
+    /// Stop the event loop for the reason, or because the session is ending if windows is
+    /// shutting down at the moment
+    ///
+    /// ## Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getsystemmetrics>
+    ///
+    fn stopped(&self, reason: ExitReason) -> Wait<T> {
+        match unsafe { GetSystemMetrics(SM_SHUTTINGDOWN) } {
+            0 => Wait::Stopped(reason),
+            _ => Wait::Stopped(ExitReason::SessionEnding),
+        }
+    }
+
+    /// Wait until a message for the event loop is received or the deadline is reached. Returns
+    /// false if the deadline was reached without a message.
+    ///
+    /// ## Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-msgwaitformultipleobjectsex>
+    ///
+    fn wait_message(&self, msg: *mut MSG, deadline: Instant) -> bool {
+        loop {
+            let found = unsafe { PeekMessageW(msg, self.hwnd.0, WM_NULL, WM_HOTKEY, PM_REMOVE) };
+            if found != 0 {
+                return true;
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return false;
+            }
+
+            // Wake up on any new input, since the wait can't be filtered like `GetMessageW`. Only
+            // messages that arrived since the peek wake it up, otherwise a queued message for
+            // another window or outside of the range would make this loop spin until the deadline
+            unsafe {
+                MsgWaitForMultipleObjectsEx(
+                    0,
+                    std::ptr::null(),
+                    remaining.as_millis().min(u32::MAX as u128 - 1) as u32,
+                    QS_ALLINPUT,
+                    0,
+                )
+            };
+        }
+    }
+
+    /// Run the event loop like `event_loop`, but call `on_idle` whenever no hotkey callback was
+    /// executed within the timeout. This allows housekeeping tasks, like checking for
+    /// configuration changes, to run on the hotkey thread without an additional thread.
+    ///
+    /// ## Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-msgwaitformultipleobjectsex>
+    ///
+    pub fn event_loop_with_idle(&self, timeout: Duration, mut on_idle: impl FnMut()) -> ExitReason {
+        loop {
+            match self.wait_hotkey(Some(timeout)) {
+                Wait::Executed(_) => (),
+                Wait::Idle => on_idle(),
+                Wait::Stopped(reason) => return reason,
+            }
+        }
+    }
+
+    /// Start or restart the timer for a long-press hotkey, after which the callback is executed if
+    /// the hotkey is still held down
+    ///
+    fn start_long_press(&self, id: HotkeyId, handler: &HotkeyCallback<T>, msg: &MSG) {
+        let Some(hold) = handler.long_press else {
+            return;
+        };
+
+        let pending = self.timers.borrow().iter().find_map(|(&timer_id, action)| {
+            matches!(action, TimerAction::LongPress { id: press_id, .. } if *press_id == id)
+                .then_some(timer_id)
+        });
+        if let Some(timer_id) = pending {
+            self.stop_timer(timer_id);
+        }
+
+        let action = TimerAction::LongPress {
+            id,
+            lparam: msg.lParam,
+        };
+        self.start_timer(hold, action);
+    }
+
+    /// Handle a press of a hotkey with a double-tap callback. The first press starts the
+    /// double-tap window and the second press within the window executes the double-tap callback.
+    /// Returns the callback result if a callback was executed.
+    ///
+    fn handle_tap(&self, id: HotkeyId, handler: &HotkeyCallback<T>, msg: &MSG) -> Option<T> {
+        let double_tap = handler.double_tap.as_ref()?;
+
+        let pending = self.timers.borrow().iter().find_map(|(&timer_id, action)| {
+            matches!(action, TimerAction::SingleTap { id: tap_id, .. } if *tap_id == id)
+                .then_some(timer_id)
+        });
+
+        match pending {
+            Some(timer_id) => {
+                self.stop_timer(timer_id);
+                self.emit(id, handler.combo(), msg.lParam, 0, TriggerOutcome::Executed);
+                // The double-tap callback replaces the hotkey, including action and trigger
+                // callbacks
+                self.execute(id, &double_tap.callback)
+            }
+            None => {
+                let action = TimerAction::SingleTap {
+                    id,
+                    lparam: msg.lParam,
+                };
+
+                // Without a timer, the double-tap can't be detected
+                if self.start_timer(double_tap.window, action).is_some() {
+                    return None;
+                }
+                self.emit(id, handler.combo(), msg.lParam, 0, TriggerOutcome::Executed);
+                self.execute_hotkey(id, handler)
+            }
+        }
+    }
+
+    /// Start repeating the callback of the hotkey if it has a `RepeatPolicy` and isn't already
+    /// being repeated
+    ///
+    fn start_repeat(&self, id: HotkeyId, handler: &HotkeyCallback<T>) {
+        let Some(policy) = handler.repeat else {
+            return;
+        };
+
+        let repeating = self
+            .timers
+            .borrow()
+            .values()
+            .any(|action| matches!(action, TimerAction::Repeat { id: rid, .. } if *rid == id));
+
+        if !repeating {
+            let action = TimerAction::Repeat {
+                id,
+                interval: policy.interval,
+                count: 1,
+            };
+            self.start_timer(policy.initial_delay, action);
+        }
+    }
+
+    /// Count executed triggers and deliver an event to the event sink and the trigger feedback if
+    /// they are set
+    ///
+    fn emit(
+        &self,
+        id: HotkeyId,
+        combo: HotkeyCombo,
+        lparam: isize,
+        repeat_index: u32,
+        outcome: TriggerOutcome,
+    ) {
+        // Sequences don't have a handler, so they are not counted
+        let trigger_count = self.handlers.get(&id).map_or(0, |handler| match outcome {
+            TriggerOutcome::Executed => handler.triggers.fetch_add(1, Ordering::Relaxed) + 1,
+            TriggerOutcome::Rejected(_) => handler.triggers.load(Ordering::Relaxed),
+        });
+
+        if let (Some(feedback), TriggerOutcome::Executed) = (&self.trigger_feedback, &outcome) {
+            let label = self.handlers.get(&id).and_then(|h| h.label.as_deref());
+            feedback(&combo.to_string(), label);
+        }
+        if let (Some(feedback), TriggerOutcome::Executed) =
+            (self.keyboard_feedback.borrow_mut().as_mut(), &outcome)
+        {
+            let label = self.handlers.get(&id).and_then(|h| h.label.as_deref());
+            feedback.triggered(&combo, label);
+        }
+
+        // The action handler receives the event of executed action hotkeys
+        let for_action = self.action_handler.is_some()
+            && outcome == TriggerOutcome::Executed
+            && self.handlers.get(&id).is_some_and(|h| h.action().is_some());
+        if self.event_sink.is_none() && !for_action {
+            return;
+        }
+
+        // The mouse hook reports the wheel delta in the high word instead of the key
+        let wheel_delta = MouseWheel::from_vkey(combo.key).map(|_| (lparam >> 16) as i16);
+
+        let event = HotkeyEvent {
+            id: self.group_of(id),
+            combo,
+            modifiers: held_modifiers(lparam),
+            wheel_delta,
+            is_repeat: repeat_index > 0,
+            repeat_index,
+            label: self.handlers.get(&id).and_then(|h| h.label.clone()),
+            foreground: trigger_window(),
+            trigger_count,
+            merged: 0,
+            outcome,
+        };
+
+        if for_action {
+            *self.action_event.borrow_mut() = Some(event.clone());
+        }
+        if let Some(sink) = &self.event_sink {
+            self.send_event(sink, event);
+        }
+    }
+
+    /// Pass the event to the event sink. While the event sink is throttled, only the first trigger
+    /// of a hotkey per interval is passed on directly and the later ones are merged.
+    ///
+    fn send_event(&self, sink: &EventSink, event: HotkeyEvent) {
+        let Some(interval) = self.event_throttle else {
+            return sink(&event);
+        };
+
+        let mut throttled = self.throttled.borrow_mut();
+        if let Some(state) = throttled.get_mut(&event.id) {
+            if state.since.elapsed() < interval {
+                let merged = state.pending.as_ref().map_or(0, |pending| pending.merged);
+                state.pending = Some(HotkeyEvent {
+                    merged: merged + 1,
+                    ..event
+                });
+                return;
+            }
+
+            // The interval is over, but the timer didn't elapse yet or there is no window for it
+            if let Some(timer_id) = state.timer {
+                self.stop_timer(timer_id);
+            }
+            if let Some(pending) = state.pending.take() {
+                sink(&pending);
+            }
+        }
+
+        let state = ThrottledEvents {
+            since: Instant::now(),
+            pending: None,
+            timer: self.start_timer(interval, TimerAction::FlushEvents { id: event.id }),
+        };
+        throttled.insert(event.id, state);
+        drop(throttled);
+
+        sink(&event);
+    }
+
+    /// Count the repeats of a hotkey. Only the keyboard hook marks the hotkey messages that are
+    /// caused by the automatic key repeat, so hotkeys registered with `RegisterHotKey` always
+    /// report `0`.
+    ///
+    fn repeat_index(&self, id: HotkeyId, lparam: isize) -> u32 {
+        let mut repeats = self.repeats.borrow_mut();
+        let count = repeats.entry(id).or_insert(0);
+
+        match lparam as u32 & REPEAT_FLAG {
+            0 => *count = 0,
+            _ => *count += 1,
+        }
+        *count
+    }
+
+    /// Classify a received message for the message tap
+    ///
+    fn tapped_message(&self, msg: &MSG) -> TappedMessage {
+        match msg.message {
+            WM_HOTKEY => {
+                let id = HotkeyId(msg.wParam as i32);
+                TappedMessage::Hotkey {
+                    id,
+                    registered: self.handlers.contains_key(&id)
+                        || self.sequences.borrow().combo_of(id).is_some()
+                        || self.reserved.values().any(|&reserved| reserved == id),
+                    lparam: msg.lParam,
+                }
+            }
+            WM_NULL => TappedMessage::Interrupt,
+            message => TappedMessage::Other {
+                message,
+                wparam: msg.wParam,
+                lparam: msg.lParam,
+            },
+        }
+    }
+
+    /// Execute what a matched hotkey is registered with. Action hotkeys are executed by the action
+    /// handler and trigger hotkeys get the context of the current trigger.
+    ///
+    fn execute_hotkey(&self, id: HotkeyId, handler: &HotkeyCallback<T>) -> Option<T> {
+        match &handler.target {
+            HotkeyTarget::Callback(callback) => self.execute(id, callback),
+            HotkeyTarget::Trigger(with_trigger) => {
+                let callback = self.trigger_callback(id, handler, with_trigger);
+                self.execute(id, &callback)
+            }
+            HotkeyTarget::Action(action) => {
+                if self.action_handler.is_none() {
+                    self.report_error(id, &HkError::NoActionHandler(action.clone()));
+                    return None;
+                }
+                let callback = self.action_callback(action.clone())?;
+                self.execute(id, &callback)
+            }
+        }
+    }
+
+    /// Execute a callback of a matched hotkey, or hand it to the dispatcher if one is set. This
+    /// is the common path of all triggers, including the ones that are started by timers like
+    /// taps and long presses, so the trigger limit is applied here.
+    ///
+    fn execute(&self, id: HotkeyId, callback: &SharedCallback<T>) -> Option<T> {
+        // Discarded triggers don't count towards the trigger limit
+        if self.paused.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        if let Some(handler) = self.handlers.get(&id) {
+            self.apply_trigger_limit(id, handler);
+        }
+
+        let start = Instant::now();
+        let ret = self.run_callback(id, callback);
+        self.metrics.borrow_mut().dispatched(start.elapsed());
+        ret
+    }
+
+    /// Create a callback that executes the action handler with the action name and the event of
+    /// the current trigger. Returns `None` if no action handler is set.
+    ///
+    fn action_callback(&self, action: String) -> Option<SharedCallback<T>> {
+        let handler = self.action_handler.as_ref()?;
+        let event = self.action_event.borrow_mut().take()?;
+        Some(handler(action, event))
+    }
+
+    /// Create a callback that executes the callback of a hotkey registered with
+    /// `register_with_trigger` with the context of the current trigger
+    ///
+    fn trigger_callback(
+        &self,
+        id: HotkeyId,
+        handler: &HotkeyCallback<T>,
+        with_trigger: &TriggerCallback<T>,
+    ) -> SharedCallback<T> {
+        let (time, cursor) = self.trigger_message.get();
+        let context = TriggerContext {
+            id: self.group_of(id),
+            combo: handler.combo(),
+            time,
+            cursor,
+        };
+        with_trigger(context)
+    }
+
+    /// Unregister or disable the hotkey and its aliases if it reached its trigger limit
+    ///
+    fn apply_trigger_limit(&self, id: HotkeyId, handler: &HotkeyCallback<T>) {
+        let (max, unregister) = match handler.limit {
+            Some(TriggerLimit::Unregister(max)) => (max, true),
+            Some(TriggerLimit::Disable(max)) => (max, false),
+            None => return,
+        };
+        if handler.triggers.load(Ordering::Relaxed) < max {
+            return;
+        }
+
+        for member in self.members(self.group_of(id)) {
+            let Some(handler) = self.handlers.get(&member) else {
+                continue;
+            };
+            match unregister {
+                true => self.retire(member, handler),
+                false => handler.enabled.set(false),
+            }
+        }
+    }
+
+    /// Unregister a hotkey that reached its trigger limit. It stays parked, so it is not
+    /// registered again by mode switches or the game mode
+    ///
+    fn retire(&self, id: HotkeyId, handler: &HotkeyCallback<T>) {
+        if let Err(err) = self.deactivate(id, handler) {
+            self.report_error(id, &err);
+        }
+        handler.spent.set(true);
+    }
+
+    /// Inject the next probe of the hook diagnostics. Probes are skipped while modifiers are held,
+    /// since the probe would arrive as a different key combination
+    ///
+    fn send_hook_probe(&self) {
+        let Some(id) = self.hook_probe.borrow().as_ref().map(|probe| probe.id) else {
+            return;
+        };
+        if current_modifiers() != 0 {
+            return;
+        }
+
+        if let Err(err) = tap_key(PROBE_KEY) {
+            self.report_hook_finding(id, &err);
+            return;
+        }
+
+        let sent = self.hook_probe.borrow_mut().as_mut().map(HookProbe::sent);
+        if let Some(Err(err)) = sent {
+            self.report_hook_finding(id, &err);
+        }
+    }
+
+    /// Measure a probe of the hook diagnostics. Returns false if the hotkey is not the probe
+    ///
+    fn receive_hook_probe(&self, id: HotkeyId) -> bool {
+        let received = match self.hook_probe.borrow_mut().as_mut() {
+            Some(probe) if probe.id == id => probe.received(),
+            _ => return false,
+        };
+
+        if let Err(err) = received {
+            self.report_hook_finding(id, &err);
+        }
+        true
+    }
+
+    /// Report a finding of the hook diagnostics in the metrics and to the error sink
+    ///
+    fn report_hook_finding(&self, id: HotkeyId, err: &HkError) {
+        self.metrics.borrow_mut().error(err);
+        self.report_error(id, err);
+    }
+
+    /// Pass an error of the hotkey to its error handler, or to the error sink if the hotkey has no
+    /// error handler
+    ///
+    fn report_error(&self, id: HotkeyId, err: &HkError) {
+        if let Some(on_error) = self.handlers.get(&id).and_then(|h| h.on_error.as_ref()) {
+            on_error(err);
+            return;
+        }
+
+        let sink = self
+            .error_sink
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        if let Some(sink) = sink {
+            sink(id, err);
+        }
+    }
+
+    /// Run the callback, applying the panic policy
+    ///
+    fn run_callback(&self, id: HotkeyId, callback: &SharedCallback<T>) -> Option<T> {
+        if let Some(dispatcher) = &self.dispatcher {
+            return Some(dispatcher(callback.clone()));
+        }
+
+        let callback = callback.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(policy) = self.panic_policy else {
+            return Some(callback());
+        };
+
+        if let Ok(ret) = catch_unwind(AssertUnwindSafe(&**callback)) {
+            return Some(ret);
+        }
+
+        self.metrics.borrow_mut().panicked(id);
+        self.report_error(id, &HkError::CallbackPanicked(self.group_of(id)));
+        match policy {
+            PanicPolicy::Abort => std::process::abort(),
+            PanicPolicy::Ignore => (),
+            PanicPolicy::DisableHotkey => {
+                for member in self.members(self.group_of(id)) {
+                    if let Some(handler) = self.handlers.get(&member) {
+                        handler.enabled.set(false);
+                    }
+                }
+            }
+            PanicPolicy::StopLoop => self.panic_stop.set(true),
+        }
+
+        None
+    }
+}
+
+impl HotkeyManager<()> {
+    /// Set a dispatcher that receives the callbacks of matched hotkeys instead of executing them
+    /// directly on the hotkey thread. The dispatcher is called with a boxed closure that executes
+    /// the callback and can for example be posted to the main thread of a GUI framework.
     ///
-    /// When this option is disabled, the `ModKey::NoRepeat` can still be manually added while
-    /// registering hotkeys.
+    /// While a dispatcher is set, `handle_hotkey` returns `Some(())` as soon as the callback was
+    /// handed to the dispatcher.
     ///
-    /// Note: Setting this flag doesn't change previously registered hotkeys. It only applies to
-    /// registrations performed after calling this function.
-    pub fn set_no_repeat(&mut self, no_repeat: bool) {
-        self.no_repeat = no_repeat;
+    pub fn set_dispatcher(
+        &mut self,
+        dispatcher: impl Fn(Box<dyn FnOnce() + Send>) + Send + 'static,
+    ) {
+        self.set_dispatch_fn(Some(wrap_dispatcher(dispatcher)));
+    }
+
+    /// Remove a previously set dispatcher. Callbacks will be executed directly on the hotkey thread
+    /// again.
+    ///
+    pub fn remove_dispatcher(&mut self) {
+        self.set_dispatch_fn(None);
+    }
+}
+
+/// A handle to a hotkey of a `HotkeyManager`, returned by `register_handle` and `handle`. All
+/// methods act on the manager directly, so the handle always sees the current state of the
+/// hotkey, including changes made through the manager while the handle didn't exist.
+///
+pub struct HotkeyHandle<'a, T: 'static> {
+    id: HotkeyId,
+    hkm: &'a mut HotkeyManager<T>,
+}
+
+impl<T: 'static> HotkeyHandle<'_, T> {
+    /// The id of the hotkey
+    ///
+    pub fn id(&self) -> HotkeyId {
+        self.id
+    }
+
+    /// The key combination the hotkey is currently bound to. See `HotkeyManager::combo`.
+    ///
+    pub fn combo(&self) -> Result<HotkeyCombo, HkError> {
+        self.hkm.combo(self.id)
+    }
+
+    /// Unregister the hotkey. See `HotkeyManagerImpl::unregister`.
+    ///
+    pub fn unregister(self) -> Result<(), HkError> {
+        self.hkm.unregister(self.id)
+    }
+
+    /// Stop executing the callback of the hotkey, while keeping it registered. See
+    /// `HotkeyManager::set_enabled`.
+    ///
+    pub fn disable(&mut self) -> Result<(), HkError> {
+        self.hkm.set_enabled(self.id, false)
+    }
+
+    /// Execute the callback of the hotkey again after it was disabled.
+    ///
+    pub fn enable(&mut self) -> Result<(), HkError> {
+        self.hkm.set_enabled(self.id, true)
+    }
+
+    /// Replace the callback of the hotkey. See `HotkeyManager::set_callback`.
+    ///
+    pub fn set_callback(
+        &mut self,
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<(), HkError> {
+        self.hkm.set_callback(self.id, callback)
+    }
+
+    /// Change the key combination of the hotkey. See `HotkeyManager::rebind`.
+    ///
+    pub fn rebind(&mut self, key: VKey, key_modifiers: &[ModKey]) -> Result<(), HkError> {
+        self.hkm.rebind(self.id, key, key_modifiers)
     }
 }
 
@@ -73,6 +3686,44 @@ impl<T> HotkeyManagerImpl<T> for HotkeyManager<T> {
             id_offset: 0,
             handlers: HashMap::new(),
             no_repeat: true,
+            dispatcher: None,
+            registered_observers: Vec::new(),
+            unregistered_observers: Vec::new(),
+            message_tap: None,
+            event_sink: None,
+            event_throttle: None,
+            throttled: RefCell::new(HashMap::new()),
+            trigger_feedback: None,
+            keyboard_feedback: RefCell::new(None),
+            hook: None,
+            mouse_hook: None,
+            timers: RefCell::new(HashMap::new()),
+            next_timer_id: Cell::new(1),
+            repeats: RefCell::new(HashMap::new()),
+            draining: Cell::new(false),
+            spin_wait: None,
+            panic_policy: None,
+            error_sink: Arc::new(Mutex::new(None)),
+            panic_stop: Cell::new(false),
+            sequences: RefCell::new(Sequences::default()),
+            reserved: HashMap::new(),
+            alias_groups: HashMap::new(),
+            toggles: HashMap::new(),
+            disabled_groups: HashSet::new(),
+            context: None,
+            suspended: false,
+            instance_handler: None,
+            modes: RefCell::new(Modes::default()),
+            metrics: RefCell::new(MetricsRecorder::default()),
+            action_handler: None,
+            action_event: RefCell::new(None),
+            game_mode_timer: None,
+            hook_probe: RefCell::new(None),
+            fullscreen: Cell::new(false),
+            paused: Arc::default(),
+            trigger_message: Cell::new((0, (0, 0))),
+            #[cfg(feature = "test-support")]
+            manual_timers: false,
             _unimpl_send_sync: PhantomData,
         }
     }
@@ -84,59 +3735,45 @@ impl<T> HotkeyManagerImpl<T> for HotkeyManager<T> {
         extra_keys: &[VKey],
         callback: impl Fn() -> T + Send + 'static,
     ) -> Result<HotkeyId, HkError> {
-        let register_id = HotkeyId(self.id_offset);
-        self.id_offset += 1;
-
-        let mut modifiers = ModKey::combine(key_modifiers);
-        if self.no_repeat {
-            modifiers |= ModKey::NoRepeat.to_mod_code();
-        }
-
-        // Try to register the hotkey combination with windows
-        let reg_ok = unsafe {
-            RegisterHotKey(
-                self.hwnd.0,
-                register_id.0,
-                modifiers,
-                key.to_vk_code() as u32,
-            )
+        let options = RegisterOptions {
+            extra_keys: extra_keys.to_vec(),
+            ..Default::default()
         };
-
-        if reg_ok == 0 {
-            Err(HkError::RegistrationFailed)
-        } else {
-            // Add the HotkeyCallback to the handlers when the hotkey was registered
-            self.handlers.insert(
-                register_id,
-                HotkeyCallback {
-                    callback: Box::new(callback),
-                    extra_keys: extra_keys.to_owned(),
-                },
-            );
-
-            Ok(register_id)
-        }
-    }
-
-    fn register(
-        &mut self,
-        key: VKey,
-        key_modifiers: &[ModKey],
-        callback: impl Fn() -> T + Send + 'static,
-    ) -> Result<HotkeyId, HkError> {
-        self.register_extrakeys(key, key_modifiers, &[], callback)
+        self.register_with_options(key, key_modifiers, options, callback)
     }
 
     fn unregister(&mut self, id: HotkeyId) -> Result<(), HkError> {
-        let ok = unsafe { UnregisterHotKey(self.hwnd.0, id.0) };
+        if self.unregister_sequence(id) {
+            return Ok(());
+        }
 
-        match ok {
-            0 => Err(HkError::UnregistrationFailed),
-            _ => {
-                self.handlers.remove(&id);
-                Ok(())
+        if let Some(switch) = self.modes.get_mut().switches.remove(&id) {
+            if !self.suspended && self.modes.get_mut().is_active(switch.from.as_deref()) {
+                cleanup::unregister_hotkey(self.hwnd.0, id.0);
+            }
+            return Ok(());
+        }
+
+        // The group entry is removed last, so the members are still resolved as aliases while
+        // they are unregistered. Members that fail stay registered under their own ids
+        if let Some(members) = self.alias_groups.get(&id).cloned() {
+            let mut result = Ok(());
+            for member in members {
+                if let Err(err) = self.unregister(member) {
+                    result = result.and(Err(err));
+                }
             }
+            self.alias_groups.remove(&id);
+            return result;
         }
+
+        let handler = self
+            .handlers
+            .get(&id)
+            .ok_or(HkError::UnregistrationFailed)?;
+        self.deactivate(id, handler)?;
+        self.remove_handler(id);
+        Ok(())
     }
 
     fn unregister_all(&mut self) -> Result<(), HkError> {
@@ -145,47 +3782,111 @@ impl<T> HotkeyManagerImpl<T> for HotkeyManager<T> {
             self.unregister(id)?;
         }
 
+        let sequence_ids: Vec<_> = self.sequences.get_mut().sequences.keys().copied().collect();
+        for id in sequence_ids {
+            self.unregister_sequence(id);
+        }
+
+        let switch_ids: Vec<_> = self.modes.get_mut().switches.keys().copied().collect();
+        for id in switch_ids {
+            self.unregister(id)?;
+        }
+
+        for (_, id) in self.reserved.drain() {
+            cleanup::unregister_hotkey(self.hwnd.0, id.0);
+        }
+
         Ok(())
     }
 
     fn handle_hotkey(&self) -> Option<T> {
-        loop {
-            let mut msg = std::mem::MaybeUninit::<MSG>::uninit();
+        match self.wait_hotkey(None) {
+            Wait::Executed(ret) => Some(ret),
+            Wait::Stopped(_) | Wait::Idle => None,
+        }
+    }
 
-            // Block and read a message from the message queue. Filtered to receive messages from
-            // WM_NULL to WM_HOTKEY
-            let ok = unsafe { GetMessageW(msg.as_mut_ptr(), self.hwnd.0, WM_NULL, WM_HOTKEY) };
-
-            if ok != 0 {
-                let msg = unsafe { msg.assume_init() };
-
-                if WM_HOTKEY == msg.message {
-                    let hk_id = HotkeyId(msg.wParam as i32);
-
-                    // Get the callback for the received ID
-                    if let Some(handler) = self.handlers.get(&hk_id) {
-                        // Check if all extra keys are pressed
-                        if !handler
-                            .extra_keys
-                            .iter()
-                            .any(|vk| !get_global_keystate(*vk))
-                        {
-                            return Some((handler.callback)());
-                        }
-                    }
-                } else if WM_NULL == msg.message {
-                    return None;
-                }
+    fn event_loop(&self) -> ExitReason {
+        loop {
+            if let Wait::Stopped(reason) = self.wait_hotkey(None) {
+                return reason;
             }
         }
     }
 
-    fn event_loop(&self) {
-        while self.handle_hotkey().is_some() {}
+    fn interrupt_handle(&self) -> InterruptHandle {
+        InterruptHandle(vec![self.hwnd.0])
     }
+}
 
-    fn interrupt_handle(&self) -> InterruptHandle {
-        InterruptHandle(self.hwnd.0)
+#[cfg(feature = "test-support")]
+impl<T> HotkeyManager<T> {
+    /// Get a `TestHandle` that triggers hotkeys and advances the timers of this `HotkeyManager`
+    /// without real key presses, for integration tests
+    ///
+    pub fn test_handle(&self) -> TestHandle {
+        TestHandle::new(self.hwnd.0)
+    }
+
+    /// Only let the timers of the event loop elapse when `TestHandle::advance_timers` is called,
+    /// instead of after their real delay. This makes sequence timeouts, double-taps, long-presses
+    /// and mode timeouts deterministic in tests. Timers that are already running are not affected.
+    ///
+    pub fn set_manual_timers(&mut self, manual: bool) {
+        self.manual_timers = manual;
+    }
+
+    /// Process all messages that are currently queued for the event loop, including the ones
+    /// posted by a `TestHandle`, without blocking. Returns the results of the callbacks that were
+    /// executed, in order.
+    ///
+    pub fn run_until_idle(&self) -> Vec<T> {
+        let mut results = Vec::new();
+        while let Wait::Executed(ret) = self.wait_hotkey(Some(Duration::ZERO)) {
+            results.push(ret);
+        }
+        results
+    }
+
+    /// Post a hotkey message for the hotkey, with the same `lParam` that windows or the keyboard
+    /// hook would use for a real trigger
+    ///
+    /// ## Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-postmessagew>
+    ///
+    fn post_simulated_trigger(&self, id: HotkeyId, repeat: bool) {
+        let mut lparam = match self.handlers.get(&id) {
+            Some(handler) => {
+                let modifiers =
+                    ModKey::combine(&handler.modifiers) & !ModKey::NoRepeat.to_mod_code();
+                modifiers | (handler.key.to_vk_code() as u32) << 16
+            }
+            None => 0,
+        };
+        if repeat {
+            lparam |= REPEAT_FLAG;
+        }
+
+        unsafe { PostMessageW(self.hwnd.0, WM_HOTKEY, id.0 as WPARAM, lparam as LPARAM) };
+    }
+
+    /// Let all running timers elapse by posting their timer messages. The real timers are stopped,
+    /// so they don't elapse a second time.
+    ///
+    /// ## Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-killtimer>
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-postmessagew>
+    ///
+    fn elapse_timers(&self) {
+        let mut timer_ids: Vec<_> = self.timers.borrow().keys().copied().collect();
+        timer_ids.sort_unstable();
+
+        for timer_id in timer_ids {
+            unsafe {
+                KillTimer(self.hwnd.0, timer_id);
+                PostMessageW(self.hwnd.0, WM_TIMER, timer_id, 0);
+            }
+        }
     }
 }
 
@@ -195,6 +3896,44 @@ impl<T> Drop for HotkeyManager<T> {
     }
 }
 
+/// A `HotkeyManager` that implements `Send`. This is created with
+/// `HotkeyManager::assume_same_thread` and dereferences to the underlying `HotkeyManager`.
+///
+pub struct SendHotkeyManager<T>(HotkeyManager<T>);
+
+// Safety: The caller of `assume_same_thread` guarantees that the manager is only used on the
+// thread that created it
+unsafe impl<T> Send for SendHotkeyManager<T> {}
+
+impl<T> SendHotkeyManager<T> {
+    /// Get the underlying `HotkeyManager` back.
+    ///
+    pub fn into_inner(self) -> HotkeyManager<T> {
+        self.0
+    }
+}
+
+impl<T> Deref for SendHotkeyManager<T> {
+    type Target = HotkeyManager<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for SendHotkeyManager<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Decode the modifier keys that were held when the hotkey was triggered from the low word of the
+/// `lParam` of a `WM_HOTKEY` message
+///
+fn held_modifiers(lparam: isize) -> Vec<ModKey> {
+    ModKey::from_mod_code((lparam & 0xFFFF) as u32)
+}
+
 /// Wrapper around a HWND windows pointer that destroys the window on drop
 ///
 struct HwndDropper(HWND);
@@ -217,8 +3956,8 @@ fn create_hidden_window() -> Result<HwndDropper, ()> {
             WS_EX_NOACTIVATE,
             // The "Static" class is not intended for windows, but this shouldn't matter since the
             // window is hidden anyways
-            b"Static\0".as_ptr() as *const i8,
-            b"\0".as_ptr() as *const i8,
+            c"Static".as_ptr(),
+            c"".as_ptr(),
             WS_DISABLED,
             0,
             0,
@@ -0,0 +1,82 @@
+use winapi::shared::minwindef::{LPARAM, WPARAM};
+use winapi::shared::windef::HWND;
+use winapi::um::winuser::{PostMessageW, WM_NULL};
+
+use crate::HotkeyId;
+
+/// The `wParam` of the `WM_NULL` message that is posted by `TestHandle::trigger`
+///
+pub(crate) const SIMULATE_TRIGGER: usize = 3;
+
+/// The `wParam` of the `WM_NULL` message that is posted by `TestHandle::trigger_repeat`
+///
+pub(crate) const SIMULATE_REPEAT: usize = 4;
+
+/// The `wParam` of the `WM_NULL` message that is posted by `TestHandle::advance_timers`
+///
+pub(crate) const ADVANCE_TIMERS: usize = 5;
+
+/// A handle to drive the event loop of a `HotkeyManager` in integration tests, without real key
+/// presses. This is created with `test_handle` and can be used from any thread, like the
+/// `InterruptHandle`.
+///
+/// Synthetic triggers take the same path through the event loop as real ones, so conditions,
+/// modes, sequences, double-taps and the event sink all behave like in production. Only the
+/// extra keys and the held state of long-press and repeat hotkeys still depend on the real
+/// keyboard state.
+///
+/// Everything that happens after a delay, like sequence timeouts, double-tap windows, long-presses
+/// and mode timeouts, is driven by the timers of the hidden window. With
+/// `HotkeyManager::set_manual_timers` these only elapse when `advance_timers` is called, which
+/// makes the timing of a test independent of the load on the test machine.
+///
+/// # Note
+/// The handle only posts messages, so the effects are visible once the event loop processed
+/// them. With a `singlethreaded::HotkeyManager`, `run_until_idle` processes all of them.
+///
+pub struct TestHandle(HWND);
+
+unsafe impl Sync for TestHandle {}
+
+unsafe impl Send for TestHandle {}
+
+impl TestHandle {
+    pub(crate) fn new(hwnd: HWND) -> Self {
+        Self(hwnd)
+    }
+
+    /// Trigger the hotkey as if its key combination was pressed
+    ///
+    /// # Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-postmessagew>
+    ///
+    pub fn trigger(&self, id: HotkeyId) {
+        self.post(SIMULATE_TRIGGER, id.0 as LPARAM);
+    }
+
+    /// Trigger the hotkey as if its key combination was held down and repeated by the keyboard.
+    /// Only hotkeys that are registered without `ModKey::NoRepeat` or through the keyboard hook
+    /// receive repeats in production.
+    ///
+    pub fn trigger_repeat(&self, id: HotkeyId) {
+        self.post(SIMULATE_REPEAT, id.0 as LPARAM);
+    }
+
+    /// Let all currently running timers of the event loop elapse right away. Timers that are
+    /// started by the elapsed timers, like the next step of an auto-repeat, only elapse on the
+    /// next call.
+    ///
+    pub fn advance_timers(&self) {
+        self.post(ADVANCE_TIMERS, 0);
+    }
+
+    /// The hidden message-only window that receives the messages of the event loop
+    ///
+    pub fn hidden_window(&self) -> HWND {
+        self.0
+    }
+
+    fn post(&self, code: usize, lparam: LPARAM) {
+        unsafe { PostMessageW(self.0, WM_NULL, code as WPARAM, lparam) };
+    }
+}
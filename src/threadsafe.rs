@@ -1,37 +1,304 @@
 use std::{
+    any::Any,
+    collections::HashMap,
     marker::PhantomData,
-    sync::mpsc::{channel, Receiver, Sender},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{channel, Receiver, Sender},
+        Arc,
+    },
     thread::{spawn, JoinHandle},
+    time::Duration,
 };
 
+#[cfg(feature = "test-support")]
+use crate::test_support::TestHandle;
 use crate::{
+    combo::HotkeyCombo,
+    condition::{AccessibilityCondition, CursorRegion, SessionKind, WindowCondition},
+    cycle_callback, downcast_context,
     error::HkError,
-    keys::{ModKey, VKey},
-    singlethreaded, HotkeyId, HotkeyManagerImpl, InterruptHandle,
+    feedback::KeyboardFeedback,
+    gesture::Stroke,
+    instance::{InstanceCommand, SingleInstance},
+    keys::{IntoModifiers, ModKey, VKey},
+    metrics::Metrics,
+    mode::ModeObserver,
+    mouse::{MouseButton, MouseWheel},
+    options::{self, RegisterOptions, TriggerLimit},
+    repeat::RepeatPolicy,
+    sequence::SequenceStep,
+    singlethreaded, wrap_action_handler, wrap_dispatcher, ActionHandler, CallbackError, DispatchFn,
+    ErrorHandler, ErrorSink, EventSink, ExitReason, HotkeyCallback, HotkeyEvent, HotkeyId,
+    HotkeyInfo, HotkeyManagerImpl, InstanceHandler, InterruptHandle, MessageTap, PanicPolicy,
+    Predicate, RegistrationObserver, SharedContext, TappedMessage, TriggerContext, TriggerFeedback,
 };
 
 struct Hotkey<T: 'static> {
     key: VKey,
     key_modifiers: Vec<ModKey>,
-    extra_keys: Vec<VKey>,
     callback: Box<dyn Fn() -> T + Send + 'static>,
 }
 
 enum HkMsg<T: 'static> {
-    Register(Sender<Result<HotkeyId, HkError>>, Hotkey<T>),
+    Register(
+        Sender<Result<HotkeyId, HkError>>,
+        VKey,
+        Vec<ModKey>,
+        Box<RegisterOptions>,
+        Box<dyn Fn() -> T + Send + 'static>,
+    ),
+    RegisterHooked(Sender<Result<HotkeyId, HkError>>, Hotkey<T>, bool),
+    RegisterWheel(
+        Sender<Result<HotkeyId, HkError>>,
+        MouseWheel,
+        Vec<ModKey>,
+        bool,
+        Box<dyn Fn() -> T + Send + 'static>,
+    ),
+    RegisterMouse(
+        Sender<Result<HotkeyId, HkError>>,
+        MouseButton,
+        Vec<ModKey>,
+        bool,
+        Box<dyn Fn() -> T + Send + 'static>,
+    ),
+    RegisterGesture(
+        Sender<Result<HotkeyId, HkError>>,
+        MouseButton,
+        Vec<ModKey>,
+        Vec<Stroke>,
+        Box<dyn Fn() -> T + Send + 'static>,
+    ),
+    RegisterLongPress(Sender<Result<HotkeyId, HkError>>, Hotkey<T>, Duration),
+    RegisterModifierTap(
+        Sender<Result<HotkeyId, HkError>>,
+        ModKey,
+        Box<dyn Fn() -> T + Send + 'static>,
+    ),
+    RegisterSequence(
+        Sender<Result<HotkeyId, HkError>>,
+        Vec<SequenceStep>,
+        Box<dyn Fn() -> T + Send + 'static>,
+    ),
+    SetSequenceTimeout(Sender<()>, Duration),
+    SetLeader(Sender<Result<(), HkError>>, HotkeyCombo, Duration),
+    RegisterLeaderAction(Sender<Result<HotkeyId, HkError>>, Hotkey<T>),
+    RegisterAliases(
+        Sender<Result<HotkeyId, HkError>>,
+        Vec<HotkeyCombo>,
+        Box<dyn Fn() -> T + Send + 'static>,
+    ),
+    Reserve(Sender<Result<(), HkError>>, VKey, Vec<ModKey>),
+    Release(Sender<Result<(), HkError>>, VKey, Vec<ModKey>),
     HandleHotkey(Sender<Option<T>>),
     Unregister(Sender<Result<(), HkError>>, HotkeyId),
     UnregisterAll(Sender<Result<(), HkError>>),
-    EventLoop(Sender<()>),
+    EventLoop(Sender<ExitReason>),
+    EventLoopWithIdle(
+        Sender<ExitReason>,
+        Duration,
+        Box<dyn FnMut() + Send + 'static>,
+    ),
     InterruptHandle(Sender<InterruptHandle>),
+    SetDispatcher(Sender<()>, Option<DispatchFn<T>>),
+    SetCursorRegion(Sender<Result<(), HkError>>, HotkeyId, Option<CursorRegion>),
+    SetAllowedSession(Sender<Result<(), HkError>>, HotkeyId, Option<SessionKind>),
+    SetAccessibilityCondition(
+        Sender<Result<(), HkError>>,
+        HotkeyId,
+        Option<AccessibilityCondition>,
+    ),
+    SetWindowCondition(
+        Sender<Result<(), HkError>>,
+        HotkeyId,
+        Option<WindowCondition>,
+    ),
+    SetCondition(Sender<Result<(), HkError>>, HotkeyId, Option<Predicate>),
+    SetErrorHandler(Sender<Result<(), HkError>>, HotkeyId, Option<ErrorHandler>),
+    SetTriggerLimit(Sender<Result<(), HkError>>, HotkeyId, Option<TriggerLimit>),
+    SetEnabled(Sender<Result<(), HkError>>, HotkeyId, bool),
+    SetGroup(Sender<Result<(), HkError>>, HotkeyId, Option<String>),
+    SetGroupEnabled(Sender<()>, String, bool),
+    RegisterModeSwitch(
+        Sender<Result<HotkeyId, HkError>>,
+        VKey,
+        Vec<ModKey>,
+        Option<String>,
+        Option<String>,
+    ),
+    SetMode(Sender<Vec<(HotkeyId, HkError)>>, Option<String>),
+    Mode(Sender<Option<String>>),
+    SetModeTimeout(Sender<()>, String, Option<Duration>),
+    SetModeObserver(Sender<()>, Option<ModeObserver>),
+    SetInstanceHandler(Sender<()>, String, InstanceHandler),
+    Suspend(Sender<()>),
+    Resume(Sender<Vec<(HotkeyId, HkError)>>),
+    IsSuspended(Sender<bool>),
+    SetGameMode(Sender<()>, Option<Duration>),
+    SetHookDiagnostics(Sender<Result<(), HkError>>, Option<(Duration, Duration)>),
+    IsGameModeActive(Sender<bool>),
+    SetSuppressInFullscreen(Sender<Result<(), HkError>>, HotkeyId, bool),
+    IsGroupEnabled(Sender<bool>, String),
+    SetCallback(
+        Sender<Result<(), HkError>>,
+        HotkeyId,
+        Box<dyn Fn() -> T + Send + 'static>,
+    ),
+    TriggerCount(Sender<Result<u64, HkError>>, HotkeyId),
+    Metrics(Sender<Metrics>),
+    #[cfg(feature = "test-support")]
+    TestHandle(Sender<TestHandle>),
+    #[cfg(feature = "test-support")]
+    SetManualTimers(Sender<()>, bool),
+    RegisterAction(Sender<Result<HotkeyId, HkError>>, HotkeyCombo, String),
+    SetActionHandler(Sender<()>, Option<ActionHandler<T>>),
+    Hotkeys(Sender<Vec<HotkeyInfo>>),
+    RegisterCounted(
+        Sender<Result<HotkeyId, HkError>>,
+        VKey,
+        Vec<ModKey>,
+        Box<dyn Fn(u64) -> T + Send + 'static>,
+    ),
+    RegisterOnce(
+        Sender<Result<HotkeyId, HkError>>,
+        VKey,
+        Vec<ModKey>,
+        Box<dyn FnOnce() -> T + Send + 'static>,
+    ),
+    RegisterWithTrigger(
+        Sender<Result<HotkeyId, HkError>>,
+        VKey,
+        Vec<ModKey>,
+        Box<dyn Fn(&TriggerContext) -> T + Send + 'static>,
+    ),
+    RegisterFallible(
+        Sender<Result<HotkeyId, HkError>>,
+        VKey,
+        Vec<ModKey>,
+        Box<dyn Fn() -> Result<T, CallbackError> + Send + 'static>,
+        fn() -> T,
+    ),
+    RegisterToggle(
+        Sender<Result<HotkeyId, HkError>>,
+        VKey,
+        Vec<ModKey>,
+        Box<dyn Fn() -> T + Send + 'static>,
+        Box<dyn Fn() -> T + Send + 'static>,
+    ),
+    IsOn(Sender<Result<bool, HkError>>, HotkeyId),
+    SetPanicPolicy(Sender<()>, Option<PanicPolicy>),
+    Rebind(Sender<Result<(), HkError>>, HotkeyId, VKey, Vec<ModKey>),
+    Combo(Sender<Result<HotkeyCombo, HkError>>, HotkeyId),
+    SetRepeatPolicy(Sender<Result<(), HkError>>, HotkeyId, Option<RepeatPolicy>),
+    SetDoubleTap(
+        Sender<Result<(), HkError>>,
+        HotkeyId,
+        Option<(Duration, Box<dyn Fn() -> T + Send + 'static>)>,
+    ),
+    OnRegistered(Sender<()>, RegistrationObserver),
+    SetMessageTap(Sender<()>, Option<MessageTap>),
+    SetEventSink(Sender<()>, Option<EventSink>),
+    SetEventThrottle(Sender<()>, Option<Duration>),
+    SetSpinWait(Sender<()>, Option<Duration>),
+    SetTriggerFeedback(Sender<()>, Option<TriggerFeedback>),
+    SetKeyboardFeedback(Sender<()>, Option<Box<dyn KeyboardFeedback>>),
+    SetErrorSink(Sender<()>, Option<ErrorSink>),
+    TakeHandlers(Sender<Vec<(HotkeyId, HotkeyCallback<T>)>>),
+    AbsorbHandlers(
+        Sender<HashMap<HotkeyId, Result<HotkeyId, HkError>>>,
+        Vec<(HotkeyId, HotkeyCallback<T>)>,
+    ),
+    OnUnregistered(Sender<()>, RegistrationObserver),
     Exit(Sender<()>),
 }
 
 pub struct HotkeyManager<T: 'static> {
     no_repeat: bool,
+    /// The context for `register_with_context`. It is kept on the calling side, since the
+    /// callbacks capture it before they are sent to the backend thread
+    context: Option<SharedContext>,
     _phantom: PhantomData<T>,
     snd: Sender<HkMsg<T>>,
     backend_handle: Option<JoinHandle<()>>,
+    /// Shared with the backend, see `pause_backend`
+    paused: Arc<AtomicBool>,
+}
+
+/// A handle to a hotkey registered with `register_handle`. The handle keeps a connection to the
+/// `HotkeyManager` it was registered with, so it can't be used with a different manager by
+/// accident. It can be moved to other threads independently of the manager.
+///
+/// The handle doesn't cache any state of the hotkey, so changes made through the manager, like
+/// `HotkeyManager::rebind`, are visible through the handle as well.
+///
+/// Dropping the handle doesn't unregister the hotkey. Once the manager was dropped, all methods
+/// return `HkError::InvalidHotkeyId`.
+///
+pub struct HotkeyHandle<T: 'static> {
+    id: HotkeyId,
+    snd: Sender<HkMsg<T>>,
+}
+
+impl<T: 'static> HotkeyHandle<T> {
+    /// The id of the hotkey
+    ///
+    pub fn id(&self) -> HotkeyId {
+        self.id
+    }
+
+    /// The key combination the hotkey is currently bound to. See `HotkeyManager::combo`.
+    ///
+    pub fn combo(&self) -> Result<HotkeyCombo, HkError> {
+        self.request(|ret| HkMsg::Combo(ret, self.id))
+    }
+
+    /// Unregister the hotkey. See `HotkeyManagerImpl::unregister`.
+    ///
+    pub fn unregister(self) -> Result<(), HkError> {
+        self.request(|ret| HkMsg::Unregister(ret, self.id))
+    }
+
+    /// Stop executing the callback of the hotkey, while keeping it registered. See
+    /// `HotkeyManager::set_enabled`.
+    ///
+    pub fn disable(&self) -> Result<(), HkError> {
+        self.request(|ret| HkMsg::SetEnabled(ret, self.id, false))
+    }
+
+    /// Execute the callback of the hotkey again after it was disabled.
+    ///
+    pub fn enable(&self) -> Result<(), HkError> {
+        self.request(|ret| HkMsg::SetEnabled(ret, self.id, true))
+    }
+
+    /// Replace the callback of the hotkey. See `HotkeyManager::set_callback`.
+    ///
+    pub fn set_callback(&self, callback: impl Fn() -> T + Send + 'static) -> Result<(), HkError> {
+        self.request(|ret| HkMsg::SetCallback(ret, self.id, Box::new(callback)))
+    }
+
+    /// Change the key combination of the hotkey. See `HotkeyManager::rebind`.
+    ///
+    pub fn rebind(&self, key: VKey, key_modifiers: &[ModKey]) -> Result<(), HkError> {
+        self.request(|ret| HkMsg::Rebind(ret, self.id, key, key_modifiers.to_vec()))
+    }
+
+    /// Send a message to the backend of the manager and wait for the result
+    ///
+    fn request<R>(
+        &self,
+        msg: impl FnOnce(Sender<Result<R, HkError>>) -> HkMsg<T>,
+    ) -> Result<R, HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(msg(ret_ch.0))
+            .map_err(|_| HkError::InvalidHotkeyId(self.id))?;
+        ret_ch
+            .1
+            .recv()
+            .map_err(|_| HkError::InvalidHotkeyId(self.id))?
+    }
 }
 
 struct TSHotkeyManagerBackend<T: 'static> {
@@ -39,18 +306,1539 @@ struct TSHotkeyManagerBackend<T: 'static> {
     rec: Receiver<HkMsg<T>>,
 }
 
-impl<T: 'static> HotkeyManager<T> {
-    /// Enable or disable the automatically applied `ModKey::NoRepeat` modifier. By default, this
-    /// option is set to `true` which causes all hotkey registration calls to add the `NoRepeat`
-    /// modifier, thereby disabling automatic retriggers of hotkeys when holding down the keys.
+impl<T: 'static> HotkeyManager<T> {
+    /// Enable or disable the automatically applied `ModKey::NoRepeat` modifier. By default, this
+    /// option is set to `true` which causes all hotkey registration calls to add the `NoRepeat`
+    /// modifier, thereby disabling automatic retriggers of hotkeys when holding down the keys.
+    ///
+    /// When this option is disabled, the `ModKey::NoRepeat` can still be manually added while
+    /// registering hotkeys.
+    ///
+    /// Note: Setting this flag doesn't change previously registered hotkeys. It only applies to
+    /// registrations performed after calling this function.
+    pub fn set_no_repeat(&mut self, no_repeat: bool) {
+        self.no_repeat = no_repeat;
+    }
+
+    /// Start the event loop on the backend thread without waiting for it. The returned receiver
+    /// gets the reason once the event loop stopped. Used by the `ShardedHotkeyManager`.
+    ///
+    pub(crate) fn start_event_loop(&self) -> Receiver<ExitReason> {
+        let ret_ch = channel();
+        self.snd.send(HkMsg::EventLoop(ret_ch.0)).unwrap();
+        ret_ch.1
+    }
+
+    /// Restrict a registered hotkey to only trigger while the cursor is inside of the given screen
+    /// region. Passing `None` removes the restriction again.
+    ///
+    pub fn set_cursor_region(
+        &mut self,
+        id: HotkeyId,
+        region: Option<CursorRegion>,
+    ) -> Result<(), HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetCursorRegion(ret_ch.0, id, region))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Restrict a registered hotkey to only trigger in the given kind of session. Passing `None`
+    /// removes the restriction again.
+    ///
+    pub fn set_allowed_session(
+        &mut self,
+        id: HotkeyId,
+        session: Option<SessionKind>,
+    ) -> Result<(), HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetAllowedSession(ret_ch.0, id, session))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Restrict a registered hotkey to only trigger if the accessibility condition is met. See
+    /// `singlethreaded::HotkeyManager::set_accessibility_condition`.
+    ///
+    pub fn set_accessibility_condition(
+        &mut self,
+        id: HotkeyId,
+        condition: Option<AccessibilityCondition>,
+    ) -> Result<(), HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetAccessibilityCondition(ret_ch.0, id, condition))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Restrict a registered hotkey to only trigger while the foreground window meets the
+    /// condition. See `singlethreaded::HotkeyManager::set_window_condition`.
+    ///
+    pub fn set_window_condition(
+        &mut self,
+        id: HotkeyId,
+        condition: Option<WindowCondition>,
+    ) -> Result<(), HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetWindowCondition(ret_ch.0, id, condition))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Unregister or disable a registered hotkey after the given number of executed triggers. See
+    /// `singlethreaded::HotkeyManager::set_trigger_limit`.
+    ///
+    pub fn set_trigger_limit(
+        &mut self,
+        id: HotkeyId,
+        limit: Option<TriggerLimit>,
+    ) -> Result<(), HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetTriggerLimit(ret_ch.0, id, limit))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Set a function that receives the errors that only concern this hotkey. The function is
+    /// called from the backend thread. See `singlethreaded::HotkeyManager::set_error_handler`.
+    ///
+    pub fn set_error_handler(
+        &mut self,
+        id: HotkeyId,
+        on_error: impl Fn(&HkError) + Send + Sync + 'static,
+    ) -> Result<(), HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetErrorHandler(
+                ret_ch.0,
+                id,
+                Some(Arc::new(on_error)),
+            ))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Set a function that receives the errors of all hotkeys without an error handler, along with
+    /// the id of the hotkey. The function is called from the backend thread, or from the thread of
+    /// the dispatcher for the errors of `register_fallible` callbacks. See
+    /// `singlethreaded::HotkeyManager::set_error_sink`.
+    ///
+    pub fn set_error_sink(&mut self, sink: impl Fn(HotkeyId, &HkError) + Send + Sync + 'static) {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetErrorSink(ret_ch.0, Some(Arc::new(sink))))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Remove a previously set error sink
+    ///
+    pub fn remove_error_sink(&mut self) {
+        let ret_ch = channel();
+        self.snd.send(HkMsg::SetErrorSink(ret_ch.0, None)).unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Remove the error handler of a registered hotkey. See
+    /// `singlethreaded::HotkeyManager::clear_error_handler`.
+    ///
+    pub fn clear_error_handler(&mut self, id: HotkeyId) -> Result<(), HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetErrorHandler(ret_ch.0, id, None))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Restrict a registered hotkey to only trigger while the condition function returns true.
+    /// See `singlethreaded::HotkeyManager::set_condition`.
+    ///
+    pub fn set_condition(
+        &mut self,
+        id: HotkeyId,
+        condition: impl Fn() -> bool + Send + Sync + 'static,
+    ) -> Result<(), HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetCondition(ret_ch.0, id, Some(Arc::new(condition))))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Remove the condition function of a registered hotkey. See
+    /// `singlethreaded::HotkeyManager::clear_condition`.
+    ///
+    pub fn clear_condition(&mut self, id: HotkeyId) -> Result<(), HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetCondition(ret_ch.0, id, None))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Set what happens when a callback panics. See `singlethreaded::HotkeyManager::set_panic_policy`.
+    ///
+    /// Without a policy, a panicking callback kills the backend thread. See `take_backend_panic`.
+    ///
+    pub fn set_panic_policy(&mut self, policy: Option<PanicPolicy>) {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetPanicPolicy(ret_ch.0, policy))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Enable or disable a registered hotkey. See `singlethreaded::HotkeyManager::set_enabled`.
+    ///
+    pub fn set_enabled(&mut self, id: HotkeyId, enabled: bool) -> Result<(), HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetEnabled(ret_ch.0, id, enabled))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Register a key combination that switches the manager into a different mode. See
+    /// `singlethreaded::HotkeyManager::register_mode_switch`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_mode_switch(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> Result<HotkeyId, HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::RegisterModeSwitch(
+                ret_ch.0,
+                key,
+                key_modifiers.to_vec(),
+                from.map(str::to_string),
+                to.map(str::to_string),
+            ))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Switch to a mode, or to the default mode with `None`. Returns the hotkeys of the new mode
+    /// that couldn't be registered. See `singlethreaded::HotkeyManager::set_mode`.
+    ///
+    pub fn set_mode(&mut self, mode: Option<&str>) -> Vec<(HotkeyId, HkError)> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetMode(ret_ch.0, mode.map(str::to_string)))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Get the currently active mode, `None` for the default mode
+    ///
+    pub fn mode(&self) -> Option<String> {
+        let ret_ch = channel();
+        self.snd.send(HkMsg::Mode(ret_ch.0)).unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Leave the mode automatically if no hotkey is pressed for the given time. See
+    /// `singlethreaded::HotkeyManager::set_mode_timeout`.
+    ///
+    pub fn set_mode_timeout(&mut self, mode: &str, timeout: Option<Duration>) {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetModeTimeout(ret_ch.0, mode.to_string(), timeout))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Set a function that is called with the new mode whenever the mode changes. The function is
+    /// called on the backend thread. See `singlethreaded::HotkeyManager::on_mode_change`.
+    ///
+    pub fn on_mode_change(&mut self, observer: impl Fn(Option<&str>) + Send + 'static) {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetModeObserver(ret_ch.0, Some(Box::new(observer))))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Accept the commands that new instances of the program send with `RunningInstance::send`.
+    /// The handler is called on the backend thread. See
+    /// `singlethreaded::HotkeyManager::accept_instance_commands`.
+    ///
+    pub fn accept_instance_commands(
+        &mut self,
+        instance: &SingleInstance,
+        handler: impl Fn(InstanceCommand) + Send + 'static,
+    ) {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetInstanceHandler(
+                ret_ch.0,
+                instance.window_title(),
+                Box::new(handler),
+            ))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Suspend all hotkeys until `resume` is called. See
+    /// `singlethreaded::HotkeyManager::suspend`.
+    ///
+    pub fn suspend(&mut self) {
+        let ret_ch = channel();
+        self.snd.send(HkMsg::Suspend(ret_ch.0)).unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Register all hotkeys again after they were suspended. Returns the hotkeys that couldn't be
+    /// registered anymore. See `singlethreaded::HotkeyManager::resume`.
+    ///
+    pub fn resume(&mut self) -> Vec<(HotkeyId, HkError)> {
+        let ret_ch = channel();
+        self.snd.send(HkMsg::Resume(ret_ch.0)).unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Pause the backend: triggered hotkeys are discarded without executing their callbacks until
+    /// `resume_backend` is called. Everything else keeps working while paused, so hotkeys can
+    /// still be registered, unregistered and configured, for example while a new configuration
+    /// is loaded.
+    ///
+    /// Unlike `suspend`, the hotkeys stay registered with windows, so pausing and resuming can't
+    /// fail and no other application can take over the key combinations in the meantime. Their
+    /// key presses are still consumed while paused.
+    ///
+    /// This takes effect immediately, even while the event loop is running on another thread.
+    ///
+    pub fn pause_backend(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    /// Resume executing the callbacks of triggered hotkeys after `pause_backend`. Triggers that
+    /// were discarded while paused are not executed later.
+    ///
+    pub fn resume_backend(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+    }
+
+    /// Check if the backend is currently paused with `pause_backend`
+    ///
+    pub fn is_backend_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Check if the hotkeys are currently suspended. See
+    /// `singlethreaded::HotkeyManager::is_suspended`.
+    ///
+    pub fn is_suspended(&self) -> bool {
+        let ret_ch = channel();
+        self.snd.send(HkMsg::IsSuspended(ret_ch.0)).unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Enable the game mode, which unregisters the marked hotkeys while a fullscreen application
+    /// is in the foreground. See `singlethreaded::HotkeyManager::enable_game_mode`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-settimer>
+    ///
+    pub fn enable_game_mode(&mut self, interval: Duration) {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetGameMode(ret_ch.0, Some(interval)))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Disable the game mode and register the suppressed hotkeys again. See
+    /// `singlethreaded::HotkeyManager::disable_game_mode`.
+    ///
+    pub fn disable_game_mode(&mut self) {
+        let ret_ch = channel();
+        self.snd.send(HkMsg::SetGameMode(ret_ch.0, None)).unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Enable the hook diagnostics, which measure how low-level hooks of other applications delay
+    /// or swallow key presses. See `singlethreaded::HotkeyManager::enable_hook_diagnostics`.
+    ///
+    pub fn enable_hook_diagnostics(
+        &mut self,
+        interval: Duration,
+        threshold: Duration,
+    ) -> Result<(), HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetHookDiagnostics(
+                ret_ch.0,
+                Some((interval, threshold)),
+            ))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Disable the hook diagnostics. See
+    /// `singlethreaded::HotkeyManager::disable_hook_diagnostics`.
+    ///
+    pub fn disable_hook_diagnostics(&mut self) {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetHookDiagnostics(ret_ch.0, None))
+            .unwrap();
+        let _ = ret_ch.1.recv().unwrap();
+    }
+
+    /// Check if the game mode currently suppresses the hotkeys. See
+    /// `singlethreaded::HotkeyManager::is_game_mode_active`.
+    ///
+    pub fn is_game_mode_active(&self) -> bool {
+        let ret_ch = channel();
+        self.snd.send(HkMsg::IsGameModeActive(ret_ch.0)).unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Mark a registered hotkey to be unregistered while a fullscreen application is in the
+    /// foreground. See `singlethreaded::HotkeyManager::set_suppress_in_fullscreen`.
+    ///
+    pub fn set_suppress_in_fullscreen(
+        &mut self,
+        id: HotkeyId,
+        suppress: bool,
+    ) -> Result<(), HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetSuppressInFullscreen(ret_ch.0, id, suppress))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Add a registered hotkey to a named group or remove it from its group. See
+    /// `singlethreaded::HotkeyManager::set_group`.
+    ///
+    pub fn set_group(&mut self, id: HotkeyId, group: Option<&str>) -> Result<(), HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetGroup(ret_ch.0, id, group.map(str::to_string)))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Enable or disable all hotkeys of a named group at once. See
+    /// `singlethreaded::HotkeyManager::set_group_enabled`.
+    ///
+    pub fn set_group_enabled(&mut self, group: &str, enabled: bool) {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetGroupEnabled(ret_ch.0, group.to_string(), enabled))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Check if a named group is enabled. See `singlethreaded::HotkeyManager::is_group_enabled`.
+    ///
+    pub fn is_group_enabled(&self, group: &str) -> bool {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::IsGroupEnabled(ret_ch.0, group.to_string()))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Replace the callback of a registered hotkey, without unregistering it. See
+    /// `singlethreaded::HotkeyManager::set_callback`.
+    ///
+    pub fn set_callback(
+        &mut self,
+        id: HotkeyId,
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<(), HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetCallback(ret_ch.0, id, Box::new(callback)))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Set the context that is passed to the callbacks registered with `register_with_context`.
+    /// See `singlethreaded::HotkeyManager::set_context`.
+    ///
+    pub fn set_context<C: Send + Sync + 'static>(&mut self, context: C) {
+        self.context = Some(Arc::new(context));
+    }
+
+    /// Get the context that was set with `set_context`. See
+    /// `singlethreaded::HotkeyManager::context`.
+    ///
+    pub fn context<C: Send + Sync + 'static>(&self) -> Option<Arc<C>> {
+        downcast_context(&self.context).ok()
+    }
+
+    /// Register a new hotkey with a callback that receives a reference to the context of the
+    /// manager. See `singlethreaded::HotkeyManager::register_with_context`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_with_context<C: Send + Sync + 'static>(
+        &mut self,
+        key: VKey,
+        key_modifiers: impl IntoModifiers,
+        callback: impl Fn(&C) -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError>
+    where
+        T: Send,
+    {
+        let context = downcast_context::<C>(&self.context)?;
+        self.register(key, key_modifiers, move || callback(&context))
+    }
+
+    /// Get the information about all registered hotkeys, ordered by their ids. See
+    /// `singlethreaded::HotkeyManager::hotkeys`.
+    ///
+    pub fn hotkeys(&self) -> Vec<HotkeyInfo> {
+        let ret_ch = channel();
+        self.snd.send(HkMsg::Hotkeys(ret_ch.0)).unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Get the number of times that the callback of a hotkey was executed. See
+    /// `singlethreaded::HotkeyManager::trigger_count`.
+    ///
+    pub fn trigger_count(&self, id: HotkeyId) -> Result<u64, HkError> {
+        let ret_ch = channel();
+        self.snd.send(HkMsg::TriggerCount(ret_ch.0, id)).unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Get a snapshot of the self-metrics of the backend thread. See
+    /// `singlethreaded::HotkeyManager::metrics`.
+    ///
+    pub fn metrics(&self) -> Metrics {
+        let ret_ch = channel();
+        self.snd.send(HkMsg::Metrics(ret_ch.0)).unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Get a `TestHandle` for the hidden window of the backend thread. Get the handle before
+    /// starting the event loop, since the backend can't answer while the event loop is running.
+    /// See `singlethreaded::HotkeyManager::test_handle`.
+    ///
+    #[cfg(feature = "test-support")]
+    pub fn test_handle(&self) -> TestHandle {
+        let ret_ch = channel();
+        self.snd.send(HkMsg::TestHandle(ret_ch.0)).unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Only let the timers of the backend elapse when `TestHandle::advance_timers` is called. See
+    /// `singlethreaded::HotkeyManager::set_manual_timers`.
+    ///
+    #[cfg(feature = "test-support")]
+    pub fn set_manual_timers(&self, manual: bool) {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetManualTimers(ret_ch.0, manual))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Register a new hotkey that only carries an action name instead of a callback. See
+    /// `singlethreaded::HotkeyManager::register_action`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_action(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        action: &str,
+    ) -> Result<HotkeyId, HkError> {
+        self.register_action_combo(&HotkeyCombo::new(key, key_modifiers), action)
+    }
+
+    /// Same as `register_action`, but the key combination is taken from a `HotkeyCombo`. See
+    /// `singlethreaded::HotkeyManager::register_action_combo`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_action_combo(
+        &mut self,
+        combo: &HotkeyCombo,
+        action: &str,
+    ) -> Result<HotkeyId, HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::RegisterAction(
+                ret_ch.0,
+                combo.clone(),
+                action.to_string(),
+            ))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Set the function that executes the hotkeys registered with `register_action`. See
+    /// `singlethreaded::HotkeyManager::set_action_handler`.
+    ///
+    pub fn set_action_handler(
+        &mut self,
+        handler: impl Fn(&str, &HotkeyEvent) -> T + Send + 'static,
+    ) {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetActionHandler(
+                ret_ch.0,
+                Some(wrap_action_handler(handler)),
+            ))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Remove the action handler, so triggers of action hotkeys are ignored again
+    ///
+    pub fn remove_action_handler(&mut self) {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetActionHandler(ret_ch.0, None))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Register a new hotkey whose callback receives the trigger count. See
+    /// `singlethreaded::HotkeyManager::register_counted`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_counted(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        callback: impl Fn(u64) -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let ret_ch = channel();
+
+        let mut key_modifiers = key_modifiers.to_vec();
+        if self.no_repeat {
+            key_modifiers.push(ModKey::NoRepeat);
+        }
+
+        self.snd
+            .send(HkMsg::RegisterCounted(
+                ret_ch.0,
+                key,
+                key_modifiers,
+                Box::new(callback),
+            ))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Register a new hotkey that is unregistered after its first trigger. See
+    /// `singlethreaded::HotkeyManager::register_once`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-unregisterhotkey>
+    ///
+    pub fn register_once(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        callback: impl FnOnce() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let ret_ch = channel();
+
+        let mut key_modifiers = key_modifiers.to_vec();
+        if self.no_repeat {
+            key_modifiers.push(ModKey::NoRepeat);
+        }
+
+        self.snd
+            .send(HkMsg::RegisterOnce(
+                ret_ch.0,
+                key,
+                key_modifiers,
+                Box::new(callback),
+            ))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Register a new hotkey whose callback receives the `TriggerContext` of each trigger. See
+    /// `singlethreaded::HotkeyManager::register_with_trigger`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_with_trigger(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        callback: impl Fn(&TriggerContext) -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let ret_ch = channel();
+
+        let mut key_modifiers = key_modifiers.to_vec();
+        if self.no_repeat {
+            key_modifiers.push(ModKey::NoRepeat);
+        }
+
+        self.snd
+            .send(HkMsg::RegisterWithTrigger(
+                ret_ch.0,
+                key,
+                key_modifiers,
+                Box::new(callback),
+            ))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Register a new hotkey whose callback returns a `Result`, with the errors going to the error
+    /// sink. See `singlethreaded::HotkeyManager::register_fallible`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_fallible<E>(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        callback: impl Fn() -> Result<T, E> + Send + 'static,
+    ) -> Result<HotkeyId, HkError>
+    where
+        T: Default,
+        E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+    {
+        let ret_ch = channel();
+
+        let mut key_modifiers = key_modifiers.to_vec();
+        if self.no_repeat {
+            key_modifiers.push(ModKey::NoRepeat);
+        }
+
+        self.snd
+            .send(HkMsg::RegisterFallible(
+                ret_ch.0,
+                key,
+                key_modifiers,
+                Box::new(move || callback().map_err(Into::into)),
+                T::default,
+            ))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Register a new hotkey that switches between an on and an off state with every press. See
+    /// `singlethreaded::HotkeyManager::register_toggle`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_toggle(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        on_enable: impl Fn() -> T + Send + 'static,
+        on_disable: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let ret_ch = channel();
+
+        let mut key_modifiers = key_modifiers.to_vec();
+        if self.no_repeat {
+            key_modifiers.push(ModKey::NoRepeat);
+        }
+
+        self.snd
+            .send(HkMsg::RegisterToggle(
+                ret_ch.0,
+                key,
+                key_modifiers,
+                Box::new(on_enable),
+                Box::new(on_disable),
+            ))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Check if a toggle hotkey is currently in the on state. See
+    /// `singlethreaded::HotkeyManager::is_on`.
+    ///
+    pub fn is_on(&self, id: HotkeyId) -> Result<bool, HkError> {
+        let ret_ch = channel();
+        self.snd.send(HkMsg::IsOn(ret_ch.0, id)).unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Change the key combination of a registered hotkey while keeping its id, callback and all
+    /// other settings. See `singlethreaded::HotkeyManager::rebind`.
+    ///
+    pub fn rebind(
+        &mut self,
+        id: HotkeyId,
+        key: VKey,
+        key_modifiers: &[ModKey],
+    ) -> Result<(), HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::Rebind(ret_ch.0, id, key, key_modifiers.to_vec()))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Get the key combination that a registered hotkey is currently bound to. See
+    /// `singlethreaded::HotkeyManager::combo`.
+    ///
+    pub fn combo(&self, id: HotkeyId) -> Result<HotkeyCombo, HkError> {
+        let ret_ch = channel();
+        self.snd.send(HkMsg::Combo(ret_ch.0, id)).unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Same as `register`, but returns a `HotkeyHandle` instead of the id. The handle can be used
+    /// to unregister, disable or rebind the hotkey without access to the manager.
+    ///
+    pub fn register_handle(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyHandle<T>, HkError>
+    where
+        T: Send,
+    {
+        let id = self.register(key, key_modifiers, callback)?;
+        Ok(HotkeyHandle {
+            id,
+            snd: self.snd.clone(),
+        })
+    }
+
+    /// Register a new hotkey with a mouse button as the main key, for example
+    /// `CTRL + MiddleClick`. See `singlethreaded::HotkeyManager::register_mouse`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setwindowshookexw>
+    ///
+    pub fn register_mouse(
+        &mut self,
+        button: MouseButton,
+        key_modifiers: &[ModKey],
+        block: bool,
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::RegisterMouse(
+                ret_ch.0,
+                button,
+                key_modifiers.to_vec(),
+                block,
+                Box::new(callback),
+            ))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Register a mouse gesture that is drawn while the given mouse button is held. See
+    /// `singlethreaded::HotkeyManager::register_gesture`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setwindowshookexw>
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-sendinput>
+    ///
+    pub fn register_gesture(
+        &mut self,
+        button: MouseButton,
+        key_modifiers: &[ModKey],
+        strokes: &[Stroke],
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::RegisterGesture(
+                ret_ch.0,
+                button,
+                key_modifiers.to_vec(),
+                strokes.to_vec(),
+                Box::new(callback),
+            ))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Register a new hotkey that is triggered by scrolling the mouse wheel, for example
+    /// `CTRL + WheelUp`. See `singlethreaded::HotkeyManager::register_wheel`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setwindowshookexw>
+    ///
+    pub fn register_wheel(
+        &mut self,
+        direction: MouseWheel,
+        key_modifiers: &[ModKey],
+        block: bool,
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::RegisterWheel(
+                ret_ch.0,
+                direction,
+                key_modifiers.to_vec(),
+                block,
+                Box::new(callback),
+            ))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Register a new hotkey and choose if it is retriggered while the keys are held down,
+    /// independent of the manager wide `set_no_repeat` option. See
+    /// `singlethreaded::HotkeyManager::register_with_repeat`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_with_repeat(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        repeat: bool,
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let options = RegisterOptions {
+            no_repeat: Some(!repeat),
+            ..Default::default()
+        };
+        self.register_with_options(key, key_modifiers, options, callback)
+    }
+
+    /// Register a hotkey that was built with the `HotkeyBuilder`. See
+    /// `singlethreaded::HotkeyManager::add`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn add(&mut self, hotkey: options::Hotkey<T>) -> Result<HotkeyId, HkError> {
+        self.register_with_options(
+            hotkey.key,
+            &hotkey.modifiers,
+            hotkey.options,
+            hotkey.callback,
+        )
+    }
+
+    /// Register a new hotkey with additional per-hotkey options, like extra keys, a label or a
+    /// cursor region. See `singlethreaded::HotkeyManager::register_with_options`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_with_options(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        mut options: RegisterOptions,
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let ret_ch = channel();
+
+        // The backend manager never adds `ModKey::NoRepeat` by itself
+        options.no_repeat = Some(options.no_repeat.unwrap_or(self.no_repeat));
+
+        self.snd
+            .send(HkMsg::Register(
+                ret_ch.0,
+                key,
+                key_modifiers.to_vec(),
+                Box::new(options),
+                Box::new(callback),
+            ))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Register a new hotkey that is detected by a low-level keyboard hook instead of registering
+    /// it with windows. This allows using key combinations that are reserved by the OS, for example
+    /// using the Win key as the only modifier for arbitrary keys like Win + E.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`, `key_modifiers` and `callback` - Same as for `register`.
+    ///
+    /// * `block` - If true, the key event is blocked, so the default action of the OS (like
+    ///   Explorer opening for Win + E) or the focused application is not executed. The release of
+    ///   the Win or ALT key is masked so that it doesn't open the start menu or the menu bar. If
+    ///   false, the callback is executed in addition to the default action.
+    ///
+    /// # Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setwindowshookexw>
+    ///
+    pub fn register_hooked(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        block: bool,
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let ret_ch = channel();
+
+        let mut key_modifiers = key_modifiers.to_vec();
+        if self.no_repeat {
+            key_modifiers.push(ModKey::NoRepeat);
+        }
+
+        let hk = Hotkey {
+            key,
+            key_modifiers,
+            callback: Box::new(callback),
+        };
+        self.snd
+            .send(HkMsg::RegisterHooked(ret_ch.0, hk, block))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Register a new hotkey that is triggered by tapping a modifier key on its own, meaning that
+    /// the modifier is pressed and released without any other key in between. For example
+    /// tapping `ModKey::Win` can be used to open a launcher instead of the start menu.
+    ///
+    /// The modifier tap is detected by a low-level keyboard hook and the default action of the
+    /// tap, like opening the start menu or activating the menu bar, is suppressed.
+    ///
+    /// # Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setwindowshookexw>
+    ///
+    pub fn register_modifier_tap(
+        &mut self,
+        modifier: ModKey,
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::RegisterModifierTap(
+                ret_ch.0,
+                modifier,
+                Box::new(callback),
+            ))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Register multiple alternative key combinations that all execute the same callback. The
+    /// returned id controls all of the aliases together, for example when unregistering the
+    /// hotkey or setting a cursor region. The alias that was pressed is reported in the
+    /// `HotkeyEvent`.
+    ///
+    /// If one of the aliases can't be registered, none of them are registered.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_aliases(
+        &mut self,
+        combos: &[HotkeyCombo],
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let ret_ch = channel();
+
+        let mut combos = combos.to_vec();
+        if self.no_repeat {
+            combos
+                .iter_mut()
+                .for_each(|combo| combo.modifiers.push(ModKey::NoRepeat));
+        }
+
+        self.snd
+            .send(HkMsg::RegisterAliases(ret_ch.0, combos, Box::new(callback)))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Reserve a key combination without a callback. The combination is registered with windows,
+    /// so other applications can't register it and the key presses are swallowed, but nothing is
+    /// executed when it is pressed. This is useful to block combinations in kiosk or exam
+    /// software.
+    ///
+    /// Reserving an already reserved combination does nothing.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn reserve(&mut self, key: VKey, key_modifiers: &[ModKey]) -> Result<(), HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::Reserve(ret_ch.0, key, key_modifiers.to_vec()))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Release a key combination that was reserved with `reserve`, so it can be used by other
+    /// applications again.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-unregisterhotkey>
+    ///
+    pub fn release(&mut self, key: VKey, key_modifiers: &[ModKey]) -> Result<(), HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::Release(ret_ch.0, key, key_modifiers.to_vec()))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Register a multi-step hotkey sequence, like `CTRL + K` followed by `CTRL + C`. The callback
+    /// is executed when all steps are pressed in order, with at most the sequence timeout between
+    /// two steps (see `set_sequence_timeout`).
+    ///
+    /// Only the first step is registered with windows permanently. Pressing it arms the sequence
+    /// and registers the next step until the sequence is completed or times out, so the other
+    /// steps can still be used normally. Multiple sequences can start with the same steps.
+    ///
+    /// The returned id can be used to unregister the sequence with `unregister`.
+    ///
+    /// # Note
+    /// Steps that are also registered as regular hotkeys of this `HotkeyManager` can't be armed,
+    /// since windows only allows registering every key combination once.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-settimer>
+    ///
+    pub fn register_sequence(
+        &mut self,
+        steps: &[SequenceStep],
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::RegisterSequence(
+                ret_ch.0,
+                steps.to_vec(),
+                Box::new(callback),
+            ))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Set the maximum time between two steps of a hotkey sequence. If the next step is not
+    /// pressed in time, the sequence has to be started from the first step again. The default is
+    /// one second.
+    ///
+    pub fn set_sequence_timeout(&mut self, timeout: Duration) {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetSequenceTimeout(ret_ch.0, timeout))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Set the leader key combination and the time in which a leader action has to be pressed
+    /// after it. Pressing the leader enters the leader mode, in which the keys registered with
+    /// `register_leader_action` execute their actions, similar to the leader key in vim.
+    ///
+    /// Changing the leader also changes it for all leader actions that are already registered.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn set_leader(&mut self, leader: HotkeyCombo, timeout: Duration) -> Result<(), HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetLeader(ret_ch.0, leader, timeout))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Register an action for the leader mode. The callback is executed when the key combination
+    /// is pressed after the leader, within the leader timeout. The leader mode ends after one
+    /// action. The leader has to be set with `set_leader` first.
+    ///
+    /// The returned id can be used to unregister the action with `unregister`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_leader_action(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let ret_ch = channel();
+        let hk = Hotkey {
+            key,
+            key_modifiers: key_modifiers.to_vec(),
+            callback: Box::new(callback),
+        };
+        self.snd
+            .send(HkMsg::RegisterLeaderAction(ret_ch.0, hk))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Register a new hotkey that executes the actions in turn on successive presses. See
+    /// `singlethreaded::HotkeyManager::register_cycle`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    ///
+    pub fn register_cycle(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        actions: Vec<Box<dyn Fn() -> T + Send + 'static>>,
+        reset_after: Option<Duration>,
+    ) -> Result<HotkeyId, HkError> {
+        if actions.is_empty() {
+            return Err(HkError::NoActions);
+        }
+
+        let callback = cycle_callback(actions, reset_after);
+        self.register_with_options(key, key_modifiers, RegisterOptions::default(), callback)
+    }
+
+    /// Register a new hotkey that only executes the callback after the key combination was held
+    /// down for the given duration, for example holding `WIN + SPACE` for 800ms. If the keys are
+    /// released early, the trigger is cancelled.
+    ///
+    /// `ModKey::NoRepeat` is always added for long-press hotkeys.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-settimer>
+    ///
+    pub fn register_long_press(
+        &mut self,
+        key: VKey,
+        key_modifiers: &[ModKey],
+        hold: Duration,
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<HotkeyId, HkError> {
+        let ret_ch = channel();
+        let hk = Hotkey {
+            key,
+            key_modifiers: key_modifiers.to_vec(),
+            callback: Box::new(callback),
+        };
+        self.snd
+            .send(HkMsg::RegisterLongPress(ret_ch.0, hk, hold))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Set a separate callback that is executed when the hotkey is pressed twice within the given
+    /// time window. For example double-tapping `CTRL + C` can execute a different action than
+    /// pressing it once.
+    ///
+    /// While a double-tap callback is set, the regular callback is only executed after the time
+    /// window expired without a second press. `ModKey::NoRepeat` should be used, so that holding
+    /// the hotkey down doesn't count as a double-tap.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-settimer>
+    ///
+    pub fn set_double_tap(
+        &mut self,
+        id: HotkeyId,
+        window: Duration,
+        callback: impl Fn() -> T + Send + 'static,
+    ) -> Result<(), HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetDoubleTap(
+                ret_ch.0,
+                id,
+                Some((window, Box::new(callback))),
+            ))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Remove the double-tap callback of a hotkey, so that the regular callback is executed
+    /// immediately again.
+    ///
+    pub fn remove_double_tap(&mut self, id: HotkeyId) -> Result<(), HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetDoubleTap(ret_ch.0, id, None))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Run the event loop like `event_loop`, but call `on_idle` whenever no hotkey callback was
+    /// executed within the timeout. This allows housekeeping tasks, like checking for
+    /// configuration changes, to run without an additional thread.
+    ///
+    /// The `on_idle` callback is executed on the backend thread.
+    ///
+    /// ## Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-msgwaitformultipleobjectsex>
+    ///
+    pub fn event_loop_with_idle(
+        &self,
+        timeout: Duration,
+        on_idle: impl FnMut() + Send + 'static,
+    ) -> ExitReason {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::EventLoopWithIdle(
+                ret_ch.0,
+                timeout,
+                Box::new(on_idle),
+            ))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Repeat the callback of a registered hotkey while the hotkey is held down, according to the
+    /// given `RepeatPolicy`. Passing `None` disables the repeating again.
+    ///
+    /// # Note
+    /// The repeating relies on the global key state, which is not updated for keys blocked by a
+    /// keyboard hook. So this doesn't work for hotkeys registered with `register_hooked` and
+    /// `block = true`.
+    ///
+    pub fn set_repeat_policy(
+        &mut self,
+        id: HotkeyId,
+        policy: Option<RepeatPolicy>,
+    ) -> Result<(), HkError> {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetRepeatPolicy(ret_ch.0, id, policy))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Add an observer that is called with the information about every hotkey after it was
+    /// successfully registered. This can be used to keep UI elements in sync with the active
+    /// hotkeys, regardless of where they were registered.
+    ///
+    /// The observer is executed on the backend thread.
+    ///
+    pub fn on_registered(&mut self, observer: impl Fn(&HotkeyInfo) + Send + 'static) {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::OnRegistered(ret_ch.0, Box::new(observer)))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Add an observer that is called with the information about every hotkey after it was
+    /// successfully unregistered. This includes hotkeys unregistered by `unregister_all`.
+    ///
+    /// The observer is executed on the backend thread.
+    ///
+    pub fn on_unregistered(&mut self, observer: impl Fn(&HotkeyInfo) + Send + 'static) {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::OnUnregistered(ret_ch.0, Box::new(observer)))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Set a message tap that is called for every message received by the event loop, including
+    /// hotkey messages for unknown ids and interrupts. This is intended for troubleshooting.
+    ///
+    /// The message tap is executed on the backend thread.
+    ///
+    pub fn set_message_tap(&mut self, tap: impl Fn(&TappedMessage) + Send + 'static) {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetMessageTap(ret_ch.0, Some(Box::new(tap))))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Remove a previously set message tap.
+    ///
+    pub fn remove_message_tap(&mut self) {
+        let ret_ch = channel();
+        self.snd.send(HkMsg::SetMessageTap(ret_ch.0, None)).unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Set an event sink that is called for every triggered hotkey, both when the callback is
+    /// executed and when the trigger is rejected because the extra keys or other conditions are
+    /// not met. This helps debugging hotkeys with extra keys, which would otherwise fail silently.
+    ///
+    /// The event sink is executed on the backend thread.
+    ///
+    pub fn set_event_sink(&mut self, sink: impl Fn(&HotkeyEvent) + Send + 'static) {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetEventSink(ret_ch.0, Some(Box::new(sink))))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Remove a previously set event sink.
+    ///
+    pub fn remove_event_sink(&mut self) {
+        let ret_ch = channel();
+        self.snd.send(HkMsg::SetEventSink(ret_ch.0, None)).unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Throttle the events that are passed to the event sink, merging the triggers of a hotkey
+    /// within the interval into one event. See
+    /// `singlethreaded::HotkeyManager::set_event_throttle`.
+    ///
+    /// # Windows API Functions used
+    /// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-settimer>
+    ///
+    pub fn set_event_throttle(&mut self, interval: Option<Duration>) {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetEventThrottle(ret_ch.0, interval))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Enable a low latency mode that polls the message queue for the given duration before the
+    /// event loop blocks. See `singlethreaded::HotkeyManager::set_spin_wait`.
+    ///
+    /// ## Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-peekmessagew>
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-switchtothread>
+    ///
+    pub fn set_spin_wait(&mut self, spin: Option<Duration>) {
+        let ret_ch = channel();
+        self.snd.send(HkMsg::SetSpinWait(ret_ch.0, spin)).unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Set a feedback function that is called whenever a hotkey callback is executed, with the
+    /// formatted key combination and the label of the hotkey. The function is called on the
+    /// backend thread. See `singlethreaded::HotkeyManager::set_trigger_feedback`.
+    ///
+    pub fn set_trigger_feedback(&mut self, feedback: impl Fn(&str, Option<&str>) + Send + 'static) {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetTriggerFeedback(
+                ret_ch.0,
+                Some(Box::new(feedback)),
+            ))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Remove a previously set trigger feedback function.
+    ///
+    pub fn remove_trigger_feedback(&mut self) {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetTriggerFeedback(ret_ch.0, None))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Set a `KeyboardFeedback` that shows the current mode and the executed hotkeys on the
+    /// keyboard. It is called on the backend thread. See
+    /// `singlethreaded::HotkeyManager::set_keyboard_feedback`.
+    ///
+    pub fn set_keyboard_feedback(&mut self, feedback: impl KeyboardFeedback + 'static) {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetKeyboardFeedback(
+                ret_ch.0,
+                Some(Box::new(feedback)),
+            ))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Remove a previously set keyboard feedback
+    ///
+    pub fn remove_keyboard_feedback(&mut self) {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetKeyboardFeedback(ret_ch.0, None))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Move all hotkeys from another `HotkeyManager` into this one. The hotkeys are unregistered
+    /// from the other manager and registered again with this manager, keeping their callbacks
+    /// and all other settings. This allows composing hotkeys that were registered independently,
+    /// for example by plugins, into a single event loop.
+    ///
+    /// Since the hotkeys receive new ids, a map from the old ids to the results of the new
+    /// registrations is returned. Hotkeys that fail to register again are dropped.
+    ///
+    pub fn absorb(
+        &mut self,
+        other: HotkeyManager<T>,
+    ) -> HashMap<HotkeyId, Result<HotkeyId, HkError>> {
+        let ret_ch = channel();
+        other.snd.send(HkMsg::TakeHandlers(ret_ch.0)).unwrap();
+        let handlers = ret_ch.1.recv().unwrap();
+
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::AbsorbHandlers(ret_ch.0, handlers))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Check if the backend thread has died because of a panic and take the panic payload. This
+    /// returns `None` while the backend is still running, or if the payload was already taken.
+    ///
+    /// Once the backend has died, all further calls to the `HotkeyManager` that communicate with
+    /// the backend will panic. Dropping the `HotkeyManager` is still safe though.
+    ///
+    pub fn take_backend_panic(&mut self) -> Option<Box<dyn Any + Send + 'static>> {
+        if !self.backend_handle.as_ref()?.is_finished() {
+            return None;
+        }
+
+        self.backend_handle.take()?.join().err()
+    }
+}
+
+impl HotkeyManager<()> {
+    /// Set a dispatcher that receives the callbacks of matched hotkeys instead of executing them
+    /// directly on the backend thread. The dispatcher is called with a boxed closure that executes
+    /// the callback and can for example be posted to the main thread of a GUI framework.
     ///
-    /// When this option is disabled, the `ModKey::NoRepeat` can still be manually added while
-    /// registering hotkeys.
+    /// While a dispatcher is set, `handle_hotkey` returns `Some(())` as soon as the callback was
+    /// handed to the dispatcher.
     ///
-    /// Note: Setting this flag doesn't change previously registered hotkeys. It only applies to
-    /// registrations performed after calling this function.
-    pub fn set_no_repeat(&mut self, no_repeat: bool) {
-        self.no_repeat = no_repeat;
+    pub fn set_dispatcher(
+        &mut self,
+        dispatcher: impl Fn(Box<dyn FnOnce() + Send>) + Send + 'static,
+    ) {
+        let ret_ch = channel();
+        self.snd
+            .send(HkMsg::SetDispatcher(
+                ret_ch.0,
+                Some(wrap_dispatcher(dispatcher)),
+            ))
+            .unwrap();
+        ret_ch.1.recv().unwrap()
+    }
+
+    /// Remove a previously set dispatcher. Callbacks will be executed directly on the backend
+    /// thread again.
+    ///
+    pub fn remove_dispatcher(&mut self) {
+        let ret_ch = channel();
+        self.snd.send(HkMsg::SetDispatcher(ret_ch.0, None)).unwrap();
+        ret_ch.1.recv().unwrap()
     }
 }
 
@@ -67,13 +1855,74 @@ impl<T> TSHotkeyManagerBackend<T> {
     fn backend_loop(&mut self) {
         while let Ok(msg) = self.rec.recv() {
             match msg {
-                HkMsg::Register(chan_ret, hk) => {
-                    let ret_val = self.hkm.register_extrakeys(
-                        hk.key,
-                        &hk.key_modifiers,
-                        &hk.extra_keys,
-                        hk.callback,
-                    );
+                HkMsg::Register(chan_ret, key, key_modifiers, options, callback) => {
+                    let ret_val =
+                        self.hkm
+                            .register_with_options(key, &key_modifiers, *options, callback);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::RegisterHooked(chan_ret, hk, block) => {
+                    let ret_val =
+                        self.hkm
+                            .register_hooked(hk.key, &hk.key_modifiers, block, hk.callback);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::RegisterWheel(chan_ret, direction, key_modifiers, block, callback) => {
+                    let ret_val =
+                        self.hkm
+                            .register_wheel(direction, &key_modifiers, block, callback);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::RegisterMouse(chan_ret, button, key_modifiers, block, callback) => {
+                    let ret_val = self
+                        .hkm
+                        .register_mouse(button, &key_modifiers, block, callback);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::RegisterGesture(chan_ret, button, key_modifiers, strokes, callback) => {
+                    let ret_val =
+                        self.hkm
+                            .register_gesture(button, &key_modifiers, &strokes, callback);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::RegisterModifierTap(chan_ret, modifier, callback) => {
+                    let ret_val = self.hkm.register_modifier_tap(modifier, callback);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::RegisterSequence(chan_ret, steps, callback) => {
+                    let ret_val = self.hkm.register_sequence(&steps, callback);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::SetSequenceTimeout(chan_ret, timeout) => {
+                    self.hkm.set_sequence_timeout(timeout);
+                    chan_ret.send(()).unwrap();
+                }
+                HkMsg::RegisterAliases(chan_ret, combos, callback) => {
+                    let ret_val = self.hkm.register_aliases(&combos, callback);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::Reserve(chan_ret, key, key_modifiers) => {
+                    let ret_val = self.hkm.reserve(key, &key_modifiers);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::Release(chan_ret, key, key_modifiers) => {
+                    let ret_val = self.hkm.release(key, &key_modifiers);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::SetLeader(chan_ret, leader, timeout) => {
+                    let ret_val = self.hkm.set_leader(leader, timeout);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::RegisterLeaderAction(chan_ret, hk) => {
+                    let ret_val =
+                        self.hkm
+                            .register_leader_action(hk.key, &hk.key_modifiers, hk.callback);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::RegisterLongPress(chan_ret, hk, hold) => {
+                    let ret_val =
+                        self.hkm
+                            .register_long_press(hk.key, &hk.key_modifiers, hold, hk.callback);
                     chan_ret.send(ret_val).unwrap();
                 }
                 HkMsg::HandleHotkey(chan_ret) => {
@@ -89,13 +1938,261 @@ impl<T> TSHotkeyManagerBackend<T> {
                     chan_ret.send(ret_val).unwrap();
                 }
                 HkMsg::EventLoop(chan_ret) => {
-                    self.hkm.event_loop();
-                    chan_ret.send(()).unwrap();
+                    let ret_val = self.hkm.event_loop();
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::EventLoopWithIdle(chan_ret, timeout, on_idle) => {
+                    let ret_val = self.hkm.event_loop_with_idle(timeout, on_idle);
+                    chan_ret.send(ret_val).unwrap();
                 }
                 HkMsg::InterruptHandle(chan_ret) => {
                     let ret_val = self.hkm.interrupt_handle();
                     chan_ret.send(ret_val).unwrap();
                 }
+                HkMsg::SetDispatcher(chan_ret, dispatcher) => {
+                    self.hkm.set_dispatch_fn(dispatcher);
+                    chan_ret.send(()).unwrap();
+                }
+                HkMsg::SetCursorRegion(chan_ret, hkid, region) => {
+                    let ret_val = self.hkm.set_cursor_region(hkid, region);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::SetAllowedSession(chan_ret, hkid, session) => {
+                    let ret_val = self.hkm.set_allowed_session(hkid, session);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::SetAccessibilityCondition(chan_ret, hkid, condition) => {
+                    let ret_val = self.hkm.set_accessibility_condition(hkid, condition);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::SetWindowCondition(chan_ret, hkid, condition) => {
+                    let ret_val = self.hkm.set_window_condition(hkid, condition);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::SetTriggerLimit(chan_ret, hkid, limit) => {
+                    let ret_val = self.hkm.set_trigger_limit(hkid, limit);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::SetErrorHandler(chan_ret, hkid, on_error) => {
+                    let ret_val = self.hkm.update_error_handler(hkid, on_error);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::SetCondition(chan_ret, hkid, condition) => {
+                    let ret_val = self.hkm.update_condition(hkid, condition);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::SetPanicPolicy(chan_ret, policy) => {
+                    self.hkm.set_panic_policy(policy);
+                    chan_ret.send(()).unwrap();
+                }
+                HkMsg::Hotkeys(chan_ret) => {
+                    let ret_val = self.hkm.hotkeys();
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::RegisterAction(chan_ret, combo, action) => {
+                    let ret_val = self.hkm.register_action_combo(&combo, &action);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::SetActionHandler(chan_ret, handler) => {
+                    self.hkm.set_action_handler_fn(handler);
+                    chan_ret.send(()).unwrap();
+                }
+                HkMsg::Metrics(chan_ret) => {
+                    let ret_val = self.hkm.metrics();
+                    chan_ret.send(ret_val).unwrap();
+                }
+                #[cfg(feature = "test-support")]
+                HkMsg::TestHandle(chan_ret) => {
+                    let ret_val = self.hkm.test_handle();
+                    chan_ret.send(ret_val).unwrap();
+                }
+                #[cfg(feature = "test-support")]
+                HkMsg::SetManualTimers(chan_ret, manual) => {
+                    self.hkm.set_manual_timers(manual);
+                    chan_ret.send(()).unwrap();
+                }
+                HkMsg::TriggerCount(chan_ret, hkid) => {
+                    let ret_val = self.hkm.trigger_count(hkid);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::RegisterCounted(chan_ret, key, key_modifiers, callback) => {
+                    let ret_val = self.hkm.register_counted(key, &key_modifiers, callback);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::RegisterOnce(chan_ret, key, key_modifiers, callback) => {
+                    let ret_val = self.hkm.register_once(key, &key_modifiers, callback);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::RegisterWithTrigger(chan_ret, key, key_modifiers, callback) => {
+                    let ret_val = self
+                        .hkm
+                        .register_with_trigger(key, &key_modifiers, callback);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::RegisterFallible(chan_ret, key, key_modifiers, callback, fallback) => {
+                    let ret_val =
+                        self.hkm
+                            .register_fallible_fn(key, &key_modifiers, callback, fallback);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::RegisterToggle(chan_ret, key, key_modifiers, on_enable, on_disable) => {
+                    let ret_val =
+                        self.hkm
+                            .register_toggle(key, &key_modifiers, on_enable, on_disable);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::IsOn(chan_ret, hkid) => {
+                    let ret_val = self.hkm.is_on(hkid);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::SetEnabled(chan_ret, hkid, enabled) => {
+                    let ret_val = self.hkm.set_enabled(hkid, enabled);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::SetGroup(chan_ret, hkid, group) => {
+                    let ret_val = self.hkm.set_group(hkid, group.as_deref());
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::SetGroupEnabled(chan_ret, group, enabled) => {
+                    self.hkm.set_group_enabled(&group, enabled);
+                    chan_ret.send(()).unwrap();
+                }
+                HkMsg::RegisterModeSwitch(chan_ret, key, key_modifiers, from, to) => {
+                    let ret_val = self.hkm.register_mode_switch(
+                        key,
+                        &key_modifiers,
+                        from.as_deref(),
+                        to.as_deref(),
+                    );
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::SetMode(chan_ret, mode) => {
+                    let ret_val = self.hkm.set_mode(mode.as_deref());
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::Mode(chan_ret) => {
+                    let ret_val = self.hkm.mode();
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::SetModeTimeout(chan_ret, mode, timeout) => {
+                    self.hkm.set_mode_timeout(&mode, timeout);
+                    chan_ret.send(()).unwrap();
+                }
+                HkMsg::SetModeObserver(chan_ret, observer) => {
+                    self.hkm.set_mode_observer(observer);
+                    chan_ret.send(()).unwrap();
+                }
+                HkMsg::SetInstanceHandler(chan_ret, title, handler) => {
+                    self.hkm.set_instance_handler(&title, handler);
+                    chan_ret.send(()).unwrap();
+                }
+                HkMsg::Suspend(chan_ret) => {
+                    self.hkm.suspend();
+                    chan_ret.send(()).unwrap();
+                }
+                HkMsg::Resume(chan_ret) => {
+                    let ret_val = self.hkm.resume();
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::IsSuspended(chan_ret) => {
+                    let ret_val = self.hkm.is_suspended();
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::SetGameMode(chan_ret, interval) => {
+                    match interval {
+                        Some(interval) => self.hkm.enable_game_mode(interval),
+                        None => self.hkm.disable_game_mode(),
+                    }
+                    chan_ret.send(()).unwrap();
+                }
+                HkMsg::SetHookDiagnostics(chan_ret, diagnostics) => {
+                    let ret_val = match diagnostics {
+                        Some((interval, threshold)) => {
+                            self.hkm.enable_hook_diagnostics(interval, threshold)
+                        }
+                        None => {
+                            self.hkm.disable_hook_diagnostics();
+                            Ok(())
+                        }
+                    };
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::IsGameModeActive(chan_ret) => {
+                    let ret_val = self.hkm.is_game_mode_active();
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::SetSuppressInFullscreen(chan_ret, hkid, suppress) => {
+                    let ret_val = self.hkm.set_suppress_in_fullscreen(hkid, suppress);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::IsGroupEnabled(chan_ret, group) => {
+                    let ret_val = self.hkm.is_group_enabled(&group);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::SetCallback(chan_ret, hkid, callback) => {
+                    let ret_val = self.hkm.set_callback(hkid, callback);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::Rebind(chan_ret, hkid, key, key_modifiers) => {
+                    let ret_val = self.hkm.rebind(hkid, key, &key_modifiers);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::Combo(chan_ret, hkid) => {
+                    let ret_val = self.hkm.combo(hkid);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::OnRegistered(chan_ret, observer) => {
+                    self.hkm.on_registered(observer);
+                    chan_ret.send(()).unwrap();
+                }
+                HkMsg::OnUnregistered(chan_ret, observer) => {
+                    self.hkm.on_unregistered(observer);
+                    chan_ret.send(()).unwrap();
+                }
+                HkMsg::TakeHandlers(chan_ret) => {
+                    let ret_val = self.hkm.take_handlers();
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::AbsorbHandlers(chan_ret, handlers) => {
+                    let ret_val = self.hkm.absorb_handlers(handlers);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::SetEventSink(chan_ret, sink) => {
+                    self.hkm.set_event_sink_fn(sink);
+                    chan_ret.send(()).unwrap();
+                }
+                HkMsg::SetEventThrottle(chan_ret, interval) => {
+                    self.hkm.set_event_throttle(interval);
+                    chan_ret.send(()).unwrap();
+                }
+                HkMsg::SetSpinWait(chan_ret, spin) => {
+                    self.hkm.set_spin_wait(spin);
+                    chan_ret.send(()).unwrap();
+                }
+                HkMsg::SetKeyboardFeedback(chan_ret, feedback) => {
+                    self.hkm.set_keyboard_feedback_box(feedback);
+                    chan_ret.send(()).unwrap();
+                }
+                HkMsg::SetErrorSink(chan_ret, sink) => {
+                    self.hkm.set_error_sink_fn(sink);
+                    chan_ret.send(()).unwrap();
+                }
+                HkMsg::SetTriggerFeedback(chan_ret, feedback) => {
+                    self.hkm.set_trigger_feedback_fn(feedback);
+                    chan_ret.send(()).unwrap();
+                }
+                HkMsg::SetMessageTap(chan_ret, tap) => {
+                    self.hkm.set_message_tap_fn(tap);
+                    chan_ret.send(()).unwrap();
+                }
+                HkMsg::SetDoubleTap(chan_ret, hkid, double_tap) => {
+                    let ret_val = self.hkm.set_double_tap_fn(hkid, double_tap);
+                    chan_ret.send(ret_val).unwrap();
+                }
+                HkMsg::SetRepeatPolicy(chan_ret, hkid, policy) => {
+                    let ret_val = self.hkm.set_repeat_policy(hkid, policy);
+                    chan_ret.send(ret_val).unwrap();
+                }
                 HkMsg::Exit(chan_ret) => {
                     chan_ret.send(()).unwrap();
                     return;
@@ -108,28 +2205,24 @@ impl<T> TSHotkeyManagerBackend<T> {
 impl<T: 'static + Send> HotkeyManagerImpl<T> for HotkeyManager<T> {
     fn new() -> Self {
         let (snd, rec) = channel();
+        let paused = Arc::new(AtomicBool::new(false));
+        let backend_paused = paused.clone();
         let backend_handle = spawn(move || {
             let mut backend = TSHotkeyManagerBackend::<T>::new(rec);
+            backend.hkm.set_pause_flag(backend_paused);
             backend.backend_loop();
         });
 
         Self {
             no_repeat: true,
+            context: None,
             _phantom: PhantomData,
             snd,
             backend_handle: Some(backend_handle),
+            paused,
         }
     }
 
-    fn register(
-        &mut self,
-        key: VKey,
-        key_modifiers: &[ModKey],
-        callback: impl Fn() -> T + Send + 'static,
-    ) -> Result<HotkeyId, HkError> {
-        self.register_extrakeys(key, key_modifiers, &[], callback)
-    }
-
     fn register_extrakeys(
         &mut self,
         key: VKey,
@@ -137,21 +2230,11 @@ impl<T: 'static + Send> HotkeyManagerImpl<T> for HotkeyManager<T> {
         extra_keys: &[VKey],
         callback: impl Fn() -> T + Send + 'static,
     ) -> Result<HotkeyId, HkError> {
-        let ret_ch = channel();
-
-        let mut key_modifiers = key_modifiers.to_vec();
-        if self.no_repeat {
-            key_modifiers.push(ModKey::NoRepeat);
-        }
-
-        let hk = Hotkey {
-            key,
-            key_modifiers,
+        let options = RegisterOptions {
             extra_keys: extra_keys.to_vec(),
-            callback: Box::new(callback),
+            ..Default::default()
         };
-        self.snd.send(HkMsg::Register(ret_ch.0, hk)).unwrap();
-        ret_ch.1.recv().unwrap()
+        self.register_with_options(key, key_modifiers, options, callback)
     }
 
     fn unregister(&mut self, id: HotkeyId) -> Result<(), HkError> {
@@ -172,10 +2255,8 @@ impl<T: 'static + Send> HotkeyManagerImpl<T> for HotkeyManager<T> {
         ret_ch.1.recv().unwrap()
     }
 
-    fn event_loop(&self) {
-        let ret_ch = channel();
-        self.snd.send(HkMsg::EventLoop(ret_ch.0)).unwrap();
-        ret_ch.1.recv().unwrap()
+    fn event_loop(&self) -> ExitReason {
+        self.start_event_loop().recv().unwrap()
     }
 
     fn interrupt_handle(&self) -> InterruptHandle {
@@ -187,9 +2268,15 @@ impl<T: 'static + Send> HotkeyManagerImpl<T> for HotkeyManager<T> {
 
 impl<T> Drop for HotkeyManager<T> {
     fn drop(&mut self) {
+        // If the backend thread has already died, the exit command can't be delivered. Since the
+        // backend is gone, there is nothing left to clean up in that case, so any errors are
+        // ignored instead of panicking (possibly while already unwinding)
         let ret_ch = channel();
-        self.snd.send(HkMsg::Exit(ret_ch.0)).unwrap();
-        ret_ch.1.recv().unwrap();
-        self.backend_handle.take().unwrap().join().unwrap();
+        if self.snd.send(HkMsg::Exit(ret_ch.0)).is_ok() {
+            let _ = ret_ch.1.recv();
+        }
+        if let Some(backend_handle) = self.backend_handle.take() {
+            let _ = backend_handle.join();
+        }
     }
 }
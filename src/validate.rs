@@ -0,0 +1,362 @@
+use std::{collections::HashMap, fmt};
+
+use winapi::um::winuser::{RegisterHotKey, UnregisterHotKey};
+
+use crate::{
+    combo::{ComboCode, HotkeyCombo},
+    error::HkError,
+    keys::{ModKey, VKey},
+    HotkeyId, HotkeyManagerImpl,
+};
+
+/// Hotkey id that is used for probing. Application defined hotkey ids are limited to the range
+/// `0x0000` to `0xBFFF`, so the highest id is used to avoid colliding with regular hotkeys.
+///
+const PROBE_ID: i32 = 0xBFFF;
+
+/// Definition of a hotkey using key names, as they would be provided by a user or a config file.
+/// The names are parsed using `VKey::from_keyname` and `ModKey::from_keyname`.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct HotkeyDef {
+    /// Name of the main key
+    pub key: String,
+    /// Names of the modifier keys
+    pub modifiers: Vec<String>,
+    /// Names of the extra keys
+    pub extra_keys: Vec<String>,
+    /// Name of the action that the hotkey triggers. Multiple definitions with the same action
+    /// are alternate bindings of the action, see `HotkeyManagerImpl::register_actions`
+    pub action: Option<String>,
+    /// The line in the config file that the definition was read from, if any. This is only used
+    /// to point at the definition in reports
+    pub line: Option<usize>,
+}
+
+impl HotkeyDef {
+    /// Parse the key names of the definition into the main key, modifiers and extra keys.
+    ///
+    pub fn parse(&self) -> Result<(VKey, Vec<ModKey>, Vec<VKey>), HkError> {
+        if self.key.trim().is_empty() {
+            return Err(HkError::MissingMainKey(self.modifiers.join(" + ")));
+        }
+        let key = VKey::from_keyname(&self.key)?;
+        let modifiers = self
+            .modifiers
+            .iter()
+            .map(|m| ModKey::from_keyname(m))
+            .collect::<Result<_, _>>()?;
+        let extra_keys = self
+            .extra_keys
+            .iter()
+            .map(|k| VKey::from_keyname(k))
+            .collect::<Result<_, _>>()?;
+
+        Ok((key, modifiers, extra_keys))
+    }
+}
+
+/// A problem with one of the validated hotkey definitions.
+///
+#[derive(Debug)]
+pub struct ValidationIssue {
+    /// Index of the definition with the problem
+    pub index: usize,
+    /// The kind of problem
+    pub kind: ValidationIssueKind,
+}
+
+/// The kinds of problems that are detected by `validate`.
+///
+#[derive(Debug)]
+pub enum ValidationIssueKind {
+    /// One of the key names couldn't be parsed
+    Invalid(HkError),
+    /// The key combination was already defined by the definition at the given index
+    Duplicate { first: usize },
+    /// The definition can never be triggered, because the definition at the given index has the
+    /// same main key and modifiers, but only requires a part of the extra keys of this one. It
+    /// always matches first, whenever this one would match
+    Shadowed { by: usize },
+    /// The key combination can't be registered with windows, it is most likely already in use by
+    /// a different application
+    Unavailable,
+}
+
+/// Check a list of hotkey definitions for problems without leaving any of them registered. This
+/// checks if all key names can be parsed, if any key combinations are defined more than once or
+/// are shadowed by an earlier definition and if the key combinations can currently be registered
+/// with windows.
+///
+/// This allows showing all problems of a hotkey configuration before actually applying it.
+///
+/// # Note
+/// Combinations that are already registered from the current thread, for example by an active
+/// `singlethreaded::HotkeyManager`, are reported as unavailable.
+///
+pub fn validate(defs: &[HotkeyDef]) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let mut seen: HashMap<ComboCode, (usize, Vec<VKey>)> = HashMap::new();
+
+    for (index, def) in defs.iter().enumerate() {
+        let (key, modifiers, extra_keys) = match def.parse() {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                issues.push(ValidationIssue {
+                    index,
+                    kind: ValidationIssueKind::Invalid(err),
+                });
+                continue;
+            }
+        };
+
+        // NoRepeat doesn't change the key combination, so it is ignored for the duplicate check
+        let combo = HotkeyCombo::new(key, &modifiers).code();
+        if let Some((first, first_extras)) = seen.get(&combo) {
+            let kind = match is_shadowed(first_extras, &extra_keys) {
+                true => ValidationIssueKind::Shadowed { by: *first },
+                false => ValidationIssueKind::Duplicate { first: *first },
+            };
+            issues.push(ValidationIssue { index, kind });
+            continue;
+        }
+        seen.insert(combo, (index, extra_keys));
+
+        if probe(key, &modifiers).is_err() {
+            issues.push(ValidationIssue {
+                index,
+                kind: ValidationIssueKind::Unavailable,
+            });
+        }
+    }
+
+    issues
+}
+
+/// The result of registering all hotkey definitions of a config with
+/// `HotkeyManagerImpl::register_defs`. Contains one entry for every definition, in the same order
+/// as the definitions.
+///
+#[derive(Debug, Default)]
+pub struct LoadReport {
+    pub entries: Vec<LoadEntry>,
+}
+
+/// The result of registering a single hotkey definition.
+///
+#[derive(Debug)]
+pub struct LoadEntry {
+    /// Index of the definition
+    pub index: usize,
+    /// The line of the definition in the config file, copied from `HotkeyDef::line`
+    pub line: Option<usize>,
+    /// What happened with the definition
+    pub outcome: LoadOutcome,
+}
+
+/// The outcome of registering a single hotkey definition.
+///
+#[derive(Debug)]
+pub enum LoadOutcome {
+    /// The hotkey was registered under the given id
+    Registered(HotkeyId),
+    /// One of the key names couldn't be parsed
+    Invalid(HkError),
+    /// The key combination was already defined by the definition at the given index, so it was
+    /// not registered again
+    Duplicate { first: usize },
+    /// The definition was not registered, because it would never be triggered. See
+    /// `ValidationIssueKind::Shadowed`
+    Shadowed { by: usize },
+    /// The key combination was rejected by windows, it is most likely already in use by a
+    /// different application
+    Rejected(HkError),
+}
+
+impl LoadReport {
+    /// True if all definitions were registered
+    ///
+    pub fn is_ok(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    /// The index and hotkey id of all registered definitions
+    ///
+    pub fn registered(&self) -> impl Iterator<Item = (usize, HotkeyId)> + '_ {
+        self.entries.iter().filter_map(|entry| match entry.outcome {
+            LoadOutcome::Registered(id) => Some((entry.index, id)),
+            _ => None,
+        })
+    }
+
+    /// All definitions that couldn't be registered
+    ///
+    pub fn failures(&self) -> impl Iterator<Item = &LoadEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| !matches!(entry.outcome, LoadOutcome::Registered(_)))
+    }
+}
+
+impl fmt::Display for LoadEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: ", line)?,
+            None => write!(f, "definition {}: ", self.index)?,
+        }
+
+        match &self.outcome {
+            LoadOutcome::Registered(id) => write!(f, "registered as {:?}", id),
+            LoadOutcome::Invalid(err) => write!(f, "invalid: {}", err),
+            LoadOutcome::Duplicate { first } => write!(f, "duplicate of definition {}", first),
+            LoadOutcome::Shadowed { by } => write!(f, "shadowed by definition {}", by),
+            LoadOutcome::Rejected(err) => write!(f, "rejected: {}", err),
+        }
+    }
+}
+
+impl fmt::Display for LoadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            writeln!(f, "{}", entry)?;
+        }
+        Ok(())
+    }
+}
+
+/// Register all hotkey definitions with the manager, without stopping at the first problem.
+/// See `HotkeyManagerImpl::register_defs`.
+///
+pub(crate) fn register_defs<T, M, F>(
+    hkm: &mut M,
+    defs: &[HotkeyDef],
+    mut callback_for: impl FnMut(usize, &HotkeyDef) -> F,
+) -> LoadReport
+where
+    M: HotkeyManagerImpl<T>,
+    F: Fn() -> T + Send + 'static,
+{
+    register_each(hkm, defs, |index, def| Ok(callback_for(index, def)))
+}
+
+/// Register the hotkey definitions as bindings of named actions. See
+/// `HotkeyManagerImpl::register_actions`.
+///
+pub(crate) fn register_actions<T, M, F>(
+    hkm: &mut M,
+    defs: &[HotkeyDef],
+    mut callback_for: impl FnMut(&str, usize) -> F,
+) -> LoadReport
+where
+    M: HotkeyManagerImpl<T>,
+    F: Fn() -> T + Send + 'static,
+{
+    // The position of every definition among the bindings of its action, 0 being the primary
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    let alternates: Vec<_> = defs
+        .iter()
+        .map(|def| {
+            def.action.as_deref().map(|action| {
+                let count = counts.entry(action).or_default();
+                *count += 1;
+                *count - 1
+            })
+        })
+        .collect();
+
+    register_each(hkm, defs, |index, def| {
+        match (def.action.as_deref(), alternates[index]) {
+            (Some(action), Some(alternate)) => Ok(callback_for(action, alternate)),
+            _ => Err(HkError::NoAction),
+        }
+    })
+}
+
+/// Register every definition that can be parsed and is not a duplicate. The callback is only
+/// created for definitions that are registered, an error from `callback_for` marks the
+/// definition as invalid.
+///
+fn register_each<T, M, F>(
+    hkm: &mut M,
+    defs: &[HotkeyDef],
+    mut callback_for: impl FnMut(usize, &HotkeyDef) -> Result<F, HkError>,
+) -> LoadReport
+where
+    M: HotkeyManagerImpl<T>,
+    F: Fn() -> T + Send + 'static,
+{
+    let mut seen: HashMap<ComboCode, (usize, Vec<VKey>)> = HashMap::new();
+
+    let entries = defs
+        .iter()
+        .enumerate()
+        .map(|(index, def)| {
+            let outcome = match def.parse() {
+                Err(err) => LoadOutcome::Invalid(err),
+                Ok((key, modifiers, extra_keys)) => {
+                    let combo = HotkeyCombo::new(key, &modifiers).code();
+                    match seen.get(&combo) {
+                        Some((first, first_extras)) if is_shadowed(first_extras, &extra_keys) => {
+                            LoadOutcome::Shadowed { by: *first }
+                        }
+                        Some((first, _)) => LoadOutcome::Duplicate { first: *first },
+                        None => match callback_for(index, def) {
+                            Err(err) => LoadOutcome::Invalid(err),
+                            Ok(callback) => {
+                                match hkm.register_extrakeys(key, &modifiers, &extra_keys, callback)
+                                {
+                                    Ok(id) => {
+                                        seen.insert(combo, (index, extra_keys));
+                                        LoadOutcome::Registered(id)
+                                    }
+                                    Err(err) => LoadOutcome::Rejected(err),
+                                }
+                            }
+                        },
+                    }
+                }
+            };
+
+            LoadEntry {
+                index,
+                line: def.line,
+                outcome,
+            }
+        })
+        .collect();
+
+    LoadReport { entries }
+}
+
+/// Check if a definition with the extra keys can never be triggered, because an earlier
+/// definition with the same main key and modifiers requires only a part of its extra keys
+///
+fn is_shadowed(first_extras: &[VKey], extra_keys: &[VKey]) -> bool {
+    let subset = |a: &[VKey], b: &[VKey]| a.iter().all(|key| b.contains(key));
+    subset(first_extras, extra_keys) && !subset(extra_keys, first_extras)
+}
+
+/// Check if a key combination can currently be registered, by registering it and immediately
+/// unregistering it again.
+///
+/// # Windows API Functions used
+/// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey>
+/// - <https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-unregisterhotkey>
+///
+pub fn probe(key: VKey, key_modifiers: &[ModKey]) -> Result<(), HkError> {
+    let reg_ok = unsafe {
+        RegisterHotKey(
+            std::ptr::null_mut(),
+            PROBE_ID,
+            ModKey::combine(key_modifiers),
+            key.to_vk_code() as u32,
+        )
+    };
+
+    if reg_ok == 0 {
+        return Err(HkError::last_registration_error());
+    }
+
+    unsafe { UnregisterHotKey(std::ptr::null_mut(), PROBE_ID) };
+    Ok(())
+}
@@ -0,0 +1,185 @@
+use std::cell::RefCell;
+
+use winapi::shared::windef::{HWND, RECT};
+use winapi::shared::winerror::S_OK;
+use winapi::um::handleapi::CloseHandle;
+use winapi::um::processthreadsapi::OpenProcess;
+use winapi::um::shellapi::{
+    SHQueryUserNotificationState, QUNS_PRESENTATION_MODE, QUNS_RUNNING_D3D_FULL_SCREEN,
+};
+use winapi::um::winbase::QueryFullProcessImageNameW;
+use winapi::um::winnt::PROCESS_QUERY_LIMITED_INFORMATION;
+use winapi::um::winuser::{
+    GetClassNameW, GetDesktopWindow, GetForegroundWindow, GetMonitorInfoW, GetShellWindow,
+    GetWindowRect, GetWindowTextW, GetWindowThreadProcessId, MonitorFromWindow, MONITORINFO,
+    MONITOR_DEFAULTTONULL,
+};
+
+thread_local! {
+    /// The foreground window at the time of the latest hotkey trigger on this thread
+    static TRIGGER_WINDOW: RefCell<Option<ForegroundWindow>> = const { RefCell::new(None) };
+}
+
+/// The window that was in the foreground when a hotkey was triggered. This is captured before the
+/// callback is executed, so it still refers to the right window if the focus changes in the
+/// meantime.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ForegroundWindow {
+    /// The window handle as an integer, so the event can be sent to other threads. See `hwnd`
+    pub handle: usize,
+    /// The title of the window
+    pub title: String,
+    /// The name of the window class
+    pub class: String,
+    /// The id of the process that created the window
+    pub process_id: u32,
+}
+
+impl ForegroundWindow {
+    /// Get the information about the current foreground window. Returns `None` if there is no
+    /// foreground window, for example while the focus changes.
+    ///
+    /// # Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getforegroundwindow>
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getwindowtextw>
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getclassnamew>
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getwindowthreadprocessid>
+    ///
+    pub fn capture() -> Option<Self> {
+        let hwnd = unsafe { GetForegroundWindow() };
+        if hwnd.is_null() {
+            return None;
+        }
+
+        let mut title = [0u16; 512];
+        let title_len = unsafe { GetWindowTextW(hwnd, title.as_mut_ptr(), title.len() as i32) };
+        let mut class = [0u16; 256];
+        let class_len = unsafe { GetClassNameW(hwnd, class.as_mut_ptr(), class.len() as i32) };
+        let mut process_id = 0;
+        unsafe { GetWindowThreadProcessId(hwnd, &mut process_id) };
+
+        Some(Self {
+            handle: hwnd as usize,
+            title: String::from_utf16_lossy(&title[..title_len.max(0) as usize]),
+            class: String::from_utf16_lossy(&class[..class_len.max(0) as usize]),
+            process_id,
+        })
+    }
+
+    /// The window handle. The window might have been closed since it was captured
+    ///
+    pub fn hwnd(&self) -> HWND {
+        self.handle as HWND
+    }
+
+    /// Get the full path of the executable of the process that created the window. Returns `None`
+    /// if the process has exited or can't be queried, for example because it runs elevated while
+    /// this process doesn't.
+    ///
+    /// # Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-openprocess>
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-queryfullprocessimagenamew>
+    ///
+    pub fn process_path(&self) -> Option<String> {
+        let process = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, self.process_id) };
+        if process.is_null() {
+            return None;
+        }
+
+        let mut path = [0u16; 1024];
+        let mut path_len = path.len() as u32;
+        let ok =
+            unsafe { QueryFullProcessImageNameW(process, 0, path.as_mut_ptr(), &mut path_len) };
+        unsafe { CloseHandle(process) };
+
+        match ok {
+            0 => None,
+            _ => Some(String::from_utf16_lossy(&path[..path_len as usize])),
+        }
+    }
+
+    /// Check if the window covers its whole monitor, like borderless fullscreen games and videos.
+    /// The desktop itself is not considered fullscreen.
+    ///
+    /// # Windows API Functions used
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getwindowrect>
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-monitorfromwindow>
+    /// - <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getmonitorinfow>
+    ///
+    pub fn is_fullscreen(&self) -> bool {
+        let hwnd = self.hwnd();
+        let is_desktop = hwnd == unsafe { GetDesktopWindow() }
+            || hwnd == unsafe { GetShellWindow() }
+            || self.class == "Progman"
+            || self.class == "WorkerW";
+        if is_desktop {
+            return false;
+        }
+
+        let mut rect: RECT = unsafe { std::mem::zeroed() };
+        if unsafe { GetWindowRect(hwnd, &mut rect) } == 0 {
+            return false;
+        }
+
+        let monitor = unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTONULL) };
+        if monitor.is_null() {
+            return false;
+        }
+        let mut info: MONITORINFO = unsafe { std::mem::zeroed() };
+        info.cbSize = std::mem::size_of::<MONITORINFO>() as u32;
+        if unsafe { GetMonitorInfoW(monitor, &mut info) } == 0 {
+            return false;
+        }
+
+        let screen = info.rcMonitor;
+        rect.left <= screen.left
+            && rect.top <= screen.top
+            && rect.right >= screen.right
+            && rect.bottom >= screen.bottom
+    }
+
+    /// Get the file name of the executable of the process that created the window, for example
+    /// `firefox.exe`. See `process_path`.
+    ///
+    pub fn process_name(&self) -> Option<String> {
+        let path = self.process_path()?;
+        let name = path.rsplit(['\\', '/']).next().unwrap_or(&path);
+        Some(name.to_string())
+    }
+}
+
+/// Check if a fullscreen application is in the foreground. This detects exclusive fullscreen
+/// Direct3D applications and presentations, as reported by the shell, as well as borderless
+/// windows that cover their whole monitor. See `HotkeyManager::enable_game_mode`.
+///
+/// # Windows API Functions used
+/// - <https://learn.microsoft.com/en-us/windows/win32/api/shellapi/nf-shellapi-shqueryusernotificationstate>
+///
+pub fn is_fullscreen_app_active() -> bool {
+    let mut state = 0;
+    let ok = unsafe { SHQueryUserNotificationState(&mut state) };
+    if ok == S_OK && (state == QUNS_RUNNING_D3D_FULL_SCREEN || state == QUNS_PRESENTATION_MODE) {
+        return true;
+    }
+
+    ForegroundWindow::capture().is_some_and(|window| window.is_fullscreen())
+}
+
+/// Get the window that was in the foreground when the hotkey of the currently running callback
+/// was triggered. This is the same as `HotkeyEvent::foreground`, for callbacks that don't have
+/// access to the event.
+///
+/// Only works on the thread of the event loop, so it returns `None` in callbacks that are executed
+/// by a dispatcher on a different thread.
+///
+pub fn trigger_window() -> Option<ForegroundWindow> {
+    TRIGGER_WINDOW.with(|window| window.borrow().clone())
+}
+
+/// Capture the foreground window for a new trigger on this thread
+///
+pub(crate) fn capture_trigger_window() {
+    let window = ForegroundWindow::capture();
+    TRIGGER_WINDOW.with(|trigger| *trigger.borrow_mut() = window);
+}
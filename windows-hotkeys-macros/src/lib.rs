@@ -0,0 +1,226 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, LitStr};
+
+/// Parse a key combination string like `"ctrl + alt + k"` at compile time and expand it to a
+/// `windows_hotkeys::combo::HotkeyCombo` that is built from the `ModKey` and `VKey` variants.
+/// The string uses the same format as `HotkeyCombo::parse`, but an invalid key name or a missing
+/// main key fails the build instead of returning an error at runtime.
+///
+/// # Example
+/// ```ignore
+/// use windows_hotkeys::{hotkey, HotkeyManager, HotkeyManagerImpl};
+///
+/// let mut hkm = HotkeyManager::new();
+/// hkm.register_combo(&hotkey!("ctrl + alt + k"), || println!("CTRL + ALT + K"))
+///     .unwrap();
+/// ```
+///
+#[proc_macro]
+pub fn hotkey(input: TokenStream) -> TokenStream {
+    let combo = parse_macro_input!(input as LitStr);
+    match parse_combo(&combo.value()) {
+        Ok(tokens) => tokens.into(),
+        Err(reason) => syn::Error::new(combo.span(), reason)
+            .to_compile_error()
+            .into(),
+    }
+}
+
+/// Parse the key combination the same way as `HotkeyCombo::parse` and build the expression for
+/// it. Returns the reason on failure.
+///
+fn parse_combo(combo: &str) -> Result<TokenStream2, String> {
+    let mut names = combo.split('+').map(str::trim);
+
+    let mut modifiers = Vec::new();
+    let key = loop {
+        let Some(name) = names.next() else {
+            return Err(format!(
+                "The key combination `{}` has no main key, only modifiers",
+                combo
+            ));
+        };
+        match modkey(name) {
+            Some(modifier) => modifiers.push(modifier),
+            None => break vkey(name)?,
+        }
+    };
+    let extra_keys = names.map(vkey).collect::<Result<Vec<_>, _>>()?;
+
+    Ok(quote! {
+        ::windows_hotkeys::combo::HotkeyCombo {
+            key: #key,
+            modifiers: ::std::vec![#(#modifiers),*],
+            extra_keys: ::std::vec![#(#extra_keys),*],
+        }
+    })
+}
+
+/// The `ModKey` for a name as accepted by `ModKey::from_keyname`
+///
+fn modkey(name: &str) -> Option<TokenStream2> {
+    let variant = match name.to_ascii_uppercase().as_ref() {
+        "ALT" => quote!(Alt),
+        "CTRL" | "CONTROL" => quote!(Ctrl),
+        "SHIFT" => quote!(Shift),
+        "WIN" | "WINDOWS" | "SUPER" => quote!(Win),
+        "NOREPEAT" | "NO_REPEAT" => quote!(NoRepeat),
+        _ => return None,
+    };
+    Some(quote!(::windows_hotkeys::keys::ModKey::#variant))
+}
+
+/// The `VKey` for a name as accepted by `VKey::from_keyname`
+///
+fn vkey(name: &str) -> Result<TokenStream2, String> {
+    let val = name.to_ascii_uppercase();
+    let invalid = || format!("invalid key name `{}`", name);
+
+    // Single letters and digits have their own variants
+    if let [c] = val.as_bytes() {
+        let variant = match c {
+            b'A'..=b'Z' => (*c as char).to_string(),
+            b'0'..=b'9' => format!("Vk{}", *c as char),
+            _ => return Err(invalid()),
+        };
+        let variant = syn::Ident::new(&variant, proc_macro2::Span::call_site());
+        return Ok(quote!(::windows_hotkeys::keys::VKey::#variant));
+    }
+
+    if let Some(hex) = val.strip_prefix("0X") {
+        let code = i32::from_str_radix(hex, 16).map_err(|_| invalid())?;
+        return Ok(quote!(::windows_hotkeys::keys::VKey::CustomKeyCode(#code)));
+    }
+
+    let val = val.trim_start_matches("VK_");
+    let &(_, variant) = VKEY_NAMES
+        .iter()
+        .find(|(keyname, _)| *keyname == val)
+        .ok_or_else(invalid)?;
+    let variant = syn::Ident::new(variant, proc_macro2::Span::call_site());
+    Ok(quote!(::windows_hotkeys::keys::VKey::#variant))
+}
+
+/// The `VK_*` names without the prefix and their `VKey` variants. This needs to be kept in sync
+/// with `VKey::from_keyname`
+///
+const VKEY_NAMES: &[(&str, &str)] = &[
+    ("BACK", "Back"),
+    ("TAB", "Tab"),
+    ("CLEAR", "Clear"),
+    ("RETURN", "Return"),
+    ("SHIFT", "Shift"),
+    ("CONTROL", "Control"),
+    ("MENU", "Menu"),
+    ("PAUSE", "Pause"),
+    ("CAPITAL", "Capital"),
+    ("ESCAPE", "Escape"),
+    ("SPACE", "Space"),
+    ("PRIOR", "Prior"),
+    ("NEXT", "Next"),
+    ("END", "End"),
+    ("HOME", "Home"),
+    ("LEFT", "Left"),
+    ("UP", "Up"),
+    ("RIGHT", "Right"),
+    ("DOWN", "Down"),
+    ("SELECT", "Select"),
+    ("PRINT", "Print"),
+    ("EXECUTE", "Execute"),
+    ("SNAPSHOT", "Snapshot"),
+    ("INSERT", "Insert"),
+    ("DELETE", "Delete"),
+    ("HELP", "Help"),
+    ("LWIN", "LWin"),
+    ("RWIN", "RWin"),
+    ("APPS", "Apps"),
+    ("SLEEP", "Sleep"),
+    ("NUMPAD0", "Numpad0"),
+    ("NUMPAD1", "Numpad1"),
+    ("NUMPAD2", "Numpad2"),
+    ("NUMPAD3", "Numpad3"),
+    ("NUMPAD4", "Numpad4"),
+    ("NUMPAD5", "Numpad5"),
+    ("NUMPAD6", "Numpad6"),
+    ("NUMPAD7", "Numpad7"),
+    ("NUMPAD8", "Numpad8"),
+    ("NUMPAD9", "Numpad9"),
+    ("MULTIPLY", "Multiply"),
+    ("ADD", "Add"),
+    ("SEPARATOR", "Separator"),
+    ("SUBTRACT", "Subtract"),
+    ("DECIMAL", "Decimal"),
+    ("DIVIDE", "Divide"),
+    ("F1", "F1"),
+    ("F2", "F2"),
+    ("F3", "F3"),
+    ("F4", "F4"),
+    ("F5", "F5"),
+    ("F6", "F6"),
+    ("F7", "F7"),
+    ("F8", "F8"),
+    ("F9", "F9"),
+    ("F10", "F10"),
+    ("F11", "F11"),
+    ("F12", "F12"),
+    ("F13", "F13"),
+    ("F14", "F14"),
+    ("F15", "F15"),
+    ("F16", "F16"),
+    ("F17", "F17"),
+    ("F18", "F18"),
+    ("F19", "F19"),
+    ("F20", "F20"),
+    ("F21", "F21"),
+    ("F22", "F22"),
+    ("F23", "F23"),
+    ("F24", "F24"),
+    ("NUMLOCK", "Numlock"),
+    ("SCROLL", "Scroll"),
+    ("LSHIFT", "LShift"),
+    ("RSHIFT", "RShift"),
+    ("LCONTROL", "LControl"),
+    ("RCONTROL", "RControl"),
+    ("LMENU", "LMenu"),
+    ("RMENU", "RMenu"),
+    ("BROWSER_BACK", "BrowserBack"),
+    ("BROWSER_FORWARD", "BrowserForward"),
+    ("BROWSER_REFRESH", "BrowserRefresh"),
+    ("BROWSER_STOP", "BrowserStop"),
+    ("BROWSER_SEARCH", "BrowserSearch"),
+    ("BROWSER_FAVORITES", "BrowserFavorites"),
+    ("BROWSER_HOME", "BrowserHome"),
+    ("VOLUME_MUTE", "VolumeMute"),
+    ("VOLUME_DOWN", "VolumeDown"),
+    ("VOLUME_UP", "VolumeUp"),
+    ("MEDIA_NEXT_TRACK", "MediaNextTrack"),
+    ("MEDIA_PREV_TRACK", "MediaPrevTrack"),
+    ("MEDIA_STOP", "MediaStop"),
+    ("MEDIA_PLAY_PAUSE", "MediaPlayPause"),
+    ("LAUNCH_MAIL", "LaunchMail"),
+    ("LAUNCH_MEDIA_SELECT", "LaunchMediaSelect"),
+    ("LAUNCH_APP1", "LaunchApp1"),
+    ("LAUNCH_APP2", "LaunchApp2"),
+    ("OEM_1", "Oem1"),
+    ("OEM_PLUS", "OemPlus"),
+    ("OEM_COMMA", "OemComma"),
+    ("OEM_MINUS", "OemMinus"),
+    ("OEM_PERIOD", "OemPeriod"),
+    ("OEM_2", "Oem2"),
+    ("OEM_3", "Oem3"),
+    ("OEM_4", "Oem4"),
+    ("OEM_5", "Oem5"),
+    ("OEM_6", "Oem6"),
+    ("OEM_7", "Oem7"),
+    ("OEM_8", "Oem8"),
+    ("OEM_102", "Oem102"),
+    ("ATTN", "Attn"),
+    ("CRSEL", "Crsel"),
+    ("EXSEL", "Exsel"),
+    ("PLAY", "Play"),
+    ("ZOOM", "Zoom"),
+    ("PA1", "Pa1"),
+    ("OEM_CLEAR", "OemClear"),
+];